@@ -1,10 +1,22 @@
-mod search;
-mod task;
+pub(crate) mod modpack;
+pub(crate) mod search;
+pub(crate) mod task;
+pub(crate) mod upload;
+pub(crate) mod vanilla;
 
 use actix_web::web;
 
 pub fn init(cfg: &mut web::ServiceConfig) {
     cfg.service(search::search_resources);
     cfg.service(task::create_archive_task);
+    cfg.service(task::list_archive_tasks);
     cfg.service(task::get_archive_task);
+    cfg.service(task::stream_archive_task_events);
+    cfg.service(upload::upload_mod_archives);
+    cfg.service(vanilla::import_vanilla);
+    cfg.service(modpack::import_modpack);
+    cfg.service(modpack::list_imported_modpacks);
+    cfg.service(modpack::get_modpack_archive_task);
+    cfg.service(modpack::get_modpack);
+    cfg.app_data(web::PayloadConfig::new(modpack::MAX_MODPACK_SIZE));
 }
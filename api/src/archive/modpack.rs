@@ -0,0 +1,347 @@
+use std::collections::HashSet;
+
+use actix_web::{get, post, web};
+use entity::minecraft::{minecraft_mod, modpack};
+use futures_util::{stream, StreamExt};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use service::{
+    archive::{
+        modpack::{
+            compute_coverage, find_modpack, find_modpack_mods, find_provider_mod_id,
+            finish_modpack_member, get_modpack_task, insert_modpack_task, link_modpack_mod,
+            list_modpacks, parse_modpack, remove_modpack_task, save_modpack, start_modpack_task,
+            unlink_modpack_mods, ModpackCoverage, ModpackManifest, ModpackTask,
+        },
+        resource::ArchiveProvider,
+        task::{insert_task, subscribe_task, ArchiveTask},
+    },
+    sea_orm::DatabaseConnection,
+};
+use tokio::sync::broadcast::error::RecvError;
+use utoipa::{IntoParams, ToSchema};
+
+use super::task::run_archive_task;
+use crate::{
+    auth::AuthenticatedUser,
+    error::{ApiError, ApiResult},
+    translation::validate_language,
+    AppState,
+};
+
+/// Maximum size of an uploaded modpack file.
+pub(crate) const MAX_MODPACK_SIZE: usize = 64 * 1024 * 1024;
+/// Archive tasks of a modpack running at the same time.
+const CONCURRENT_MODPACK_TASKS: usize = 3;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModpackImportResult {
+    #[schema(value_type = Modpack)]
+    pub modpack: modpack::Model,
+    /// ID of the modpack task, not set if every mod was already archived.
+    pub task_id: Option<String>,
+    /// IDs of the mods that were already archived.
+    pub archived_mod_ids: Vec<i32>,
+    /// Mods that are not hosted by the provider, by their file in the manifest.
+    pub unresolved: Vec<String>,
+    /// Identifiers of the mods that cannot be archived from the provider yet.
+    pub unsupported: Vec<String>,
+}
+
+/// Imports a modpack from a Modrinth `.mrpack`, a CurseForge modpack zip, or the
+/// bare `modrinth.index.json`/`manifest.json` of either, given as the request body.
+/// Archive tasks are created for the mods that are not archived yet, and tracked
+/// together by a modpack task. Importing a modpack with the same name again
+/// updates it.
+///
+/// ### Response
+/// * ModpackImportResult
+///
+/// ### Errors
+/// * 401: Authentication required
+/// * 409: The modpack is still being archived
+/// * 422: Invalid modpack file (details = parse error)
+/// * 429: Too many tasks created, retry after `Retry-After` seconds
+#[utoipa::path(
+    context_path = "/archives",
+    tag = "archives",
+    request_body(content = String, description = "Modpack file or manifest"),
+    responses(
+        (status = 200, body = ModpackImportResult),
+        (status = 401, body = ApiError),
+        (status = 409, body = ApiError),
+        (status = 422, body = ApiError),
+        (status = 429, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[post("/modpacks")]
+pub async fn import_modpack(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    body: web::Bytes,
+) -> ApiResult<web::Json<ModpackImportResult>> {
+    let manifest = parse_modpack(&body).map_err(|err| {
+        ApiError::validation("Invalid modpack file").with_details(err.to_string())
+    })?;
+    let modpack = save_modpack(&state.db, &manifest).await?;
+
+    // Reserved before the mods are unlinked, the archive tasks of a running import
+    // still link the mods they archive.
+    let task = ModpackTask::new(modpack.id, Some(user.user.username.clone()));
+    let task_id = task.id.clone();
+    if !insert_modpack_task(task) {
+        return Err(
+            ApiError::conflict("The modpack is still being archived").with_details(&task_id)
+        );
+    }
+
+    let mut result = ModpackImportResult {
+        modpack,
+        task_id: None,
+        archived_mod_ids: Vec::new(),
+        unresolved: Vec::new(),
+        unsupported: Vec::new(),
+    };
+    let identifiers = match link_archived_mods(&state.db, &manifest, &mut result).await {
+        Ok(identifiers) if !identifiers.is_empty() => identifiers,
+        Ok(_) => {
+            remove_modpack_task(&task_id);
+            return Ok(web::Json(result));
+        }
+        Err(err) => {
+            remove_modpack_task(&task_id);
+            return Err(err);
+        }
+    };
+
+    let members: Vec<_> = identifiers
+        .into_iter()
+        .map(|identifier| {
+            ArchiveTask::new(
+                manifest.provider.clone(),
+                identifier,
+                Some(user.user.username.clone()),
+            )
+        })
+        .collect();
+    start_modpack_task(
+        &task_id,
+        members.iter().map(|member| member.id.clone()).collect(),
+    );
+    result.task_id = Some(task_id.clone());
+
+    let modpack_id = result.modpack.id;
+    tokio::spawn(async move {
+        stream::iter(members)
+            .for_each_concurrent(CONCURRENT_MODPACK_TASKS, |member| {
+                let state = state.clone();
+                let task_id = task_id.clone();
+                async move {
+                    let member_id = member.id.clone();
+                    let provider = member.provider.clone();
+                    let identifier = member.identifier.clone();
+                    let mc_mod = if insert_task(member) {
+                        run_archive_task(
+                            state.db.clone(),
+                            member_id,
+                            provider,
+                            identifier,
+                            state.config.download_options(),
+                        )
+                        .await
+                    } else {
+                        // Someone else already started archiving the mod.
+                        wait_for_task(&member_id).await
+                    };
+
+                    let linked = match mc_mod {
+                        Some(mc_mod) => link_member(&state.db, modpack_id, mc_mod).await,
+                        None => false,
+                    };
+                    finish_modpack_member(&task_id, linked);
+                }
+            })
+            .await;
+    });
+
+    Ok(web::Json(result))
+}
+
+/// Replaces the mods linked to the modpack with those of the manifest that are archived,
+/// returns the identifiers of the mods to archive.
+async fn link_archived_mods(
+    db: &DatabaseConnection,
+    manifest: &ModpackManifest,
+    result: &mut ModpackImportResult,
+) -> ApiResult<Vec<String>> {
+    unlink_modpack_mods(db, result.modpack.id).await?;
+
+    let mut seen = HashSet::new();
+    let mut identifiers = Vec::new();
+    for entry in &manifest.mods {
+        let Some(identifier) = entry.identifier.clone() else {
+            result.unresolved.push(entry.file.clone());
+            continue;
+        };
+        if !seen.insert(identifier.clone()) {
+            continue;
+        }
+
+        match find_provider_mod_id(db, &manifest.provider, &identifier).await? {
+            Some(mod_id) => {
+                link_modpack_mod(db, result.modpack.id, mod_id).await?;
+                result.archived_mod_ids.push(mod_id);
+            }
+            None if manifest.provider == ArchiveProvider::CurseForge => {
+                result.unsupported.push(identifier);
+            }
+            None => identifiers.push(identifier),
+        }
+    }
+
+    Ok(identifiers)
+}
+
+async fn link_member(
+    db: &DatabaseConnection,
+    modpack_id: i32,
+    mc_mod: minecraft_mod::Model,
+) -> bool {
+    match link_modpack_mod(db, modpack_id, mc_mod.id).await {
+        Ok(()) => true,
+        Err(err) => {
+            warn!(
+                "Link mod {} to modpack {} failed: {}",
+                mc_mod.id, modpack_id, err
+            );
+            false
+        }
+    }
+}
+
+/// Waits until the running archive task finishes, returns the archived mod unless it failed.
+async fn wait_for_task(task_id: &str) -> Option<minecraft_mod::Model> {
+    let (mut task, mut receiver) = subscribe_task(task_id)?;
+    while !task.stage.is_finished() {
+        task = match receiver.recv().await {
+            Ok(task) => task,
+            // Only the final state matters here.
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => return None,
+        };
+    }
+
+    task.mc_mod
+}
+
+/// Returns the modpack task with the given ID, its progress covers all archive
+/// tasks of the modpack.
+///
+/// ### Path parameters
+/// * task_id: String
+///
+/// ### Response
+/// * ModpackTask
+///
+/// ### Errors
+/// * 404: Task not found
+#[utoipa::path(
+    context_path = "/archives",
+    tag = "archives",
+    params(("task_id" = String, Path, description = "ID of the modpack task")),
+    responses(
+        (status = 200, body = ModpackTask),
+        (status = 404, body = ApiError),
+    )
+)]
+#[get("/modpacks/tasks/{task_id}")]
+pub async fn get_modpack_archive_task(
+    task_id: web::Path<String>,
+) -> ApiResult<web::Json<ModpackTask>> {
+    match get_modpack_task(&task_id) {
+        Some(task) => Ok(web::Json(task)),
+        None => Err(ApiError::not_found("Task not found")),
+    }
+}
+
+/// Lists the imported modpacks by name.
+///
+/// ### Response
+/// * Vec<Modpack>
+#[utoipa::path(
+    context_path = "/archives",
+    tag = "archives",
+    responses((status = 200, body = Vec<Modpack>))
+)]
+#[get("/modpacks")]
+pub async fn list_imported_modpacks(
+    state: web::Data<AppState>,
+) -> ApiResult<web::Json<Vec<modpack::Model>>> {
+    Ok(web::Json(list_modpacks(&state.db).await?))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ModpackQuery {
+    /// Language to compute the translation coverage for (e.g. `zh_tw`).
+    lang: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModpackInfo {
+    #[schema(value_type = Modpack)]
+    pub modpack: modpack::Model,
+    #[schema(value_type = Vec<MinecraftMod>)]
+    pub mods: Vec<minecraft_mod::Model>,
+    /// Only set if a language is given.
+    pub coverage: Option<ModpackCoverage>,
+}
+
+/// Returns the modpack with its archived mods, and how much of them is
+/// translated if a language is given.
+///
+/// ### Query parameters
+/// * lang: String (optional)
+///
+/// ### Response
+/// * ModpackInfo
+///
+/// ### Errors
+/// * 404: Modpack not found
+/// * 422: Invalid language code
+#[utoipa::path(
+    context_path = "/archives",
+    tag = "archives",
+    params(("modpack_id" = i32, Path, description = "ID of the modpack"), ModpackQuery),
+    responses(
+        (status = 200, body = ModpackInfo),
+        (status = 404, body = ApiError),
+        (status = 422, body = ApiError),
+    )
+)]
+#[get("/modpacks/{modpack_id}")]
+pub async fn get_modpack(
+    state: web::Data<AppState>,
+    modpack_id: web::Path<i32>,
+    query: web::Query<ModpackQuery>,
+) -> ApiResult<web::Json<ModpackInfo>> {
+    let modpack = find_modpack(&state.db, *modpack_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Modpack not found"))?;
+    let mods = find_modpack_mods(&state.db, &modpack).await?;
+
+    let coverage = match &query.lang {
+        Some(language) => {
+            validate_language(language)?;
+            let mod_ids: Vec<i32> = mods.iter().map(|mc_mod| mc_mod.id).collect();
+            Some(compute_coverage(&state.db, &mod_ids, language).await?)
+        }
+        None => None,
+    };
+
+    Ok(web::Json(ModpackInfo {
+        modpack,
+        mods,
+        coverage,
+    }))
+}
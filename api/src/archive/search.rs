@@ -1,32 +1,42 @@
 use actix_web::{get, web};
-use log::warn;
 use serde::Deserialize;
 use service::archive::resource::{self, ArchiveProvider, ArchiveResourceInfo};
+use utoipa::IntoParams;
 
-use crate::AppState;
+use crate::{
+    error::{ApiResult, ResultExt},
+    AppState,
+};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
 pub struct SearchFilter {
     query: Option<String>,
     page: Option<usize>,
+    /// Only Modrinth is searched for now.
+    #[param(inline)]
+    #[allow(dead_code)]
     provider: ArchiveProvider,
 }
 
+#[utoipa::path(
+    context_path = "/archives",
+    tag = "archives",
+    params(SearchFilter),
+    responses(
+        (status = 200, body = Vec<ArchiveResourceInfo>),
+        (status = 400, body = ApiError),
+        (status = 429, body = ApiError),
+        (status = 500, body = ApiError),
+    )
+)]
 #[get("/search")]
 pub async fn search_resources(
     app_state: web::Data<AppState>,
     filter: web::Query<SearchFilter>,
-) -> actix_web::Result<web::Json<Vec<ArchiveResourceInfo>>> {
-    let result =
-        resource::search_modrinth_mods(&app_state.db, filter.query.as_ref(), filter.page).await;
+) -> ApiResult<web::Json<Vec<ArchiveResourceInfo>>> {
+    let mods = resource::search_modrinth_mods(&app_state.db, filter.query.as_ref(), filter.page)
+        .await
+        .or_internal("Failed to search mods from archive source provider")?;
 
-    match result {
-        Ok(mods) => Ok(web::Json(mods)),
-        Err(e) => {
-            warn!("Failed to search mods from archive source provider: {:?}", e);
-            Err(actix_web::error::ErrorInternalServerError(
-                "Failed to search mods from archive source provider.",
-            ))
-        }
-    }
+    Ok(web::Json(mods))
 }
@@ -1,31 +1,36 @@
-use std::thread;
-
-use actix_web::{error, get, post, web};
+use actix_web::{get, post, web, HttpResponse};
+use entity::minecraft::minecraft_mod;
+use futures_util::{stream, StreamExt};
 use log::warn;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use service::{
     archive::{
-        resource::{
-            create_mod_model, create_provider_model, fetch_downloads, validate_resource_identifier,
-            ArchiveProvider,
-        },
+        failure::{clear_archive_failure, record_archive_failure},
+        resource::{validate_resource_identifier, ArchiveProvider},
         task::{
-            download_files, parse_language_files, remove_task, save_text_entries,
-            update_task_progress, ArchiveTask, ArchiveTaskStage, ARCHIVE_TASKS,
+            archive_mod, complete_task, fail_task, get_task, insert_task, list_tasks,
+            subscribe_task, update_task_progress, ArchiveTask, ArchiveTaskFilter, ArchiveTaskStage,
+            DownloadOptions,
         },
     },
+    chrono::{DateTime, Utc},
     sea_orm::DatabaseConnection,
 };
+use tokio::sync::broadcast::error::RecvError;
+use utoipa::{IntoParams, ToSchema};
 
-use crate::AppState;
+use crate::{
+    auth::AuthenticatedUser,
+    error::{ApiError, ApiResult},
+    AppState,
+};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateTaskPayload {
     pub provider: ArchiveProvider,
     pub identifier: String,
 }
 
-#[post("/tasks")]
 /// Creates a new archive task, then executes it in a background thread.
 /// If the task is already running, the task ID will be returned.
 ///
@@ -35,65 +40,129 @@ pub struct CreateTaskPayload {
 ///
 /// ### Response
 /// * String: Task ID
+///
+/// ### Errors
+/// * 401: Authentication required
+/// * 422: Invalid resource identifier
+/// * 429: Too many tasks created, retry after `Retry-After` seconds
+#[utoipa::path(
+    context_path = "/archives",
+    tag = "archives",
+    request_body = CreateTaskPayload,
+    responses(
+        (status = 200, description = "ID of the created or running task", body = String),
+        (status = 401, body = ApiError),
+        (status = 422, body = ApiError),
+        (status = 429, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[post("/tasks")]
 pub async fn create_archive_task(
     state: web::Data<AppState>,
+    user: AuthenticatedUser,
     payload: web::Json<CreateTaskPayload>,
-) -> actix_web::Result<String> {
+) -> ApiResult<String> {
     let identifier_valid = validate_resource_identifier(&payload.provider, &payload.identifier)
         .await
-        .map_or(false, |f| f);
+        .unwrap_or(false);
     if !identifier_valid {
-        return Err(error::ErrorBadRequest("Invalid resource identifier"));
+        return Err(
+            ApiError::validation("Invalid resource identifier").with_details(&payload.identifier)
+        );
     }
 
-    let task_id = format!(
-        "{}-{}",
-        serde_json::to_value(&payload.provider)
-            .unwrap()
-            .as_str()
-            .unwrap(),
-        &payload.identifier
+    let task = ArchiveTask::new(
+        payload.provider.clone(),
+        payload.identifier.clone(),
+        Some(user.user.username),
     );
-    let task = ArchiveTask {
-        stage: ArchiveTaskStage::Preparing,
-        progress: 0.05,
-        mc_mod: None,
-    };
+    let task_id = task.id.clone();
 
-    {
-        let mut tasks = ARCHIVE_TASKS.lock().unwrap();
-        // The task is already running.
-        if tasks.contains_key(&task_id) {
-            return Ok(task_id);
-        }
-
-        tasks.insert(task_id.clone(), task);
+    // The task is already running.
+    if !insert_task(task) {
+        return Ok(task_id);
     }
 
     let task_id_clone = task_id.clone();
     tokio::spawn(async move {
-        let result = start_create_task(
+        run_archive_task(
             state.db.clone(),
-            task_id_clone.clone(),
+            task_id_clone,
             payload.provider.clone(),
             payload.identifier.clone(),
-            state.config.max_simultaneous_downloads,
+            state.config.download_options(),
         )
         .await;
-
-        if let Err(err) = result {
-            let mut tasks = ARCHIVE_TASKS.lock().unwrap();
-            let task = tasks.get_mut(&task_id_clone).unwrap();
-            task.stage = ArchiveTaskStage::Failed;
-            warn!("Execute archive task failed: {:?}", err);
-        }
     });
 
     Ok(task_id)
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct TasksFilter {
+    #[param(inline)]
+    stage: Option<ArchiveTaskStage>,
+    #[param(inline)]
+    provider: Option<ArchiveProvider>,
+    /// Only tasks started at or after this time (RFC 3339).
+    started_after: Option<DateTime<Utc>>,
+    /// Only tasks started at or before this time (RFC 3339).
+    started_before: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ArchiveTaskInfo {
+    #[serde(flatten)]
+    task: ArchiveTask,
+    elapsed_seconds: i64,
+}
+
+/// Lists the archive tasks the server is working on or has recently finished,
+/// the most recently started first.
+///
+/// ### Query parameters
+/// * stage: ArchiveTaskStage (optional)
+/// * provider: ArchiveProvider (optional)
+/// * started_after: DateTime (optional)
+/// * started_before: DateTime (optional)
+///
+/// ### Response
+/// * Vec<ArchiveTaskInfo>
+#[utoipa::path(
+    context_path = "/archives",
+    tag = "archives",
+    params(TasksFilter),
+    responses(
+        (status = 200, body = Vec<ArchiveTaskInfo>),
+        (status = 422, body = ApiError),
+    )
+)]
+#[get("/tasks")]
+pub async fn list_archive_tasks(
+    filter: web::Query<TasksFilter>,
+) -> ApiResult<web::Json<Vec<ArchiveTaskInfo>>> {
+    let filter = filter.into_inner();
+    let tasks = list_tasks(&ArchiveTaskFilter {
+        stage: filter.stage,
+        provider: filter.provider,
+        started_after: filter.started_after,
+        started_before: filter.started_before,
+    });
+
+    Ok(web::Json(
+        tasks
+            .into_iter()
+            .map(|task| ArchiveTaskInfo {
+                elapsed_seconds: task.elapsed().num_seconds(),
+                task,
+            })
+            .collect(),
+    ))
+}
+
 /// Returns the archive task with the given ID.
-/// If the task is finished, it will be removed from the task list.
+/// Finished tasks are kept for a while before they are removed from the task list.
 ///
 /// ### Path parameters
 /// * task_id: String
@@ -103,62 +172,121 @@ pub async fn create_archive_task(
 ///
 /// ### Errors
 /// * 404: Task not found
+#[utoipa::path(
+    context_path = "/archives",
+    tag = "archives",
+    params(("task_id" = String, Path, description = "ID of the archive task")),
+    responses(
+        (status = 200, body = ArchiveTask),
+        (status = 404, body = ApiError),
+    )
+)]
 #[get("/tasks/{task_id}")]
-pub async fn get_archive_task(
-    task_id: web::Path<String>,
-) -> actix_web::Result<web::Json<ArchiveTask>> {
-    let task_id = &task_id.into_inner();
-    let tasks = ARCHIVE_TASKS.lock().unwrap();
-
-    if let Some(task) = tasks.get(task_id) {
-        if task.stage.is_finished() {
-            let task_id = task_id.clone();
-            thread::spawn(move || remove_task(&task_id));
+pub async fn get_archive_task(task_id: web::Path<String>) -> ApiResult<web::Json<ArchiveTask>> {
+    match get_task(&task_id) {
+        Some(task) => Ok(web::Json(task)),
+        None => Err(ApiError::not_found("Task not found")),
+    }
+}
+
+/// Streams the changes of the archive task with the given ID as Server-Sent Events.
+/// The current state is sent immediately as a `progress` event, then every change
+/// of stage or progress. The stream ends with a `completed` event containing the
+/// resulting mod, or a `failed` event.
+///
+/// ### Path parameters
+/// * task_id: String
+///
+/// ### Errors
+/// * 404: Task not found
+#[utoipa::path(
+    context_path = "/archives",
+    tag = "archives",
+    params(("task_id" = String, Path, description = "ID of the archive task")),
+    responses(
+        (status = 200, description = "Stream of task events", content_type = "text/event-stream", body = ArchiveTask),
+        (status = 404, body = ApiError),
+    )
+)]
+#[get("/tasks/{task_id}/events")]
+pub async fn stream_archive_task_events(task_id: web::Path<String>) -> ApiResult<HttpResponse> {
+    let (task, receiver) =
+        subscribe_task(&task_id).ok_or_else(|| ApiError::not_found("Task not found"))?;
+
+    // A finished task has nothing left to stream after its final event.
+    let receiver = (!task.stage.is_finished()).then_some(receiver);
+    let initial = stream::once(async move { Ok::<_, actix_web::Error>(to_event(&task)) });
+    let changes = stream::unfold(receiver, |receiver| async move {
+        let mut receiver = receiver?;
+        loop {
+            match receiver.recv().await {
+                Ok(task) => {
+                    let event = to_event(&task);
+                    // Stop streaming after the final event.
+                    let receiver = (!task.stage.is_finished()).then_some(receiver);
+                    return Some((Ok(event), receiver));
+                }
+                // Intermediate progress can be skipped, the next event carries the latest state.
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
         }
+    });
 
-        return Ok(web::Json(task.clone()));
-    }
+    let response = HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(initial.chain(changes));
+    Ok(response)
+}
+
+fn to_event(task: &ArchiveTask) -> web::Bytes {
+    let name = match task.stage {
+        ArchiveTaskStage::Completed => "completed",
+        ArchiveTaskStage::Failed => "failed",
+        _ => "progress",
+    };
+    let data = serde_json::to_string(task).unwrap();
 
-    Err(error::ErrorNotFound("Task not found"))
+    web::Bytes::from(format!("event: {}\ndata: {}\n\n", name, data))
 }
 
-async fn start_create_task(
+/// Executes a registered archive task, returns the archived mod unless the task failed.
+/// Failures are recorded so that they can be retried later.
+pub(crate) async fn run_archive_task(
     db: DatabaseConnection,
     task_id: String,
     provider: ArchiveProvider,
     identifier: String,
-    max_simultaneous_downloads: usize,
-) -> anyhow::Result<()> {
-    // Preparing download list.
-    let mut downloads = fetch_downloads(&provider, &identifier).await?;
-    downloads.sort_by(|a, b| a.game_version.cmp(&b.game_version));
-
-    // Downloading mod files.
-    update_task_progress(&task_id, Some(ArchiveTaskStage::Downloading), 0.1);
-
-    download_files(&downloads, max_simultaneous_downloads, |progress| {
-        update_task_progress(&task_id, None, 0.1 + progress * 0.75)
-    })
-    .await?;
-
-    // Extracting and parsing language files.
-    update_task_progress(&task_id, Some(ArchiveTaskStage::Extracting), 0.55);
-    let text_entries = parse_language_files(&downloads, |progress| {
-        update_task_progress(&task_id, None, 0.85 + progress * 0.1)
-    })
-    .await?;
-
-    // Saving to database.
-    update_task_progress(&task_id, Some(ArchiveTaskStage::Saving), 0.95);
-    let mc_mod =
-        create_mod_model(&db, &provider, identifier.clone(), text_entries.is_empty()).await?;
-    create_provider_model(&db, &provider, identifier, mc_mod.id).await?;
-    save_text_entries(&db, text_entries, mc_mod.id).await?;
-
-    let mut tasks = ARCHIVE_TASKS.lock().unwrap();
-    let task = tasks.get_mut(&task_id).unwrap();
-    task.stage = ArchiveTaskStage::Completed;
-    task.progress = 1.0;
-    task.mc_mod = Some(mc_mod);
-    Ok(())
+    download_options: DownloadOptions,
+) -> Option<minecraft_mod::Model> {
+    let result = archive_mod(
+        &db,
+        &provider,
+        identifier.clone(),
+        &download_options,
+        |stage, progress| update_task_progress(&task_id, stage, progress),
+    )
+    .await;
+
+    match result {
+        Ok(mc_mod) => {
+            complete_task(&task_id, mc_mod.clone());
+            if let Err(err) = clear_archive_failure(&db, &provider, &identifier).await {
+                warn!("Clear archive failure failed: {}", err);
+            }
+            Some(mc_mod)
+        }
+        Err(err) => {
+            fail_task(&task_id);
+            warn!("Execute archive task failed: {:?}", err);
+            let requester = get_task(&task_id).and_then(|task| task.requester);
+            if let Err(err) =
+                record_archive_failure(&db, &provider, &identifier, &err, requester).await
+            {
+                warn!("Record archive failure failed: {}", err);
+            }
+            None
+        }
+    }
 }
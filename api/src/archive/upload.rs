@@ -0,0 +1,321 @@
+use std::path::{Path, PathBuf};
+
+use actix_multipart::{Field, Multipart};
+use actix_web::{post, web};
+use futures_util::StreamExt;
+use log::warn;
+use service::{
+    archive::{
+        failure::{clear_archive_failure, record_archive_failure},
+        resource::{parse_loader, ArchiveProvider, ModDownloadInfo},
+        task::{complete_task, fail_task, insert_task, update_task_progress, ArchiveTask},
+        upload::{archive_uploaded_mod, create_upload_path, inspect_mod_archive, CustomModInfo},
+    },
+    minecraft::version::to_semver,
+    sea_orm::DatabaseConnection,
+};
+use tokio::io::AsyncWriteExt;
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{ApiError, ApiResult, ResultExt},
+    AppState,
+};
+
+/// Total size of the uploaded jars.
+const MAX_UPLOAD_SIZE: usize = 256 * 1024 * 1024;
+const MAX_TEXT_FIELD_SIZE: usize = 4 * 1024;
+const MAX_IDENTIFIER_LENGTH: usize = 64;
+
+#[derive(Debug, Default)]
+struct UploadForm {
+    identifier: Option<String>,
+    name: Option<String>,
+    description: Option<String>,
+    page_url: Option<String>,
+    jars: Vec<UploadedJar>,
+    size: usize,
+}
+
+#[derive(Debug)]
+struct UploadedJar {
+    file_name: String,
+    path: PathBuf,
+    game_version: Option<String>,
+    loader: Option<String>,
+}
+
+impl UploadForm {
+    fn remove_files(&self) {
+        for jar in &self.jars {
+            let _ = std::fs::remove_file(&jar.path);
+        }
+    }
+}
+
+/// Archives mod jars uploaded directly instead of fetched from a provider,
+/// the mod is stored with a `Custom` provider. Uploading again with the same
+/// identifier replaces the entries of the mod. Entries with keys of other mods
+/// are skipped.
+///
+/// ### Payload
+/// A `multipart/form-data` body with these fields:
+/// * file: Mod jar, can be repeated for several releases of the mod
+/// * game_version: String, game version of the preceding jar
+/// * loader: String, loader of the preceding jar (`fabric`, `forge`, `neoforge` or `quilt`)
+/// * identifier: String (optional), defaults to the namespace of the first jar
+/// * name: String (optional), defaults to the identifier
+/// * description: String (optional)
+/// * page_url: String (optional)
+///
+/// ### Response
+/// * String: Task ID
+///
+/// ### Errors
+/// * 401: Authentication required
+/// * 409: A task for the same mod is still running
+/// * 422: Invalid form, or a file is not a supported mod jar
+/// * 429: Too many tasks created, retry after `Retry-After` seconds
+#[utoipa::path(
+    context_path = "/archives",
+    tag = "archives",
+    request_body(content = String, content_type = "multipart/form-data", description = "Mod jars with their game versions and loaders"),
+    responses(
+        (status = 200, description = "ID of the created task", body = String),
+        (status = 401, body = ApiError),
+        (status = 409, body = ApiError),
+        (status = 422, body = ApiError),
+        (status = 429, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[post("/upload")]
+pub async fn upload_mod_archives(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    payload: Multipart,
+) -> ApiResult<String> {
+    let mut form = UploadForm::default();
+    let result = read_upload_form(payload, &mut form).await;
+    let result = match result {
+        Ok(()) => prepare_downloads(&form).await,
+        Err(err) => Err(err),
+    };
+    let (info, downloads) = match result {
+        Ok(prepared) => prepared,
+        Err(err) => {
+            form.remove_files();
+            return Err(err);
+        }
+    };
+
+    let task = ArchiveTask::new(
+        ArchiveProvider::Custom,
+        info.identifier.clone(),
+        Some(user.user.username.clone()),
+    );
+    let task_id = task.id.clone();
+
+    if !insert_task(task) {
+        form.remove_files();
+        return Err(
+            ApiError::conflict("A task for this mod is still running").with_details(&task_id)
+        );
+    }
+
+    let task_id_clone = task_id.clone();
+    let requester = user.user.username.clone();
+    tokio::spawn(async move {
+        let identifier = info.identifier.clone();
+        let result =
+            start_upload_task(state.db.clone(), task_id_clone.clone(), info, downloads).await;
+
+        if let Err(err) = result {
+            fail_task(&task_id_clone);
+            warn!("Execute upload archive task failed: {:?}", err);
+            let provider = ArchiveProvider::Custom;
+            let recorded =
+                record_archive_failure(&state.db, &provider, &identifier, &err, Some(requester));
+            if let Err(err) = recorded.await {
+                warn!("Record archive failure failed: {}", err);
+            }
+        }
+    });
+
+    Ok(task_id)
+}
+
+async fn read_upload_form(mut payload: Multipart, form: &mut UploadForm) -> ApiResult<()> {
+    while let Some(field) = payload.next().await {
+        let field = field.map_err(invalid_payload)?;
+        let name = field.name().unwrap_or_default().to_string();
+
+        match name.as_str() {
+            "file" => {
+                let file_name = field
+                    .content_disposition()
+                    .and_then(|disposition| disposition.get_filename())
+                    .unwrap_or_default()
+                    .to_string();
+                let path = create_upload_path().or_internal("Failed to store uploaded file")?;
+                // Registered before writing, so a partially written file is removed too.
+                form.jars.push(UploadedJar {
+                    file_name,
+                    path: path.clone(),
+                    game_version: None,
+                    loader: None,
+                });
+                form.size += write_file(field, &path, MAX_UPLOAD_SIZE - form.size).await?;
+            }
+            "game_version" | "loader" => {
+                let value = read_text(field).await?;
+                let jar = form.jars.last_mut().ok_or_else(|| {
+                    ApiError::validation("Versions must follow the file they belong to")
+                        .with_details(&name)
+                })?;
+                if name == "game_version" {
+                    jar.game_version = Some(value);
+                } else {
+                    jar.loader = Some(value);
+                }
+            }
+            "identifier" => form.identifier = Some(read_text(field).await?),
+            "name" => form.name = Some(read_text(field).await?),
+            "description" => form.description = Some(read_text(field).await?),
+            "page_url" => form.page_url = Some(read_text(field).await?),
+            _ => return Err(ApiError::validation("Unknown form field").with_details(&name)),
+        }
+    }
+
+    Ok(())
+}
+
+async fn prepare_downloads(form: &UploadForm) -> ApiResult<(CustomModInfo, Vec<ModDownloadInfo>)> {
+    if form.jars.is_empty() {
+        return Err(ApiError::validation("At least one mod jar is required"));
+    }
+
+    let mut namespaces = Vec::with_capacity(form.jars.len());
+    let mut downloads = Vec::with_capacity(form.jars.len());
+    for jar in &form.jars {
+        let game_version = jar.game_version.as_deref().ok_or_else(|| {
+            ApiError::validation("Missing game version").with_details(&jar.file_name)
+        })?;
+        let game_version = to_semver(game_version)
+            .map_err(|_| ApiError::validation("Invalid game version").with_details(game_version))?;
+        let loader = jar
+            .loader
+            .as_deref()
+            .ok_or_else(|| ApiError::validation("Missing loader").with_details(&jar.file_name))?;
+        let loader = parse_loader(loader)
+            .ok_or_else(|| ApiError::validation("Unsupported loader").with_details(loader))?;
+        let path = jar.path.clone();
+        let (namespace, size) = web::block(move || {
+            let size = std::fs::metadata(&path).map_or(0, |meta| meta.len() as usize);
+            (inspect_mod_archive(&path), size)
+        })
+        .await
+        .or_internal("Inspect uploaded jar failed")?;
+        let namespace = namespace.map_err(|_| {
+            ApiError::validation("Not a supported mod jar").with_details(&jar.file_name)
+        })?;
+
+        namespaces.push(namespace);
+        downloads.push(ModDownloadInfo {
+            url: jar.file_name.clone(),
+            size,
+            loader,
+            game_version,
+            path: jar.path.clone(),
+        });
+    }
+    downloads.sort_by(|a, b| a.game_version.cmp(&b.game_version));
+
+    let identifier = form
+        .identifier
+        .clone()
+        .unwrap_or_else(|| namespaces[0].clone());
+    if identifier.is_empty() || identifier.len() > MAX_IDENTIFIER_LENGTH {
+        return Err(ApiError::validation("Invalid identifier").with_details(&identifier));
+    }
+
+    let info = CustomModInfo {
+        name: form.name.clone().unwrap_or_else(|| identifier.clone()),
+        identifier,
+        description: form.description.clone(),
+        page_url: form.page_url.clone(),
+    };
+    Ok((info, downloads))
+}
+
+/// Streams the field to the file, returns the number of bytes written.
+async fn write_file(mut field: Field, path: &Path, limit: usize) -> ApiResult<usize> {
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .or_internal("Failed to store uploaded file")?;
+    let mut size = 0;
+
+    while let Some(chunk) = field.next().await {
+        let chunk = chunk.map_err(invalid_payload)?;
+        size += chunk.len();
+        if size > limit {
+            return Err(ApiError::validation("Uploaded files are too large")
+                .with_details(format!("{} bytes at most", MAX_UPLOAD_SIZE)));
+        }
+        file.write_all(&chunk)
+            .await
+            .or_internal("Failed to store uploaded file")?;
+    }
+    file.flush()
+        .await
+        .or_internal("Failed to store uploaded file")?;
+
+    Ok(size)
+}
+
+async fn read_text(mut field: Field) -> ApiResult<String> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = field.next().await {
+        let chunk = chunk.map_err(invalid_payload)?;
+        if bytes.len() + chunk.len() > MAX_TEXT_FIELD_SIZE {
+            return Err(ApiError::validation("Form field is too large").with_details(field.name()));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    String::from_utf8(bytes)
+        .map(|value| value.trim().to_string())
+        .map_err(|_| {
+            ApiError::validation("Form field is not valid UTF-8").with_details(field.name())
+        })
+}
+
+fn invalid_payload(err: actix_multipart::MultipartError) -> ApiError {
+    ApiError::validation("Invalid multipart payload").with_details(err.to_string())
+}
+
+async fn start_upload_task(
+    db: DatabaseConnection,
+    task_id: String,
+    info: CustomModInfo,
+    downloads: Vec<ModDownloadInfo>,
+) -> anyhow::Result<()> {
+    let identifier = info.identifier.clone();
+    let (mc_mod, skipped_keys) = archive_uploaded_mod(&db, info, downloads, |stage, progress| {
+        update_task_progress(&task_id, stage, progress)
+    })
+    .await?;
+
+    if !skipped_keys.is_empty() {
+        warn!(
+            "Skipped {} keys of {} used by other mods: {}",
+            skipped_keys.len(),
+            identifier,
+            skipped_keys.join(", ")
+        );
+    }
+
+    complete_task(&task_id, mc_mod);
+    clear_archive_failure(&db, &ArchiveProvider::Custom, &identifier).await?;
+    Ok(())
+}
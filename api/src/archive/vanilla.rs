@@ -0,0 +1,188 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use actix_web::{post, web};
+use log::{info, warn};
+use serde::Deserialize;
+use service::{
+    archive::{
+        failure::{clear_archive_failure, record_archive_failure},
+        resource::ArchiveProvider,
+        task::{
+            complete_task, fail_task, insert_task, update_task_progress, ArchiveTask,
+            ArchiveTaskStage,
+        },
+        vanilla::{
+            import_vanilla_assets, read_vanilla_assets, VanillaAssets, VanillaImportOptions,
+            VANILLA_IDENTIFIER,
+        },
+    },
+    minecraft::version::to_semver,
+    sea_orm::DatabaseConnection,
+};
+use utoipa::ToSchema;
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{ApiError, ApiResult, ResultExt},
+    translation::validate_language,
+    AppState,
+};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VanillaImportPayload {
+    /// Path of the client jar on the server.
+    pub client_jar: String,
+    /// Path of the asset index on the server (`assets/indexes/<id>.json`),
+    /// only the source values are imported without it.
+    pub asset_index: Option<String>,
+    pub game_version: String,
+    /// Languages to import, all languages of the asset index if not set.
+    pub languages: Option<Vec<String>>,
+    /// Add the names of blocks, items, entities, biomes, effects and
+    /// enchantments to the global glossaries.
+    #[serde(default)]
+    pub glossary: bool,
+}
+
+/// Imports the vanilla language files of a game version from the server's file system
+/// into the `minecraft` mod, so that they can be searched and feed the translation
+/// memory. Translations are saved as approved by the requester, only when the game
+/// version is the latest imported one. Keys already used by other mods are skipped and
+/// logged.
+///
+/// ### Payload
+/// * client_jar: String
+/// * asset_index: String (optional)
+/// * game_version: String
+/// * languages: Vec<String> (optional)
+/// * glossary: bool (optional)
+///
+/// ### Response
+/// * String: Task ID
+///
+/// ### Errors
+/// * 401: Authentication required
+/// * 403: Not an administrator
+/// * 409: A vanilla import is still running
+/// * 422: Invalid game version, language code or assets
+#[utoipa::path(
+    context_path = "/archives",
+    tag = "archives",
+    request_body = VanillaImportPayload,
+    responses(
+        (status = 200, description = "ID of the created task", body = String),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 409, body = ApiError),
+        (status = 422, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[post("/vanilla")]
+pub async fn import_vanilla(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    payload: web::Json<VanillaImportPayload>,
+) -> ApiResult<String> {
+    if !user.is_admin() {
+        return Err(ApiError::forbidden(
+            "Only administrators can import game assets",
+        ));
+    }
+
+    let payload = payload.into_inner();
+    let game_version = to_semver(&payload.game_version).map_err(|_| {
+        ApiError::validation("Invalid game version").with_details(&payload.game_version)
+    })?;
+    let languages = match payload.languages {
+        Some(languages) => {
+            for language in &languages {
+                validate_language(language)?;
+            }
+            Some(languages.into_iter().collect::<HashSet<_>>())
+        }
+        None => None,
+    };
+    let options = VanillaImportOptions {
+        client_jar: PathBuf::from(payload.client_jar),
+        asset_index: payload.asset_index.map(PathBuf::from),
+        game_version,
+        languages,
+        glossary: payload.glossary,
+    };
+
+    // Reading the client jar and the assets takes a while, so not on a worker thread.
+    let read_options = options.clone();
+    let assets = web::block(move || read_vanilla_assets(&read_options))
+        .await
+        .or_internal("Read game assets failed")?
+        .map_err(|err| {
+            ApiError::validation("Invalid game assets").with_details(format!("{:#}", err))
+        })?;
+
+    let task = ArchiveTask::new(
+        ArchiveProvider::Vanilla,
+        VANILLA_IDENTIFIER.to_string(),
+        Some(user.user.username.clone()),
+    );
+    let task_id = task.id.clone();
+    if !insert_task(task) {
+        return Err(ApiError::conflict("A vanilla import is still running").with_details(&task_id));
+    }
+
+    let task_id_clone = task_id.clone();
+    tokio::spawn(async move {
+        let result = start_vanilla_task(
+            state.db.clone(),
+            task_id_clone.clone(),
+            assets,
+            options,
+            user.user.id,
+        )
+        .await;
+
+        if let Err(err) = result {
+            fail_task(&task_id_clone);
+            warn!("Execute vanilla import task failed: {:?}", err);
+            let recorded = record_archive_failure(
+                &state.db,
+                &ArchiveProvider::Vanilla,
+                VANILLA_IDENTIFIER,
+                &err,
+                Some(user.user.username),
+            );
+            if let Err(err) = recorded.await {
+                warn!("Record archive failure failed: {}", err);
+            }
+        }
+    });
+
+    Ok(task_id)
+}
+
+async fn start_vanilla_task(
+    db: DatabaseConnection,
+    task_id: String,
+    assets: VanillaAssets,
+    options: VanillaImportOptions,
+    user_id: i32,
+) -> anyhow::Result<()> {
+    // The assets are already read, so there is nothing to download or extract.
+    update_task_progress(&task_id, Some(ArchiveTaskStage::Saving), 0.1);
+    let (mc_mod, report) = import_vanilla_assets(&db, assets, &options, user_id, |progress| {
+        update_task_progress(&task_id, None, 0.1 + progress * 0.9)
+    })
+    .await?;
+
+    if !report.skipped_keys.is_empty() {
+        warn!(
+            "Skipped {} vanilla keys used by other mods: {}",
+            report.skipped_keys.len(),
+            report.skipped_keys.join(", ")
+        );
+    }
+    info!("Imported vanilla assets: {:?}", report);
+    complete_task(&task_id, mc_mod);
+    clear_archive_failure(&db, &ArchiveProvider::Vanilla, VANILLA_IDENTIFIER).await?;
+    Ok(())
+}
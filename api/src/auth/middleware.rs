@@ -0,0 +1,151 @@
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header,
+    web, FromRequest, HttpMessage, HttpRequest,
+};
+use entity::account::{
+    user,
+    user_role::{self, Role},
+};
+use futures_util::future::LocalBoxFuture;
+use log::warn;
+use service::{
+    account::{
+        role::{find_user_roles, has_global_role, has_role},
+        token::authenticate_token,
+    },
+    sea_orm::DbErr,
+};
+
+use crate::{error::ApiError, AppState};
+
+/// The user authenticated by the bearer token of the request.
+///
+/// Use `Option<AuthenticatedUser>` for endpoints that also serve anonymous requests.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser {
+    pub user: user::Model,
+    pub roles: Vec<user_role::Model>,
+    /// ID of the token used for this request.
+    pub token_id: i32,
+}
+
+impl AuthenticatedUser {
+    /// See [`has_role`] for how language-specific roles are handled.
+    pub fn has_role(&self, role: &Role, language: Option<&str>) -> bool {
+        has_role(&self.roles, role, language)
+    }
+
+    /// Whether the user has at least the role for all languages.
+    pub fn has_global_role(&self, role: &Role) -> bool {
+        has_global_role(&self.roles, role)
+    }
+
+    /// Whether the user is an administrator of all languages.
+    pub fn is_admin(&self) -> bool {
+        self.has_global_role(&Role::Admin)
+    }
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = ApiError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut actix_web::dev::Payload) -> Self::Future {
+        let user = req.extensions().get::<AuthenticatedUser>().cloned();
+        ready(user.ok_or_else(|| ApiError::unauthorized("Authentication required")))
+    }
+}
+
+/// Resolves the `Authorization: Bearer <token>` header of every request to an
+/// [`AuthenticatedUser`]. Requests without the header pass through anonymously,
+/// requests with an invalid or expired token are rejected.
+pub struct Authentication;
+
+impl<S, B> Transform<S, ServiceRequest> for Authentication
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Transform = AuthenticationMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AuthenticationMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct AuthenticationMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for AuthenticationMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let token = bearer_token(req.request());
+            let state = req.app_data::<web::Data<AppState>>().cloned();
+
+            if let (Some(token), Some(state)) = (token, state) {
+                match authenticate(&state, &token).await {
+                    Ok(Some(user)) => {
+                        req.extensions_mut().insert(user);
+                    }
+                    Ok(None) => {
+                        let err = ApiError::unauthorized("Invalid or expired token");
+                        return Ok(req.error_response(err).map_into_right_body());
+                    }
+                    Err(err) => {
+                        warn!("Failed to authenticate token: {}", err);
+                        let err = ApiError::internal("Failed to authenticate token");
+                        return Ok(req.error_response(err).map_into_right_body());
+                    }
+                }
+            }
+
+            service.call(req).await.map(|res| res.map_into_left_body())
+        })
+    }
+}
+
+async fn authenticate(state: &AppState, token: &str) -> Result<Option<AuthenticatedUser>, DbErr> {
+    let Some((token, user)) = authenticate_token(&state.db, token).await? else {
+        return Ok(None);
+    };
+    let roles = find_user_roles(&state.db, user.id).await?;
+
+    Ok(Some(AuthenticatedUser {
+        user,
+        roles,
+        token_id: token.id,
+    }))
+}
+
+fn bearer_token(req: &HttpRequest) -> Option<String> {
+    let value = req.headers().get(header::AUTHORIZATION)?.to_str().ok()?;
+    let token = value.strip_prefix("Bearer ")?.trim();
+
+    (!token.is_empty()).then(|| token.to_string())
+}
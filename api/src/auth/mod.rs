@@ -0,0 +1,428 @@
+pub(crate) mod middleware;
+pub(crate) mod permission;
+
+use std::collections::HashMap;
+
+use actix_web::{delete, get, http::header, post, web, HttpResponse};
+use entity::account::{
+    access_token::{self, TokenKind},
+    user,
+};
+use serde::{Deserialize, Serialize};
+use service::{
+    account::{
+        oauth::{
+            consume_state, create_state, login_with_oauth, DiscordOAuth, GitHubOAuth, OAuthProvider,
+        },
+        token::{issue_token, list_tokens, revoke_token},
+        user::{
+            create_user, find_user_by_username, is_username_taken, is_valid_username,
+            verify_password, MIN_PASSWORD_LENGTH,
+        },
+    },
+    chrono::{DateTime, Duration, Utc},
+};
+use utoipa::{IntoParams, ToSchema};
+
+pub use self::{
+    middleware::{AuthenticatedUser, Authentication},
+    permission::RequireRole,
+};
+use crate::{
+    config::ServerConfig,
+    error::{ApiError, ApiResult, ResultExt},
+    AppState,
+};
+
+pub type OAuthProviders = HashMap<&'static str, Box<dyn OAuthProvider>>;
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.service(register);
+    cfg.service(login);
+    cfg.service(logout);
+    cfg.service(oauth_authorize);
+    cfg.service(oauth_callback);
+    cfg.service(create_api_token);
+    cfg.service(list_api_tokens);
+    cfg.service(delete_api_token);
+}
+
+/// Creates the OAuth providers enabled in the config.
+pub fn create_oauth_providers(config: &ServerConfig) -> OAuthProviders {
+    let mut providers: Vec<Box<dyn OAuthProvider>> = Vec::new();
+
+    if let Some(github) = &config.github_oauth {
+        providers.push(Box::new(GitHubOAuth::new(github.clone())));
+    }
+    if let Some(discord) = &config.discord_oauth {
+        providers.push(Box::new(DiscordOAuth::new(discord.clone())));
+    }
+
+    providers
+        .into_iter()
+        .map(|provider| (provider.name(), provider))
+        .collect()
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterPayload {
+    pub username: String,
+    pub password: String,
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LoginPayload {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionResponse {
+    /// Bearer token for the `Authorization` header.
+    pub token: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    #[schema(value_type = User)]
+    pub user: user::Model,
+}
+
+/// Registers a new user with a local password and logs in.
+///
+/// ### Payload
+/// * username: String (3-32 characters of letters, digits, `_`, `-` or `.`)
+/// * password: String (at least 8 characters)
+/// * display_name: String (optional, defaults to the username)
+///
+/// ### Response
+/// * SessionResponse
+///
+/// ### Errors
+/// * 409: Username already taken
+/// * 422: Invalid username or password
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    request_body = RegisterPayload,
+    responses(
+        (status = 200, body = SessionResponse),
+        (status = 409, body = ApiError),
+        (status = 422, body = ApiError),
+    )
+)]
+#[post("/register")]
+pub async fn register(
+    state: web::Data<AppState>,
+    payload: web::Json<RegisterPayload>,
+) -> ApiResult<web::Json<SessionResponse>> {
+    let payload = payload.into_inner();
+    validate_username(&payload.username)?;
+    if payload.password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(ApiError::validation(format!(
+            "Password must be at least {} characters",
+            MIN_PASSWORD_LENGTH
+        )));
+    }
+
+    if find_user_by_username(&state.db, &payload.username)
+        .await?
+        .is_some()
+    {
+        return Err(ApiError::conflict("Username already taken"));
+    }
+
+    let display_name = payload.display_name.unwrap_or(payload.username.clone());
+    let created = create_user(
+        &state.db,
+        payload.username,
+        display_name,
+        Some(&payload.password),
+    )
+    .await;
+    let user = match created {
+        Ok(user) => user,
+        // Another registration took the username since it was checked.
+        Err(err) if is_username_taken(&err) => {
+            return Err(ApiError::conflict("Username already taken"))
+        }
+        Err(err) => return Err(err).or_internal("Failed to create user"),
+    };
+
+    start_session(&state, user).await
+}
+
+/// Logs in with a local password.
+///
+/// ### Payload
+/// * username: String
+/// * password: String
+///
+/// ### Response
+/// * SessionResponse
+///
+/// ### Errors
+/// * 401: Wrong username or password
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    request_body = LoginPayload,
+    responses(
+        (status = 200, body = SessionResponse),
+        (status = 401, body = ApiError),
+    )
+)]
+#[post("/login")]
+pub async fn login(
+    state: web::Data<AppState>,
+    payload: web::Json<LoginPayload>,
+) -> ApiResult<web::Json<SessionResponse>> {
+    let user = verify_password(&state.db, &payload.username, &payload.password)
+        .await
+        .or_internal("Failed to verify password")?
+        .ok_or_else(|| ApiError::unauthorized("Wrong username or password"))?;
+
+    start_session(&state, user).await
+}
+
+/// Revokes the token used for this request.
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    responses(
+        (status = 204),
+        (status = 401, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[post("/logout")]
+pub async fn logout(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+) -> ApiResult<HttpResponse> {
+    revoke_token(&state.db, user.user.id, user.token_id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Redirects to the authorization page of the OAuth provider, which will redirect
+/// back to the callback endpoint.
+///
+/// ### Path parameters
+/// * provider: String (e.g. `github`, `discord`)
+///
+/// ### Errors
+/// * 404: Provider not configured
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    params(("provider" = String, Path, description = "Name of the OAuth provider")),
+    responses(
+        (status = 302, description = "Redirect to the provider"),
+        (status = 404, body = ApiError),
+    )
+)]
+#[get("/oauth/{provider}/authorize")]
+pub async fn oauth_authorize(
+    state: web::Data<AppState>,
+    provider: web::Path<String>,
+) -> ApiResult<HttpResponse> {
+    let provider = find_oauth_provider(&state, &provider)?;
+    let url = provider.authorize_url(&oauth_redirect_uri(&state, provider), &create_state());
+
+    Ok(HttpResponse::Found()
+        .insert_header((header::LOCATION, url))
+        .finish())
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct OAuthCallbackQuery {
+    code: String,
+    state: String,
+}
+
+/// Completes the OAuth login, a new user is created on the first login.
+///
+/// ### Path parameters
+/// * provider: String
+///
+/// ### Response
+/// * SessionResponse
+///
+/// ### Errors
+/// * 400: Invalid or expired state
+/// * 401: The provider rejected the authorization code
+/// * 404: Provider not configured
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    params(
+        ("provider" = String, Path, description = "Name of the OAuth provider"),
+        OAuthCallbackQuery
+    ),
+    responses(
+        (status = 200, body = SessionResponse),
+        (status = 400, body = ApiError),
+        (status = 401, body = ApiError),
+        (status = 404, body = ApiError),
+    )
+)]
+#[get("/oauth/{provider}/callback")]
+pub async fn oauth_callback(
+    state: web::Data<AppState>,
+    provider: web::Path<String>,
+    query: web::Query<OAuthCallbackQuery>,
+) -> ApiResult<web::Json<SessionResponse>> {
+    let provider = find_oauth_provider(&state, &provider)?;
+    if !consume_state(&query.state) {
+        return Err(ApiError::bad_request("Invalid or expired OAuth state"));
+    }
+
+    let identity = provider
+        .exchange_code(&query.code, &oauth_redirect_uri(&state, provider))
+        .await
+        .map_err(|err| {
+            log::warn!("OAuth login with {} failed: {:?}", provider.name(), err);
+            ApiError::unauthorized("OAuth login failed")
+        })?;
+    let user = login_with_oauth(&state.db, provider.name(), identity)
+        .await
+        .or_internal("Failed to log in with OAuth")?;
+
+    start_session(&state, user).await
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTokenPayload {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedTokenResponse {
+    /// The token is only shown once.
+    pub token: String,
+    #[schema(value_type = AccessToken)]
+    pub info: access_token::Model,
+}
+
+/// Creates a long-lived API token for scripts and bots.
+///
+/// ### Payload
+/// * name: String
+///
+/// ### Response
+/// * CreatedTokenResponse
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    request_body = CreateTokenPayload,
+    responses(
+        (status = 200, body = CreatedTokenResponse),
+        (status = 401, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[post("/tokens")]
+pub async fn create_api_token(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    payload: web::Json<CreateTokenPayload>,
+) -> ApiResult<web::Json<CreatedTokenResponse>> {
+    let issued = issue_token(
+        &state.db,
+        user.user.id,
+        TokenKind::Api,
+        Some(payload.into_inner().name),
+        None,
+    )
+    .await?;
+
+    Ok(web::Json(CreatedTokenResponse {
+        token: issued.token,
+        info: issued.model,
+    }))
+}
+
+/// Lists the API tokens of the current user.
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    responses(
+        (status = 200, body = Vec<AccessToken>),
+        (status = 401, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[get("/tokens")]
+pub async fn list_api_tokens(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+) -> ApiResult<web::Json<Vec<access_token::Model>>> {
+    let tokens = list_tokens(&state.db, user.user.id, TokenKind::Api).await?;
+    Ok(web::Json(tokens))
+}
+
+/// Revokes an API token of the current user.
+///
+/// ### Errors
+/// * 404: Token not found
+#[utoipa::path(
+    context_path = "/auth",
+    tag = "auth",
+    params(("token_id" = i32, Path, description = "ID of the token")),
+    responses(
+        (status = 204),
+        (status = 401, body = ApiError),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[delete("/tokens/{token_id}")]
+pub async fn delete_api_token(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    token_id: web::Path<i32>,
+) -> ApiResult<HttpResponse> {
+    if !revoke_token(&state.db, user.user.id, *token_id).await? {
+        return Err(ApiError::not_found("Token not found"));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+async fn start_session(
+    state: &AppState,
+    user: user::Model,
+) -> ApiResult<web::Json<SessionResponse>> {
+    let ttl = Duration::days(state.config.session_ttl_days);
+    let issued = issue_token(&state.db, user.id, TokenKind::Session, None, Some(ttl)).await?;
+
+    Ok(web::Json(SessionResponse {
+        token: issued.token,
+        expires_at: issued.model.expires_at,
+        user,
+    }))
+}
+
+fn find_oauth_provider<'a>(state: &'a AppState, name: &str) -> ApiResult<&'a dyn OAuthProvider> {
+    state
+        .oauth_providers
+        .get(name)
+        .map(|provider| provider.as_ref())
+        .ok_or_else(|| ApiError::not_found("OAuth provider not configured"))
+}
+
+fn oauth_redirect_uri(state: &AppState, provider: &dyn OAuthProvider) -> String {
+    format!(
+        "{}/auth/oauth/{}/callback",
+        state.config.public_url,
+        provider.name()
+    )
+}
+
+fn validate_username(username: &str) -> ApiResult<()> {
+    if !is_valid_username(username) {
+        return Err(ApiError::validation(
+            "Username must be 3-32 characters of letters, digits, '_', '-' or '.'",
+        ));
+    }
+
+    Ok(())
+}
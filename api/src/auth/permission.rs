@@ -0,0 +1,125 @@
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::Method,
+    HttpMessage,
+};
+use entity::account::user_role::Role;
+use futures_util::future::LocalBoxFuture;
+
+use super::AuthenticatedUser;
+use crate::error::ApiError;
+
+/// Rejects requests unless the authenticated user has at least the given role,
+/// globally or for any language. Handlers check language-specific roles themselves.
+///
+/// Wrap a scope or resource with it, optionally only for some methods so that
+/// reading stays public.
+#[derive(Clone)]
+pub struct RequireRole {
+    role: Role,
+    methods: Option<Vec<Method>>,
+    global: bool,
+}
+
+impl RequireRole {
+    pub fn new(role: Role) -> Self {
+        Self {
+            role,
+            methods: None,
+            global: false,
+        }
+    }
+
+    /// Only accepts the role if it is assigned for all languages, for resources
+    /// that are shared by every language.
+    pub fn globally(mut self) -> Self {
+        self.global = true;
+        self
+    }
+
+    /// Only requires the role for requests with one of the methods.
+    pub fn for_methods(mut self, methods: &[Method]) -> Self {
+        self.methods = Some(methods.to_vec());
+        self
+    }
+
+    /// Requires the role for every method that is not read-only.
+    pub fn for_writes(self) -> Self {
+        self.for_methods(&[Method::POST, Method::PUT, Method::PATCH, Method::DELETE])
+    }
+
+    fn check(&self, req: &ServiceRequest) -> Result<(), ApiError> {
+        if let Some(methods) = &self.methods {
+            if !methods.contains(req.method()) {
+                return Ok(());
+            }
+        }
+
+        let extensions = req.extensions();
+        let user = extensions
+            .get::<AuthenticatedUser>()
+            .ok_or_else(|| ApiError::unauthorized("Authentication required"))?;
+
+        let allowed = if self.global {
+            user.has_global_role(&self.role)
+        } else {
+            user.has_role(&self.role, None)
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(ApiError::forbidden("Insufficient permissions").with_details(&self.role))
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequireRole
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Transform = RequireRoleMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequireRoleMiddleware {
+            service: Rc::new(service),
+            requirement: self.clone(),
+        }))
+    }
+}
+
+pub struct RequireRoleMiddleware<S> {
+    service: Rc<S>,
+    requirement: RequireRole,
+}
+
+impl<S, B> Service<ServiceRequest> for RequireRoleMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if let Err(err) = self.requirement.check(&req) {
+            return Box::pin(async move { Ok(req.error_response(err).map_into_right_body()) });
+        }
+
+        let service = Rc::clone(&self.service);
+        Box::pin(async move { service.call(req).await.map(|res| res.map_into_left_body()) })
+    }
+}
@@ -1,41 +1,828 @@
-use std::env;
+//! Server configuration, read from an optional TOML file and overridden by environment
+//! variables. Every invalid value is collected so that they can be reported at once.
+
+use std::{
+    env, fmt,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use serde::{de::DeserializeOwned, Deserialize};
+use service::{
+    account::oauth::OAuthClientConfig,
+    archive::task::{default_archives_directory, DownloadOptions},
+    translation::machine::MachineTranslatorConfig,
+};
+use tracing_subscriber::EnvFilter;
+
+use crate::{
+    rate_limit::{RateLimit, RateLimits},
+    tls::load_rustls_config,
+};
 
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
+    /// Address the server listens on unless `listen` is set, a host name or an IP address.
+    pub host: String,
     pub port: u16,
+    /// Addresses the server listens on, defaults to `host` and `port`.
+    pub listen: Vec<ListenAddress>,
+    /// Serves HTTPS instead of HTTP on the TCP addresses.
+    pub tls: Option<TlsConfig>,
     pub database_url: String,
+    /// Public URL of the server, used to build OAuth redirect URLs.
+    pub public_url: String,
+    /// Number of HTTP worker threads, defaults to the number of CPU cores.
+    pub workers: Option<usize>,
+    /// Time a client has to send the request headers.
+    pub request_timeout: Duration,
+    /// Time the running requests have to finish when the server stops.
+    pub shutdown_timeout: Duration,
+    /// Log filter such as `info` or `info,sea_orm=debug`, `RUST_LOG` takes precedence.
+    pub log_level: String,
 
+    /// Where mod files are stored while they are archived.
+    pub archives_directory: PathBuf,
     pub max_simultaneous_downloads: usize,
+    pub download_timeout: Duration,
+
+    pub session_ttl_days: i64,
+    pub github_oauth: Option<OAuthClientConfig>,
+    pub discord_oauth: Option<OAuthClientConfig>,
+
+    /// Submitted suggestions are approved automatically once their score reaches this.
+    pub auto_approve_score: Option<i32>,
+
+    pub deepl: Option<MachineTranslatorConfig>,
+    pub google_translate: Option<MachineTranslatorConfig>,
+    /// Any server with an OpenAI-compatible chat completions API.
+    pub openai: Option<MachineTranslatorConfig>,
+    pub libretranslate: Option<MachineTranslatorConfig>,
+
+    pub rate_limits: RateLimits,
+    /// Use the client address from `Forwarded`/`X-Forwarded-For` for rate limiting,
+    /// only enable this behind a reverse proxy that sets these headers.
+    pub trust_proxy_headers: bool,
+    /// Origins of web frontends allowed to call the API from a browser, `*` allows any
+    /// origin. Only same-origin requests are allowed by default.
+    pub cors_allowed_origins: Vec<String>,
 }
 
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
+            host: "127.0.0.1".to_string(),
             port: 8080,
+            listen: vec![ListenAddress::Tcp("127.0.0.1:8080".to_string())],
+            tls: None,
             database_url: "sqlite::memory:".to_string(),
+            public_url: "http://localhost:8080".to_string(),
+            workers: None,
+            request_timeout: Duration::from_secs(5),
+            shutdown_timeout: Duration::from_secs(30),
+            log_level: "info".to_string(),
+            archives_directory: default_archives_directory(),
             max_simultaneous_downloads: 10,
+            download_timeout: Duration::from_secs(300),
+            session_ttl_days: 30,
+            github_oauth: None,
+            discord_oauth: None,
+            auto_approve_score: None,
+            deepl: None,
+            google_translate: None,
+            openai: None,
+            libretranslate: None,
+            rate_limits: RateLimits::default(),
+            trust_proxy_headers: false,
+            cors_allowed_origins: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListenAddress {
+    /// `<HOST>:<PORT>`, IPv6 addresses are enclosed in brackets.
+    Tcp(String),
+    /// Path of a Unix domain socket, always served without TLS.
+    Unix(PathBuf),
+}
+
+impl ListenAddress {
+    fn from_host(host: &str, port: u16) -> Self {
+        match host.parse::<IpAddr>() {
+            Ok(IpAddr::V6(ip)) => Self::Tcp(format!("[{}]:{}", ip, port)),
+            _ => Self::Tcp(format!("{}:{}", host, port)),
+        }
+    }
+}
+
+impl FromStr for ListenAddress {
+    type Err = String;
+
+    /// Parses `<HOST>:<PORT>`, `[<IPV6>]:<PORT>` or `unix:<PATH>`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = value.strip_prefix("unix:") {
+            if path.is_empty() {
+                return Err("the Unix socket path must not be empty".to_string());
+            }
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+
+        let valid = match value.rsplit_once(':') {
+            Some((host, port)) => {
+                let bracketed = host.starts_with('[') && host.ends_with(']');
+                let host_valid = if bracketed {
+                    host[1..host.len() - 1]
+                        .parse::<std::net::Ipv6Addr>()
+                        .is_ok()
+                } else {
+                    !host.is_empty() && !host.contains(':')
+                };
+                host_valid && port.parse::<u16>().is_ok()
+            }
+            None => false,
+        };
+        if !valid {
+            return Err(format!(
+                "`{}` is not a <HOST>:<PORT>, [<IPV6>]:<PORT> or unix:<PATH> address",
+                value
+            ));
+        }
+        Ok(Self::Tcp(value.to_string()))
+    }
+}
+
+impl fmt::Display for ListenAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(address) => write!(f, "{}", address),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// PEM encoded files of the server certificate.
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    /// Certificate chain, starting with the server certificate.
+    pub certificate: PathBuf,
+    pub private_key: PathBuf,
+}
+
+/// Every problem found while loading the configuration.
+#[derive(Debug)]
+pub struct ConfigError {
+    pub errors: Vec<String>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid configuration:")?;
+        for error in &self.errors {
+            write!(f, "\n  - {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Layout of the configuration file, every value is optional.
+#[derive(Debug, Default)]
+struct ConfigFile {
+    server: ServerSection,
+    database: DatabaseSection,
+    storage: StorageSection,
+    archive: ArchiveSection,
+    auth: AuthSection,
+    oauth: OAuthSection,
+    translation: TranslationSection,
+    machine_translation: MachineTranslationSection,
+    rate_limits: RateLimitsSection,
+}
+
+/// Timeouts are in seconds in every section.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ServerSection {
+    host: Option<String>,
+    port: Option<u16>,
+    listen: Option<Vec<String>>,
+    #[serde(default)]
+    tls: TlsSection,
+    public_url: Option<String>,
+    workers: Option<usize>,
+    request_timeout: Option<u64>,
+    shutdown_timeout: Option<u64>,
+    trust_proxy_headers: Option<bool>,
+    cors_allowed_origins: Option<Vec<String>>,
+    log_level: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TlsSection {
+    certificate: Option<PathBuf>,
+    private_key: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DatabaseSection {
+    url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StorageSection {
+    archives_directory: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ArchiveSection {
+    max_simultaneous_downloads: Option<usize>,
+    download_timeout: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AuthSection {
+    session_ttl_days: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OAuthSection {
+    #[serde(default)]
+    github: OAuthProviderSection,
+    #[serde(default)]
+    discord: OAuthProviderSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OAuthProviderSection {
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    base_url: Option<String>,
+    api_base_url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TranslationSection {
+    auto_approve_score: Option<i32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MachineTranslationSection {
+    timeout: Option<u64>,
+    #[serde(default)]
+    deepl: MachineTranslatorSection,
+    #[serde(default)]
+    google_translate: MachineTranslatorSection,
+    #[serde(default)]
+    openai: MachineTranslatorSection,
+    #[serde(default)]
+    libretranslate: MachineTranslatorSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MachineTranslatorSection {
+    api_key: Option<String>,
+    base_url: Option<String>,
+    model: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RateLimitsSection {
+    #[serde(default)]
+    default: RateLimitSection,
+    #[serde(default)]
+    archive_search: RateLimitSection,
+    #[serde(default)]
+    archive_tasks: RateLimitSection,
+    #[serde(default)]
+    machine_translation: RateLimitSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RateLimitSection {
+    per_minute: Option<u32>,
+    burst: Option<u32>,
+}
+
+/// Collects the problems found while reading the file and the environment.
+#[derive(Default)]
+struct Loader {
+    errors: Vec<String>,
+}
+
+impl Loader {
+    /// Parses the environment variable if it is set.
+    fn var<T>(&mut self, name: &str) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let value = env::var(name).ok()?;
+        match value.trim().parse::<T>() {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.errors
+                    .push(format!("{}: invalid value `{}`: {}", name, value, err));
+                None
+            }
+        }
+    }
+
+    /// Parses the environment variable as a boolean if it is set, accepting
+    /// `true`/`false`, `1`/`0` and `yes`/`no`.
+    fn flag(&mut self, name: &str) -> Option<bool> {
+        let value = env::var(name).ok()?;
+        match value.trim().to_lowercase().as_str() {
+            "true" | "1" | "yes" => Some(true),
+            "false" | "0" | "no" => Some(false),
+            _ => {
+                self.errors.push(format!(
+                    "{}: invalid value `{}`: expected true, false, 1, 0, yes or no",
+                    name, value
+                ));
+                None
+            }
+        }
+    }
+
+    fn read_file(&mut self, path: &Path) -> ConfigFile {
+        let mut file = ConfigFile::default();
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) => {
+                self.errors
+                    .push(format!("{}: failed to read: {}", path.display(), err));
+                return file;
+            }
+        };
+        let table = match content.parse::<toml::Table>() {
+            Ok(table) => table,
+            Err(err) => {
+                self.errors.push(format!(
+                    "{}: {}",
+                    path.display(),
+                    err.to_string().trim_end()
+                ));
+                return file;
+            }
+        };
+
+        // Sections are read one by one, so a mistake in one does not hide the others.
+        for (name, value) in table {
+            match name.as_str() {
+                "server" => self.section(path, &name, value, &mut file.server),
+                "database" => self.section(path, &name, value, &mut file.database),
+                "storage" => self.section(path, &name, value, &mut file.storage),
+                "archive" => self.section(path, &name, value, &mut file.archive),
+                "auth" => self.section(path, &name, value, &mut file.auth),
+                "oauth" => self.section(path, &name, value, &mut file.oauth),
+                "translation" => self.section(path, &name, value, &mut file.translation),
+                "machine_translation" => {
+                    self.section(path, &name, value, &mut file.machine_translation)
+                }
+                "rate_limits" => self.section(path, &name, value, &mut file.rate_limits),
+                _ => self
+                    .errors
+                    .push(format!("{}: unknown section `{}`", path.display(), name)),
+            }
+        }
+
+        file
+    }
+
+    fn section<T: DeserializeOwned>(
+        &mut self,
+        path: &Path,
+        name: &str,
+        value: toml::Value,
+        section: &mut T,
+    ) {
+        match value.try_into::<T>() {
+            Ok(value) => *section = value,
+            Err(err) => {
+                self.errors
+                    .push(format!("{}: [{}] {}", path.display(), name, err.message()))
+            }
+        }
+    }
+
+    /// Loads `RATE_LIMIT_<GROUP>_PER_MINUTE` and `RATE_LIMIT_<GROUP>_BURST`,
+    /// a limit of 0 requests per minute disables rate limiting for the group.
+    fn rate_limit(&mut self, group: &str, file: RateLimitSection, default: RateLimit) -> RateLimit {
+        let per_minute = self.var(&format!("RATE_LIMIT_{}_PER_MINUTE", group));
+        let burst = self.var(&format!("RATE_LIMIT_{}_BURST", group));
+        let limit = RateLimit {
+            per_minute: per_minute.or(file.per_minute).unwrap_or(default.per_minute),
+            burst: burst.or(file.burst).unwrap_or(default.burst),
+        };
+
+        if limit.per_minute > 0 && limit.burst == 0 {
+            self.errors.push(format!(
+                "rate limit `{}`: burst must be at least 1, set the requests per minute to 0 to disable it",
+                group.to_lowercase()
+            ));
+        }
+        limit
+    }
+
+    /// Loads `<PREFIX>_CLIENT_ID` and `<PREFIX>_CLIENT_SECRET`, the provider is disabled
+    /// unless both are set. The base URLs can be overridden to use a local stand-in.
+    fn oauth(
+        &mut self,
+        prefix: &str,
+        file: OAuthProviderSection,
+        default_base_url: &str,
+        default_api_base_url: &str,
+    ) -> Option<OAuthClientConfig> {
+        let client_id = env::var(format!("{}_CLIENT_ID", prefix))
+            .ok()
+            .or(file.client_id);
+        let client_secret = env::var(format!("{}_CLIENT_SECRET", prefix))
+            .ok()
+            .or(file.client_secret);
+        let (client_id, client_secret) = match (client_id, client_secret) {
+            (Some(client_id), Some(client_secret)) => (client_id, client_secret),
+            (None, None) => return None,
+            _ => {
+                self.errors.push(format!(
+                    "{} OAuth: the client ID and the client secret must be set together",
+                    prefix.to_lowercase()
+                ));
+                return None;
+            }
+        };
+
+        let base_url = env::var(format!("{}_OAUTH_BASE_URL", prefix))
+            .ok()
+            .or(file.base_url)
+            .unwrap_or(default_base_url.to_string());
+        let api_base_url = env::var(format!("{}_API_BASE_URL", prefix))
+            .ok()
+            .or(file.api_base_url)
+            .unwrap_or(default_api_base_url.to_string());
+        self.check_url(
+            &format!("{} OAuth base URL", prefix.to_lowercase()),
+            &base_url,
+        );
+        self.check_url(
+            &format!("{} API base URL", prefix.to_lowercase()),
+            &api_base_url,
+        );
+
+        Some(OAuthClientConfig {
+            client_id,
+            client_secret,
+            base_url,
+            api_base_url,
+        })
+    }
+
+    /// Loads `<PREFIX>_API_KEY`, `<PREFIX>_BASE_URL` and `<PREFIX>_MODEL`. The engine is
+    /// enabled if a key is set, or if a base URL is set so a local server can be used
+    /// without a key. Engines without a default base URL need one.
+    fn machine_translator(
+        &mut self,
+        prefix: &str,
+        file: MachineTranslatorSection,
+        default_base_url: Option<&str>,
+        timeout: Duration,
+    ) -> Option<MachineTranslatorConfig> {
+        let api_key = env::var(format!("{}_API_KEY", prefix))
+            .ok()
+            .or(file.api_key);
+        let base_url = env::var(format!("{}_BASE_URL", prefix))
+            .ok()
+            .or(file.base_url);
+        if api_key.is_none() && base_url.is_none() {
+            return None;
+        }
+
+        let Some(base_url) = base_url.or(default_base_url.map(str::to_string)) else {
+            self.errors
+                .push(format!("{}: a base URL must be set", prefix.to_lowercase()));
+            return None;
+        };
+        self.check_url(&format!("{} base URL", prefix.to_lowercase()), &base_url);
+
+        Some(MachineTranslatorConfig {
+            api_key,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: env::var(format!("{}_MODEL", prefix)).ok().or(file.model),
+            timeout,
+        })
+    }
+
+    /// Loads `TLS_CERTIFICATE` and `TLS_PRIVATE_KEY`, TLS is disabled unless both
+    /// are set.
+    fn tls(&mut self, file: TlsSection) -> Option<TlsConfig> {
+        let certificate = env::var_os("TLS_CERTIFICATE")
+            .map(PathBuf::from)
+            .or(file.certificate);
+        let private_key = env::var_os("TLS_PRIVATE_KEY")
+            .map(PathBuf::from)
+            .or(file.private_key);
+
+        match (certificate, private_key) {
+            (Some(certificate), Some(private_key)) => {
+                let config = TlsConfig {
+                    certificate,
+                    private_key,
+                };
+                // Read now, so that invalid files are reported with the other errors.
+                if let Err(err) = load_rustls_config(&config) {
+                    self.errors.push(format!("TLS: {:#}", err));
+                }
+                Some(config)
+            }
+            (None, None) => None,
+            _ => {
+                self.errors.push(
+                    "TLS: the certificate and the private key must be set together".to_string(),
+                );
+                None
+            }
+        }
+    }
+
+    fn check_url(&mut self, name: &str, url: &str) {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            self.errors
+                .push(format!("{}: `{}` is not an HTTP or HTTPS URL", name, url));
+        }
+    }
+
+    fn check_positive(&mut self, name: &str, value: u64) {
+        if value == 0 {
+            self.errors.push(format!("{}: must be at least 1", name));
         }
     }
 }
 
 impl ServerConfig {
-    pub fn load() -> Self {
+    /// Loads the configuration file if given, then applies the environment variables
+    /// on top of it.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        let mut loader = Loader::default();
+        let file = match path {
+            Some(path) => loader.read_file(path),
+            None => ConfigFile::default(),
+        };
         let mut config = Self::default();
 
-        if let Ok(raw_port) = env::var("PORT") {
-            config.port = raw_port.parse::<u16>().expect("PORT must be a number")
+        let server = file.server;
+        if let Some(host) = loader.var::<String>("HOST").or(server.host) {
+            config.host = host;
+        }
+        if let Some(port) = loader.var("PORT").or(server.port) {
+            config.port = port;
+        }
+        let listen = match env::var("LISTEN") {
+            Ok(addresses) => Some(addresses.split(',').map(str::to_string).collect()),
+            Err(_) => server.listen,
+        };
+        config.listen = match listen {
+            Some(addresses) => addresses
+                .iter()
+                .map(|address| address.trim())
+                .filter(|address| !address.is_empty())
+                .filter_map(|address| {
+                    address
+                        .parse()
+                        .map_err(|err| loader.errors.push(format!("listen: {}", err)))
+                        .ok()
+                })
+                .collect(),
+            None => vec![ListenAddress::from_host(&config.host, config.port)],
+        };
+        config.tls = loader.tls(server.tls);
+
+        let scheme = if config.tls.is_some() {
+            "https"
+        } else {
+            "http"
+        };
+        config.public_url = match env::var("PUBLIC_URL").ok().or(server.public_url) {
+            Some(url) => url.trim_end_matches('/').to_string(),
+            None => format!("{}://localhost:{}", scheme, config.port),
+        };
+        config.workers = loader.var("WORKERS").or(server.workers);
+        if let Some(seconds) = loader
+            .var("REQUEST_TIMEOUT_SECONDS")
+            .or(server.request_timeout)
+        {
+            config.request_timeout = Duration::from_secs(seconds);
+        }
+        if let Some(seconds) = loader
+            .var("SHUTDOWN_TIMEOUT_SECONDS")
+            .or(server.shutdown_timeout)
+        {
+            config.shutdown_timeout = Duration::from_secs(seconds);
+        }
+        if let Some(trust) = loader
+            .flag("TRUST_PROXY_HEADERS")
+            .or(server.trust_proxy_headers)
+        {
+            config.trust_proxy_headers = trust;
+        }
+        if let Ok(origins) = env::var("CORS_ALLOWED_ORIGINS") {
+            config.cors_allowed_origins = origins.split(',').map(str::to_string).collect();
+        } else if let Some(origins) = server.cors_allowed_origins {
+            config.cors_allowed_origins = origins;
+        }
+        config.cors_allowed_origins = config
+            .cors_allowed_origins
+            .iter()
+            .map(|origin| origin.trim().trim_end_matches('/').to_string())
+            .filter(|origin| !origin.is_empty())
+            .collect();
+        if let Some(level) = env::var("RUST_LOG").ok().or(server.log_level) {
+            config.log_level = level;
         }
 
-        if let Ok(url) = env::var("DATABASE_URL") {
+        if let Some(url) = env::var("DATABASE_URL").ok().or(file.database.url) {
             config.database_url = url;
         }
 
-        if let Ok(amounts) = env::var("MAX_SIMULTANEOUS_DOWNLOADS") {
-            config.max_simultaneous_downloads = amounts
-                .parse::<usize>()
-                .expect("MAX_SIMULTANEOUS_DOWNLOADS must be a number")
+        if let Some(path) = env::var_os("ARCHIVES_DIRECTORY")
+            .map(PathBuf::from)
+            .or(file.storage.archives_directory)
+        {
+            config.archives_directory = path;
         }
 
-        config
+        if let Some(amount) = loader
+            .var("MAX_SIMULTANEOUS_DOWNLOADS")
+            .or(file.archive.max_simultaneous_downloads)
+        {
+            config.max_simultaneous_downloads = amount;
+        }
+        if let Some(seconds) = loader
+            .var("DOWNLOAD_TIMEOUT_SECONDS")
+            .or(file.archive.download_timeout)
+        {
+            config.download_timeout = Duration::from_secs(seconds);
+        }
+
+        if let Some(days) = loader
+            .var("SESSION_TTL_DAYS")
+            .or(file.auth.session_ttl_days)
+        {
+            config.session_ttl_days = days;
+        }
+
+        let oauth = file.oauth;
+        config.github_oauth = loader.oauth(
+            "GITHUB",
+            oauth.github,
+            "https://github.com",
+            "https://api.github.com",
+        );
+        config.discord_oauth = loader.oauth(
+            "DISCORD",
+            oauth.discord,
+            "https://discord.com",
+            "https://discord.com/api",
+        );
+
+        config.auto_approve_score = loader
+            .var("AUTO_APPROVE_SCORE")
+            .or(file.translation.auto_approve_score);
+
+        let machine = file.machine_translation;
+        let timeout = loader
+            .var("MACHINE_TRANSLATION_TIMEOUT_SECONDS")
+            .or(machine.timeout)
+            .unwrap_or(30);
+        loader.check_positive("machine translation timeout", timeout);
+        let timeout = Duration::from_secs(timeout);
+        config.deepl = loader.machine_translator(
+            "DEEPL",
+            machine.deepl,
+            Some("https://api-free.deepl.com"),
+            timeout,
+        );
+        config.google_translate = loader.machine_translator(
+            "GOOGLE_TRANSLATE",
+            machine.google_translate,
+            Some("https://translation.googleapis.com"),
+            timeout,
+        );
+        config.openai = loader.machine_translator(
+            "OPENAI",
+            machine.openai,
+            Some("https://api.openai.com/v1"),
+            timeout,
+        );
+        // There is no public instance usable without a key, so it must be configured.
+        config.libretranslate =
+            loader.machine_translator("LIBRETRANSLATE", machine.libretranslate, None, timeout);
+
+        let file_limits = file.rate_limits;
+        let limits = &mut config.rate_limits;
+        limits.default = loader.rate_limit("DEFAULT", file_limits.default, limits.default);
+        limits.archive_search = loader.rate_limit(
+            "ARCHIVE_SEARCH",
+            file_limits.archive_search,
+            limits.archive_search,
+        );
+        limits.archive_tasks = loader.rate_limit(
+            "ARCHIVE_TASKS",
+            file_limits.archive_tasks,
+            limits.archive_tasks,
+        );
+        limits.machine_translation = loader.rate_limit(
+            "MACHINE_TRANSLATION",
+            file_limits.machine_translation,
+            limits.machine_translation,
+        );
+
+        config.validate(&mut loader);
+        if loader.errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError {
+                errors: loader.errors,
+            })
+        }
+    }
+
+    fn validate(&self, loader: &mut Loader) {
+        for origin in &self.cors_allowed_origins {
+            if origin != "*" {
+                loader.check_url("CORS allowed origin", origin);
+            }
+        }
+        if self.host.trim().is_empty() {
+            loader.errors.push("host: must not be empty".to_string());
+        }
+        if self.listen.is_empty() {
+            loader
+                .errors
+                .push("listen: at least one address is required".to_string());
+        }
+        // Clients of a Unix socket have no address, they would all share one rate limit.
+        let has_unix_socket = self
+            .listen
+            .iter()
+            .any(|address| matches!(address, ListenAddress::Unix(_)));
+        if has_unix_socket && !self.trust_proxy_headers {
+            loader.errors.push(
+                "listen: Unix sockets require trust_proxy_headers, the reverse proxy in front \
+                 must set `Forwarded` or `X-Forwarded-For`"
+                    .to_string(),
+            );
+        }
+        if let Err(err) = EnvFilter::try_new(&self.log_level) {
+            loader
+                .errors
+                .push(format!("log level: `{}`: {}", self.log_level, err));
+        }
+        loader.check_url("public URL", &self.public_url);
+        if self.database_url.trim().is_empty() {
+            loader
+                .errors
+                .push("database URL: must not be empty".to_string());
+        }
+        if let Some(workers) = self.workers {
+            loader.check_positive("workers", workers as u64);
+        }
+        if self.archives_directory.is_file() {
+            loader.errors.push(format!(
+                "archives directory: {} is a file",
+                self.archives_directory.display()
+            ));
+        }
+        loader.check_positive(
+            "max simultaneous downloads",
+            self.max_simultaneous_downloads as u64,
+        );
+        loader.check_positive("download timeout", self.download_timeout.as_secs());
+        if self.session_ttl_days < 1 {
+            loader
+                .errors
+                .push("session TTL days: must be at least 1".to_string());
+        }
+    }
+
+    pub fn download_options(&self) -> DownloadOptions {
+        DownloadOptions {
+            max_simultaneous: self.max_simultaneous_downloads,
+            timeout: self.download_timeout,
+        }
     }
 }
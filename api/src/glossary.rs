@@ -0,0 +1,305 @@
+use actix_web::{delete, get, post, put, web, HttpResponse};
+use entity::{
+    account::user_role::Role,
+    entry::glossary_term::{self, PartOfSpeech},
+};
+use serde::{Deserialize, Serialize};
+use service::glossary::{
+    create_glossary_term, delete_glossary_term, find_duplicate_term, find_glossary_term,
+    list_glossary_terms, update_glossary_term, GlossaryFilter, GlossaryTermInput,
+};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{ApiError, ApiResult},
+    translation::{ensure_role, validate_language},
+    AppState,
+};
+
+const MAX_TERM_LENGTH: usize = 128;
+const MAX_TRANSLATION_LENGTH: usize = 256;
+const MAX_NOTE_LENGTH: usize = 2000;
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.service(list_terms);
+    cfg.service(create_term);
+    cfg.service(get_term);
+    cfg.service(update_term);
+    cfg.service(delete_term);
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GlossaryQuery {
+    language: Option<String>,
+    /// Only the terms scoped to the mod.
+    mod_id: Option<i32>,
+    /// Part of the term or its translation.
+    query: Option<String>,
+    page: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GlossaryResponse {
+    total_pages: u64,
+    #[schema(value_type = Vec<GlossaryTerm>)]
+    terms: Vec<glossary_term::Model>,
+}
+
+/// Lists the glossary terms ordered by term.
+///
+/// ### Query parameters
+/// * language: String (optional)
+/// * mod_id: i32 (optional)
+/// * query: String (optional)
+/// * page: u64 (optional)
+///
+/// ### Response
+/// * GlossaryResponse
+#[utoipa::path(
+    context_path = "/glossary",
+    tag = "glossary",
+    params(GlossaryQuery),
+    responses((status = 200, body = GlossaryResponse))
+)]
+#[get("")]
+pub async fn list_terms(
+    state: web::Data<AppState>,
+    query: web::Query<GlossaryQuery>,
+) -> ApiResult<web::Json<GlossaryResponse>> {
+    let query = query.into_inner();
+    let (total_pages, terms) = list_glossary_terms(
+        &state.db,
+        GlossaryFilter {
+            language: query.language,
+            mod_id: query.mod_id,
+            query: query.query,
+        },
+        query.page.unwrap_or(0),
+    )
+    .await?;
+
+    Ok(web::Json(GlossaryResponse { total_pages, terms }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GlossaryTermPayload {
+    /// The term as written in source values, matched regardless of case.
+    pub term: String,
+    /// Minecraft language code (e.g. `zh_tw`).
+    pub language: String,
+    pub translation: String,
+    #[schema(inline)]
+    pub part_of_speech: Option<PartOfSpeech>,
+    pub note: Option<String>,
+    /// The mod the term applies to, or all mods if not set.
+    pub mod_id: Option<i32>,
+}
+
+impl GlossaryTermPayload {
+    fn into_input(self) -> ApiResult<GlossaryTermInput> {
+        validate_language(&self.language)?;
+        let term = self.term.trim().to_string();
+        let translation = self.translation.trim().to_string();
+        if term.is_empty() || term.chars().count() > MAX_TERM_LENGTH {
+            return Err(ApiError::validation("Invalid term")
+                .with_details(format!("1 to {} characters", MAX_TERM_LENGTH)));
+        }
+        if translation.is_empty() || translation.chars().count() > MAX_TRANSLATION_LENGTH {
+            return Err(ApiError::validation("Invalid translation")
+                .with_details(format!("1 to {} characters", MAX_TRANSLATION_LENGTH)));
+        }
+        let note = self.note.filter(|note| !note.trim().is_empty());
+        if note
+            .as_ref()
+            .is_some_and(|note| note.chars().count() > MAX_NOTE_LENGTH)
+        {
+            return Err(ApiError::validation("Note is too long")
+                .with_details(format!("At most {} characters", MAX_NOTE_LENGTH)));
+        }
+
+        Ok(GlossaryTermInput {
+            term,
+            language: self.language,
+            translation,
+            part_of_speech: self.part_of_speech,
+            note,
+            mod_id: self.mod_id,
+        })
+    }
+}
+
+/// Adds a term to the glossary of a language, globally or for a mod.
+///
+/// ### Payload
+/// * term: String
+/// * language: String
+/// * translation: String
+/// * part_of_speech: PartOfSpeech (optional)
+/// * note: String (optional)
+/// * mod_id: i32 (optional)
+///
+/// ### Response
+/// * GlossaryTerm
+///
+/// ### Errors
+/// * 403: Not a proofreader of the language
+/// * 404: Mod not found
+/// * 409: The term is already in the glossary (details = ID of the existing term)
+/// * 422: Invalid language code, term, translation or note
+#[utoipa::path(
+    context_path = "/glossary",
+    tag = "glossary",
+    request_body = GlossaryTermPayload,
+    responses(
+        (status = 200, body = GlossaryTerm),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+        (status = 409, body = ApiError),
+        (status = 422, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[post("")]
+pub async fn create_term(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    payload: web::Json<GlossaryTermPayload>,
+) -> ApiResult<web::Json<glossary_term::Model>> {
+    let input = payload.into_inner().into_input()?;
+    ensure_role(&user, &Role::Proofreader, &input.language)?;
+    ensure_unique(&state, &input, None).await?;
+
+    let term = create_glossary_term(&state.db, input, user.user.id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Mod not found"))?;
+    Ok(web::Json(term))
+}
+
+/// Returns the glossary term with the given ID.
+///
+/// ### Response
+/// * GlossaryTerm
+///
+/// ### Errors
+/// * 404: Term not found
+#[utoipa::path(
+    context_path = "/glossary",
+    tag = "glossary",
+    params(("term_id" = i32, Path, description = "ID of the term")),
+    responses(
+        (status = 200, body = GlossaryTerm),
+        (status = 404, body = ApiError),
+    )
+)]
+#[get("/{term_id}")]
+pub async fn get_term(
+    state: web::Data<AppState>,
+    term_id: web::Path<i32>,
+) -> ApiResult<web::Json<glossary_term::Model>> {
+    let term = find_glossary_term(&state.db, *term_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Term not found"))?;
+    Ok(web::Json(term))
+}
+
+/// Replaces a glossary term. Moving it to another language requires being a
+/// proofreader of both languages.
+///
+/// ### Payload
+/// * term: String
+/// * language: String
+/// * translation: String
+/// * part_of_speech: PartOfSpeech (optional)
+/// * note: String (optional)
+/// * mod_id: i32 (optional)
+///
+/// ### Response
+/// * GlossaryTerm
+///
+/// ### Errors
+/// * 403: Not a proofreader of the language
+/// * 404: Term or mod not found
+/// * 409: The term is already in the glossary (details = ID of the existing term)
+/// * 422: Invalid language code, term, translation or note
+#[utoipa::path(
+    context_path = "/glossary",
+    tag = "glossary",
+    params(("term_id" = i32, Path, description = "ID of the term")),
+    request_body = GlossaryTermPayload,
+    responses(
+        (status = 200, body = GlossaryTerm),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+        (status = 409, body = ApiError),
+        (status = 422, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[put("/{term_id}")]
+pub async fn update_term(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    term_id: web::Path<i32>,
+    payload: web::Json<GlossaryTermPayload>,
+) -> ApiResult<web::Json<glossary_term::Model>> {
+    let input = payload.into_inner().into_input()?;
+    let term = find_glossary_term(&state.db, *term_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Term not found"))?;
+    ensure_role(&user, &Role::Proofreader, &term.language)?;
+    ensure_role(&user, &Role::Proofreader, &input.language)?;
+    ensure_unique(&state, &input, Some(term.id)).await?;
+
+    let term = update_glossary_term(&state.db, term, input)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Mod not found"))?;
+    Ok(web::Json(term))
+}
+
+/// Removes a term from the glossary.
+///
+/// ### Errors
+/// * 403: Not a proofreader of the language
+/// * 404: Term not found
+#[utoipa::path(
+    context_path = "/glossary",
+    tag = "glossary",
+    params(("term_id" = i32, Path, description = "ID of the term")),
+    responses(
+        (status = 204),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[delete("/{term_id}")]
+pub async fn delete_term(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    term_id: web::Path<i32>,
+) -> ApiResult<HttpResponse> {
+    let term = find_glossary_term(&state.db, *term_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Term not found"))?;
+    ensure_role(&user, &Role::Proofreader, &term.language)?;
+
+    delete_glossary_term(&state.db, term.id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+async fn ensure_unique(
+    state: &AppState,
+    input: &GlossaryTermInput,
+    exclude_id: Option<i32>,
+) -> ApiResult<()> {
+    match find_duplicate_term(&state.db, input, exclude_id).await? {
+        Some(existing) => {
+            Err(ApiError::conflict("The term is already in the glossary").with_details(existing.id))
+        }
+        None => Ok(()),
+    }
+}
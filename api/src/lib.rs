@@ -1,30 +1,48 @@
 mod archive;
-mod config;
+mod auth;
+pub mod config;
+mod error;
+mod glossary;
 mod minecraft_mod;
+mod openapi;
+mod rate_limit;
+mod stats;
+mod tls;
+mod translation;
+mod user;
+
+use std::io;
 
 use actix_cors::Cors;
 use actix_web::middleware;
 use actix_web::web::Data;
-use actix_web::{web, App, HttpResponse, HttpServer, Responder};
-use log::{info, warn};
+use actix_web::{web, App, HttpResponse, HttpServer};
+use entity::account::user_role::Role;
+use log::info;
 use service::initialize_database;
 use service::sea_orm::{Database, DatabaseConnection};
+use tracing_subscriber::EnvFilter;
 
-use crate::config::ServerConfig;
+use crate::auth::{create_oauth_providers, Authentication, OAuthProviders, RequireRole};
+use crate::config::{ListenAddress, ServerConfig};
+use crate::error::{ApiError, ApiResult};
+use crate::rate_limit::{RateLimitStore, RateLimiter};
+use crate::tls::load_rustls_config;
+use crate::translation::machine::{create_machine_translators, MachineTranslators};
 
 pub struct AppState {
     db: DatabaseConnection,
     config: ServerConfig,
+    oauth_providers: OAuthProviders,
+    machine_translators: MachineTranslators,
+    rate_limits: RateLimitStore,
 }
 
 #[actix_web::main]
-pub async fn start() -> std::io::Result<()> {
-    std::env::set_var("RUST_LOG", "debug");
-    tracing_subscriber::fmt::init();
-    if let Err(e) = dotenvy::dotenv() {
-        warn!("Failed to load .env file: {}", e);
-    }
-    let config = ServerConfig::load();
+pub async fn start(config: ServerConfig) -> std::io::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new(&config.log_level))
+        .init();
 
     info!("Connecting to database at {}", config.database_url);
     let db = Database::connect(&config.database_url)
@@ -38,27 +56,155 @@ pub async fn start() -> std::io::Result<()> {
     let app_state = web::Data::new(AppState {
         db,
         config: config.clone(),
+        oauth_providers: create_oauth_providers(&config),
+        machine_translators: create_machine_translators(&config),
+        rate_limits: RateLimitStore::default(),
     });
 
-    info!("Starting server at http://localhost:{}", config.port);
-    HttpServer::new(move || {
+    let mut server = HttpServer::new(move || {
         App::new()
             .app_data(Data::clone(&app_state))
-             .wrap(middleware::Logger::default())
-            .wrap(Cors::permissive())
+            .app_data(web::JsonConfig::default().error_handler(error::json_error_handler))
+            .app_data(web::QueryConfig::default().error_handler(error::query_error_handler))
+            .app_data(web::PathConfig::default().error_handler(error::path_error_handler))
+            .wrap(Authentication)
+            .wrap(middleware::Logger::default())
+            .wrap(cors(&app_state.config.cors_allowed_origins))
             .default_service(web::route().to(not_found))
-            .configure(init)
+            .configure(|cfg| init(cfg, &app_state.config))
     })
-    .bind(("127.0.0.1", config.port))?
-    .run()
-    .await
+    .client_request_timeout(config.request_timeout)
+    .shutdown_timeout(config.shutdown_timeout.as_secs());
+    if let Some(workers) = config.workers {
+        server = server.workers(workers);
+    }
+
+    let tls = match &config.tls {
+        Some(tls) => Some(load_rustls_config(tls).map_err(io::Error::other)?),
+        None => None,
+    };
+    for address in &config.listen {
+        server = match (address, &tls) {
+            (ListenAddress::Tcp(address), Some(tls)) => {
+                info!("Listening on https://{}", address);
+                server.bind_rustls_0_22(address, tls.clone())?
+            }
+            (ListenAddress::Tcp(address), None) => {
+                info!("Listening on http://{}", address);
+                server.bind(address)?
+            }
+            #[cfg(unix)]
+            (ListenAddress::Unix(path), _) => {
+                use std::os::unix::fs::FileTypeExt;
+
+                info!("Listening on unix:{}", path.display());
+                // A socket left behind by a previous run would prevent binding.
+                if std::fs::metadata(path).is_ok_and(|meta| meta.file_type().is_socket()) {
+                    std::fs::remove_file(path)?;
+                }
+                server.bind_uds(path)?
+            }
+            #[cfg(not(unix))]
+            (ListenAddress::Unix(_), _) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "Unix sockets are not supported on this platform",
+                ));
+            }
+        };
+    }
+
+    server.run().await
 }
 
-fn init(cfg: &mut web::ServiceConfig) {
-    cfg.service(web::scope("/archives").configure(archive::init));
-    cfg.service(web::scope("/mods").configure(minecraft_mod::init));
+fn cors(allowed_origins: &[String]) -> Cors {
+    let mut cors = Cors::default()
+        .allow_any_method()
+        .allow_any_header()
+        .expose_any_header()
+        .max_age(3600);
+    for origin in allowed_origins {
+        cors = if origin == "*" {
+            cors.allow_any_origin()
+        } else {
+            cors.allowed_origin(origin)
+        };
+    }
+    cors
+}
+
+fn init(cfg: &mut web::ServiceConfig, config: &ServerConfig) {
+    let limits = &config.rate_limits;
+    let default_limit = || RateLimiter::new("default", limits.default);
+
+    // Archiving triggers heavy downloads, so only trusted roles may start it.
+    // Mods are shared by every language, so a role for one language is not enough.
+    // Searches and tasks hit the provider API, so they are limited separately.
+    cfg.service(
+        web::scope("/archives")
+            .wrap(RequireRole::new(Role::Proofreader).globally().for_writes())
+            .wrap(RateLimiter::new("archive_tasks", limits.archive_tasks).for_writes())
+            .wrap(
+                RateLimiter::new("archive_search", limits.archive_search)
+                    .for_path("/archives/search"),
+            )
+            .wrap(default_limit())
+            .configure(archive::init),
+    );
+    cfg.service(
+        web::scope("/auth")
+            .wrap(default_limit())
+            .configure(auth::init),
+    );
+    // Must be registered before `/users` to take precedence.
+    cfg.service(
+        web::scope("/users/{user_id}/roles")
+            .wrap(RequireRole::new(Role::LanguageManager))
+            .wrap(default_limit())
+            .configure(user::role::init),
+    );
+    cfg.service(
+        web::scope("/users")
+            .wrap(default_limit())
+            .configure(user::init),
+    );
+    // Permissions per language are checked by the handlers, new users are translators
+    // of every language and the quality checks are open to anyone.
+    cfg.service(
+        web::scope("/translations")
+            .wrap(
+                RateLimiter::new("machine_translation", limits.machine_translation)
+                    .for_path("/translations/machine"),
+            )
+            .wrap(default_limit())
+            .configure(translation::init),
+    );
+    // Entry searches with machine previews are billed like machine translations.
+    cfg.service(
+        web::scope("/mods")
+            .wrap(RequireRole::new(Role::Proofreader).globally().for_writes())
+            .wrap(
+                RateLimiter::new("machine_translation", limits.machine_translation)
+                    .for_query_param("machine_engine"),
+            )
+            .wrap(default_limit())
+            .configure(minecraft_mod::init),
+    );
+    // Permissions per language are checked by the handlers.
+    cfg.service(
+        web::scope("/glossary")
+            .wrap(RequireRole::new(Role::Proofreader).for_writes())
+            .wrap(default_limit())
+            .configure(glossary::init),
+    );
+    cfg.service(
+        web::scope("/stats")
+            .wrap(default_limit())
+            .configure(stats::init),
+    );
+    cfg.configure(openapi::init);
 }
 
-async fn not_found() -> impl Responder {
-    HttpResponse::NotFound().body("Not found")
+async fn not_found() -> ApiResult<HttpResponse> {
+    Err(ApiError::not_found("Not found"))
 }
@@ -0,0 +1,211 @@
+use actix_web::{get, http::header, web, HttpResponse};
+use serde::Deserialize;
+use service::{
+    exchange::{export_entries, ExchangeFormat},
+    export::resource_pack::build_resource_pack,
+    minecraft::version::to_semver,
+};
+use utoipa::IntoParams;
+
+use crate::{
+    error::{ApiError, ApiResult, ResultExt},
+    translation::validate_language,
+    AppState,
+};
+
+/// Maximum number of mods exported into one resource pack.
+const MAX_EXPORTED_MODS: usize = 200;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ResourcePackQuery {
+    /// Language of the translations (e.g. `zh_tw`).
+    lang: String,
+    /// Stable game version the pack is made for (e.g. `1.20.1`).
+    game_version: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MultiResourcePackQuery {
+    /// Comma-separated IDs of the mods (e.g. `1,2,3`).
+    ids: String,
+    /// Language of the translations (e.g. `zh_tw`).
+    lang: String,
+    /// Stable game version the pack is made for (e.g. `1.20.1`).
+    game_version: String,
+}
+
+/// Exports the approved translations of the mod as a resource pack zip. Only translations
+/// of source values found in the releases for the game version are included, or in those
+/// for the closest archived game version.
+///
+/// ### Query parameters
+/// * lang: String
+/// * game_version: String
+///
+/// ### Response
+/// * Zip file
+///
+/// ### Errors
+/// * 404: Mod not found
+/// * 422: Invalid language code or game version
+#[utoipa::path(
+    context_path = "/mods",
+    tag = "mods",
+    params(("mod_id" = i32, Path, description = "ID of the mod"), ResourcePackQuery),
+    responses(
+        (status = 200, description = "Resource pack", content_type = "application/zip", body = Vec<u8>),
+        (status = 404, body = ApiError),
+        (status = 422, body = ApiError),
+    )
+)]
+#[get("/{mod_id}/export/resourcepack")]
+pub async fn export_resource_pack(
+    app_state: web::Data<AppState>,
+    mod_id: web::Path<i32>,
+    query: web::Query<ResourcePackQuery>,
+) -> ApiResult<HttpResponse> {
+    let file_name = format!("mod-{}-{}-{}.zip", mod_id, query.lang, query.game_version);
+    resource_pack_response(
+        &app_state,
+        &[*mod_id],
+        &query.lang,
+        &query.game_version,
+        file_name,
+    )
+    .await
+}
+
+/// Exports the approved translations of several mods as one resource pack zip,
+/// e.g. for all mods of a modpack.
+///
+/// ### Query parameters
+/// * ids: String
+/// * lang: String
+/// * game_version: String
+///
+/// ### Response
+/// * Zip file
+///
+/// ### Errors
+/// * 404: Mod not found
+/// * 422: Invalid mod IDs, language code or game version
+#[utoipa::path(
+    context_path = "/mods",
+    tag = "mods",
+    params(MultiResourcePackQuery),
+    responses(
+        (status = 200, description = "Resource pack", content_type = "application/zip", body = Vec<u8>),
+        (status = 404, body = ApiError),
+        (status = 422, body = ApiError),
+    )
+)]
+#[get("/export/resourcepack")]
+pub async fn export_multi_mod_resource_pack(
+    app_state: web::Data<AppState>,
+    query: web::Query<MultiResourcePackQuery>,
+) -> ApiResult<HttpResponse> {
+    let mut mod_ids = query
+        .ids
+        .split(',')
+        .map(|id| id.trim().parse::<i32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ApiError::validation("Invalid mod IDs").with_details(&query.ids))?;
+    mod_ids.sort_unstable();
+    mod_ids.dedup();
+    if mod_ids.len() > MAX_EXPORTED_MODS {
+        return Err(ApiError::validation(format!(
+            "At most {} mods can be exported at once",
+            MAX_EXPORTED_MODS
+        )));
+    }
+
+    let file_name = format!("mods-{}-{}.zip", query.lang, query.game_version);
+    resource_pack_response(
+        &app_state,
+        &mod_ids,
+        &query.lang,
+        &query.game_version,
+        file_name,
+    )
+    .await
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct EntriesExportQuery {
+    /// Language of the translations (e.g. `zh_tw`).
+    lang: String,
+    #[param(inline)]
+    format: ExchangeFormat,
+}
+
+/// Exports the entries of the mod with their approved translations for CAT tools,
+/// as XLIFF 2.0, gettext PO or CSV. The namespaces and game versions of each entry
+/// are included as notes.
+///
+/// ### Query parameters
+/// * lang: String
+/// * format: ExchangeFormat
+///
+/// ### Response
+/// * File in the format
+///
+/// ### Errors
+/// * 404: Mod not found
+/// * 422: Invalid language code or format
+#[utoipa::path(
+    context_path = "/mods",
+    tag = "mods",
+    params(("mod_id" = i32, Path, description = "ID of the mod"), EntriesExportQuery),
+    responses(
+        (status = 200, description = "Exported entries", body = String),
+        (status = 404, body = ApiError),
+        (status = 422, body = ApiError),
+    )
+)]
+#[get("/{mod_id}/export")]
+pub async fn export_mod_entries(
+    app_state: web::Data<AppState>,
+    mod_id: web::Path<i32>,
+    query: web::Query<EntriesExportQuery>,
+) -> ApiResult<HttpResponse> {
+    validate_language(&query.lang)?;
+
+    let data = export_entries(&app_state.db, *mod_id, &query.lang, query.format)
+        .await
+        .or_internal("Failed to export entries")?
+        .ok_or_else(|| ApiError::not_found("Mod not found"))?;
+
+    let file_name = format!("mod-{}-{}.{}", mod_id, query.lang, query.format.extension());
+    Ok(HttpResponse::Ok()
+        .content_type(query.format.content_type())
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", file_name),
+        ))
+        .body(data))
+}
+
+async fn resource_pack_response(
+    app_state: &AppState,
+    mod_ids: &[i32],
+    language: &str,
+    game_version: &str,
+    file_name: String,
+) -> ApiResult<HttpResponse> {
+    validate_language(language)?;
+    let game_version = to_semver(game_version)
+        .map_err(|_| ApiError::validation("Invalid game version").with_details(game_version))?;
+
+    let pack = build_resource_pack(&app_state.db, mod_ids, language, &game_version)
+        .await
+        .or_internal("Failed to build resource pack")?
+        .ok_or_else(|| ApiError::not_found("Mod not found"))?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", file_name),
+        ))
+        .body(pack))
+}
@@ -1,32 +1,234 @@
-mod search;
+pub(crate) mod export;
+pub(crate) mod search;
+pub(crate) mod variant;
 
-use actix_web::{get, web};
-use log::warn;
-use service::minecraft::metadata::{lookup_mod_metadata, ModMetadata};
+use actix_web::{get, patch, web};
+use entity::minecraft::minecraft_mod::ModStatus;
+use serde::Deserialize;
+use service::{
+    minecraft::metadata::{lookup_mod_metadata, update_mod_status, ModMetadata},
+    qa::report::{build_qa_report, QaReportEntry},
+    translation::{
+        outdated::{find_outdated_translations, OutdatedTranslation},
+        stats::TranslationStats,
+    },
+};
+use utoipa::{IntoParams, ToSchema};
 
-use crate::AppState;
+use crate::{
+    error::{ApiError, ApiResult, ResultExt},
+    translation::validate_language,
+    AppState,
+};
 
 pub fn init(cfg: &mut web::ServiceConfig) {
     cfg.service(search::search_mods);
     cfg.service(search::search_mod_entries);
+    cfg.service(variant::list_entry_variants);
+    cfg.service(variant::get_entry_value_for_version);
+    cfg.service(export::export_resource_pack);
+    cfg.service(export::export_multi_mod_resource_pack);
+    cfg.service(export::export_mod_entries);
     cfg.service(get_mod_metadata);
+    cfg.service(change_mod_status);
+    cfg.service(list_outdated_translations);
+    cfg.service(get_mod_stats);
+    cfg.service(get_qa_report);
 }
 
+#[utoipa::path(
+    context_path = "/mods",
+    tag = "mods",
+    params(("mod_id" = i32, Path, description = "ID of the mod")),
+    responses(
+        (status = 200, body = ModMetadata),
+        (status = 404, body = ApiError),
+        (status = 500, body = ApiError),
+    )
+)]
 #[get("/{mod_id}/metadata")]
 pub async fn get_mod_metadata(
     app_state: web::Data<AppState>,
     mod_id: web::Path<i32>,
-) -> actix_web::Result<web::Json<ModMetadata>> {
-    let result = lookup_mod_metadata(&app_state.db, *mod_id).await;
-
-    match result {
-        Ok(Some(metadata)) => Ok(web::Json(metadata)),
-        Ok(None) => Err(actix_web::error::ErrorNotFound("Mod not found")),
-        Err(err) => {
-            warn!("Failed to get mod metadata: {}", err);
-            Err(actix_web::error::ErrorInternalServerError(
-                "Failed to get mod metadata",
-            ))
-        }
+) -> ApiResult<web::Json<ModMetadata>> {
+    let metadata = lookup_mod_metadata(&app_state.db, *mod_id)
+        .await
+        .or_internal("Failed to get mod metadata")?;
+
+    match metadata {
+        Some(metadata) => Ok(web::Json(metadata)),
+        None => Err(ApiError::not_found("Mod not found")),
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ChangeStatusPayload {
+    #[schema(inline)]
+    pub status: ModStatus,
+}
+
+/// Changes the status of the mod, only proofreaders of all languages are allowed to.
+///
+/// ### Payload
+/// * status: ModStatus
+///
+/// ### Response
+/// * ModMetadata
+///
+/// ### Errors
+/// * 403: Insufficient permissions
+/// * 404: Mod not found
+#[utoipa::path(
+    context_path = "/mods",
+    tag = "mods",
+    params(("mod_id" = i32, Path, description = "ID of the mod")),
+    request_body = ChangeStatusPayload,
+    responses(
+        (status = 200, body = ModMetadata),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[patch("/{mod_id}/status")]
+pub async fn change_mod_status(
+    app_state: web::Data<AppState>,
+    mod_id: web::Path<i32>,
+    payload: web::Json<ChangeStatusPayload>,
+) -> ApiResult<web::Json<ModMetadata>> {
+    let metadata = update_mod_status(&app_state.db, *mod_id, payload.into_inner().status)
+        .await
+        .or_internal("Failed to change mod status")?;
+
+    match metadata {
+        Some(metadata) => Ok(web::Json(metadata)),
+        None => Err(ApiError::not_found("Mod not found")),
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct LanguageQuery {
+    /// Language of the translations (e.g. `zh_tw`).
+    lang: String,
+}
+
+/// Lists the live translations of the mod whose source string changed after they
+/// were made, so translators can fix them first.
+///
+/// ### Query parameters
+/// * lang: String
+///
+/// ### Response
+/// * Vec<OutdatedTranslation>
+///
+/// ### Errors
+/// * 422: Invalid language code
+#[utoipa::path(
+    context_path = "/mods",
+    tag = "mods",
+    params(("mod_id" = i32, Path, description = "ID of the mod"), LanguageQuery),
+    responses(
+        (status = 200, body = Vec<OutdatedTranslation>),
+        (status = 422, body = ApiError),
+    )
+)]
+#[get("/{mod_id}/outdated")]
+pub async fn list_outdated_translations(
+    app_state: web::Data<AppState>,
+    mod_id: web::Path<i32>,
+    query: web::Query<LanguageQuery>,
+) -> ApiResult<web::Json<Vec<OutdatedTranslation>>> {
+    validate_language(&query.lang)?;
+
+    let outdated = find_outdated_translations(&app_state.db, *mod_id, &query.lang).await?;
+    Ok(web::Json(outdated))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct StatsQuery {
+    /// Only the stats of this language (e.g. `zh_tw`).
+    lang: Option<String>,
+}
+
+/// Returns the translation progress of the mod for each language it has translations in.
+/// Stats are cached, so they may lag behind the latest changes for a moment.
+///
+/// ### Query parameters
+/// * lang: String (optional)
+///
+/// ### Response
+/// * Vec<TranslationStats>
+///
+/// ### Errors
+/// * 404: Mod not found
+/// * 422: Invalid language code
+#[utoipa::path(
+    context_path = "/mods",
+    tag = "mods",
+    params(("mod_id" = i32, Path, description = "ID of the mod"), StatsQuery),
+    responses(
+        (status = 200, body = Vec<TranslationStats>),
+        (status = 404, body = ApiError),
+        (status = 422, body = ApiError),
+    )
+)]
+#[get("/{mod_id}/stats")]
+pub async fn get_mod_stats(
+    app_state: web::Data<AppState>,
+    mod_id: web::Path<i32>,
+    query: web::Query<StatsQuery>,
+) -> ApiResult<web::Json<Vec<TranslationStats>>> {
+    if let Some(language) = &query.lang {
+        validate_language(language)?;
     }
+
+    let metadata = lookup_mod_metadata(&app_state.db, *mod_id)
+        .await
+        .or_internal("Failed to get mod metadata")?
+        .ok_or_else(|| ApiError::not_found("Mod not found"))?;
+    let stats = metadata
+        .stats
+        .into_iter()
+        .filter(|stats| {
+            query
+                .lang
+                .as_ref()
+                .is_none_or(|lang| &stats.language == lang)
+        })
+        .collect();
+
+    Ok(web::Json(stats))
+}
+
+/// Runs the quality checks on the live and submitted translations of the mod,
+/// returns the translations with issues such as missing placeholders.
+///
+/// ### Query parameters
+/// * lang: String
+///
+/// ### Response
+/// * Vec<QaReportEntry>
+///
+/// ### Errors
+/// * 422: Invalid language code
+#[utoipa::path(
+    context_path = "/mods",
+    tag = "mods",
+    params(("mod_id" = i32, Path, description = "ID of the mod"), LanguageQuery),
+    responses(
+        (status = 200, body = Vec<QaReportEntry>),
+        (status = 422, body = ApiError),
+    )
+)]
+#[get("/{mod_id}/qa")]
+pub async fn get_qa_report(
+    app_state: web::Data<AppState>,
+    mod_id: web::Path<i32>,
+    query: web::Query<LanguageQuery>,
+) -> ApiResult<web::Json<Vec<QaReportEntry>>> {
+    validate_language(&query.lang)?;
+
+    let report = build_qa_report(&app_state.db, *mod_id, &query.lang).await?;
+    Ok(web::Json(report))
 }
@@ -1,76 +1,137 @@
 use actix_web::{get, web};
+use entity::account::user_role::Role;
 use log::warn;
 use serde::{Deserialize, Serialize};
-use service::minecraft::{
-    metadata::ModMetadata,
-    search::{search_mod_entries_in_database, search_mods_in_database, TextEntry},
+use service::{
+    minecraft::{
+        metadata::ModMetadata,
+        search::{search_mod_entries_in_database, search_mods_in_database, TextEntry},
+    },
+    translation::machine::prefill_machine_previews,
 };
+use utoipa::{IntoParams, ToSchema};
 
-use crate::AppState;
+use crate::{
+    auth::AuthenticatedUser,
+    error::{ApiError, ApiResult, ResultExt},
+    translation::{ensure_role, machine::find_translator, validate_language},
+    AppState,
+};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
 pub struct SearchFilter {
     query: Option<String>,
     page: Option<u64>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SearchModResponse {
     total_pages: u64,
     mods: Vec<ModMetadata>,
 }
 
+#[utoipa::path(
+    context_path = "/mods",
+    tag = "mods",
+    params(SearchFilter),
+    responses(
+        (status = 200, body = SearchModResponse),
+        (status = 500, body = ApiError),
+    )
+)]
 #[get("/search")]
 pub async fn search_mods(
     app_state: web::Data<AppState>,
     filter: web::Query<SearchFilter>,
-) -> actix_web::Result<web::Json<SearchModResponse>> {
+) -> ApiResult<web::Json<SearchModResponse>> {
     let page = filter.page.unwrap_or(0);
-    let result = search_mods_in_database(&app_state.db, filter.query.clone(), page).await;
+    let (total_pages, mods) = search_mods_in_database(&app_state.db, filter.query.clone(), page)
+        .await
+        .or_internal("Failed to search mods")?;
 
-    match result {
-        Ok((total_pages, mods)) => Ok(web::Json(SearchModResponse { total_pages, mods })),
-        Err(err) => {
-            warn!("Failed to search mods: {}", err);
-            Err(actix_web::error::ErrorInternalServerError(
-                "Failed to search mods",
-            ))
-        }
-    }
+    Ok(web::Json(SearchModResponse { total_pages, mods }))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
 pub struct EntriesFilter {
     pub query: Option<String>,
+    /// Language of the preview translations (e.g. `zh_tw`).
+    pub lang: Option<String>,
+    /// Machine translation engine filling the previews of entries without an approved
+    /// translation, requires a language and being a translator of it. Counts against the
+    /// machine translation rate limit.
+    pub machine_engine: Option<String>,
     pub page: Option<u64>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SearchEntriesResponse {
     pub total_pages: u64,
     pub entries: Vec<TextEntry>,
 }
 
+#[utoipa::path(
+    context_path = "/mods",
+    tag = "mods",
+    params(("mod_id" = i32, Path, description = "ID of the mod"), EntriesFilter),
+    responses(
+        (status = 200, body = SearchEntriesResponse),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+        (status = 422, body = ApiError),
+        (status = 429, body = ApiError),
+        (status = 500, body = ApiError),
+    )
+)]
 #[get("/{mod_id}/entries")]
 pub async fn search_mod_entries(
     app_state: web::Data<AppState>,
+    user: Option<AuthenticatedUser>,
     mod_id: web::Path<i32>,
     filter: web::Query<EntriesFilter>,
-) -> actix_web::Result<web::Json<SearchEntriesResponse>> {
+) -> ApiResult<web::Json<SearchEntriesResponse>> {
+    if let Some(language) = &filter.lang {
+        validate_language(language)?;
+    }
+    let machine = match (&filter.machine_engine, &filter.lang) {
+        (Some(engine), Some(language)) => {
+            let user = user.ok_or_else(|| ApiError::unauthorized("Authentication required"))?;
+            ensure_role(&user, &Role::Translator, language)?;
+            Some((find_translator(&app_state, engine)?, language))
+        }
+        (Some(_), None) => {
+            return Err(ApiError::validation(
+                "A language is required for machine previews",
+            ))
+        }
+        _ => None,
+    };
+
     let page = filter.page.unwrap_or(0);
-    let result =
-        search_mod_entries_in_database(&app_state.db, *mod_id, filter.query.clone(), page).await;
+    let (total_pages, mut entries) = search_mod_entries_in_database(
+        &app_state.db,
+        *mod_id,
+        filter.query.clone(),
+        filter.lang.clone(),
+        page,
+    )
+    .await
+    .or_internal("Failed to search entries for specific mod")?;
 
-    match result {
-        Ok((total_pages, entries)) => Ok(web::Json(SearchEntriesResponse {
-            total_pages,
-            entries,
-        })),
-        Err(err) => {
-            warn!("Failed to search entries for specific mod: {}", err);
-            Err(actix_web::error::ErrorInternalServerError(
-                "Failed to search entries for specific mod",
-            ))
+    if let Some((translator, language)) = machine {
+        // Previews are a convenience, the entries are still useful without them.
+        if let Err(err) = prefill_machine_previews(translator, &mut entries, language).await {
+            warn!(
+                "Machine previews with {} failed: {:?}",
+                translator.name(),
+                err
+            );
         }
     }
+
+    Ok(web::Json(SearchEntriesResponse {
+        total_pages,
+        entries,
+    }))
 }
@@ -0,0 +1,107 @@
+use actix_web::{get, web};
+use entity::minecraft::mod_loader::ModLoader;
+use serde::Deserialize;
+use service::minecraft::{
+    variant::{
+        find_entry_value_for_version, find_entry_variants, TextEntryVariant, VersionedValue,
+    },
+    version::to_semver,
+};
+use utoipa::IntoParams;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    AppState,
+};
+
+/// Lists the distinct values of an entry across the archived releases of the mod, once per
+/// loader, with the game versions of the releases for that loader containing each value.
+///
+/// ### Response
+/// * Vec<TextEntryVariant>
+///
+/// ### Errors
+/// * 404: Entry not found
+#[utoipa::path(
+    context_path = "/mods",
+    tag = "mods",
+    params(
+        ("mod_id" = i32, Path, description = "ID of the mod"),
+        ("key" = String, Path, description = "Key of the entry"),
+    ),
+    responses(
+        (status = 200, body = Vec<TextEntryVariant>),
+        (status = 404, body = ApiError),
+    )
+)]
+#[get("/{mod_id}/entries/{key}/variants")]
+pub async fn list_entry_variants(
+    app_state: web::Data<AppState>,
+    path: web::Path<(i32, String)>,
+) -> ApiResult<web::Json<Vec<TextEntryVariant>>> {
+    let (mod_id, key) = path.into_inner();
+    let variants = find_entry_variants(&app_state.db, mod_id, &key).await?;
+
+    if variants.is_empty() {
+        return Err(ApiError::not_found("Entry not found"));
+    }
+    Ok(web::Json(variants))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct VersionQuery {
+    /// Stable game version (e.g. `1.16.5`).
+    game_version: String,
+    #[param(inline)]
+    loader: Option<ModLoader>,
+}
+
+/// Returns the value of an entry for a game version, e.g. to translate an older modpack.
+/// Falls back to the closest archived game version if the mod was not archived for it.
+///
+/// ### Query parameters
+/// * game_version: String
+/// * loader: ModLoader (optional)
+///
+/// ### Response
+/// * VersionedValue
+///
+/// ### Errors
+/// * 404: Entry not found for the loader
+/// * 422: Invalid game version
+#[utoipa::path(
+    context_path = "/mods",
+    tag = "mods",
+    params(
+        ("mod_id" = i32, Path, description = "ID of the mod"),
+        ("key" = String, Path, description = "Key of the entry"),
+        VersionQuery
+    ),
+    responses(
+        (status = 200, body = VersionedValue),
+        (status = 404, body = ApiError),
+        (status = 422, body = ApiError),
+    )
+)]
+#[get("/{mod_id}/entries/{key}/value")]
+pub async fn get_entry_value_for_version(
+    app_state: web::Data<AppState>,
+    path: web::Path<(i32, String)>,
+    query: web::Query<VersionQuery>,
+) -> ApiResult<web::Json<VersionedValue>> {
+    let (mod_id, key) = path.into_inner();
+    let game_version = to_semver(&query.game_version).map_err(|_| {
+        ApiError::validation("Invalid game version").with_details(&query.game_version)
+    })?;
+
+    let value = find_entry_value_for_version(
+        &app_state.db,
+        mod_id,
+        &key,
+        &game_version,
+        query.loader.as_ref(),
+    )
+    .await?
+    .ok_or_else(|| ApiError::not_found("Entry not found"))?;
+    Ok(web::Json(value))
+}
@@ -0,0 +1,232 @@
+use actix_web::{get, web, HttpResponse};
+use service::{
+    archive::{
+        modpack::{ModpackCoverage, ModpackTask},
+        resource::{ArchiveProvider, ArchiveResourceInfo},
+        task::{ArchiveTask, ArchiveTaskStage},
+    },
+    exchange::{ChangedSource, ExchangeFormat, FailedCheck, ImportReport},
+    glossary::matcher::GlossaryMatch,
+    minecraft::{
+        metadata::ModMetadata,
+        search::TextEntry,
+        variant::{TextEntryVariant, VersionedValue},
+    },
+    qa::{report::QaReportEntry, QaIssue, QaIssueKind, QaSeverity},
+    translation::{
+        machine::{MachineSuggestion, TranslationOrigin},
+        memory::MemoryMatch,
+        outdated::OutdatedTranslation,
+        stats::{GlobalStats, TranslationStats, TranslatorStats},
+    },
+};
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use crate::{
+    archive, auth,
+    error::{ApiError, ErrorCode},
+    glossary, minecraft_mod, stats, translation, user,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "RTranslator Backend"),
+    paths(
+        archive::search::search_resources,
+        archive::task::create_archive_task,
+        archive::task::list_archive_tasks,
+        archive::task::get_archive_task,
+        archive::task::stream_archive_task_events,
+        archive::upload::upload_mod_archives,
+        archive::vanilla::import_vanilla,
+        archive::modpack::import_modpack,
+        archive::modpack::list_imported_modpacks,
+        archive::modpack::get_modpack_archive_task,
+        archive::modpack::get_modpack,
+        auth::register,
+        auth::login,
+        auth::logout,
+        auth::oauth_authorize,
+        auth::oauth_callback,
+        auth::create_api_token,
+        auth::list_api_tokens,
+        auth::delete_api_token,
+        user::get_current_user,
+        user::role::list_user_roles,
+        user::role::grant_user_role,
+        user::role::revoke_user_role,
+        minecraft_mod::get_mod_metadata,
+        minecraft_mod::change_mod_status,
+        minecraft_mod::list_outdated_translations,
+        minecraft_mod::get_mod_stats,
+        minecraft_mod::get_qa_report,
+        minecraft_mod::search::search_mods,
+        minecraft_mod::search::search_mod_entries,
+        minecraft_mod::variant::list_entry_variants,
+        minecraft_mod::variant::get_entry_value_for_version,
+        minecraft_mod::export::export_resource_pack,
+        minecraft_mod::export::export_multi_mod_resource_pack,
+        minecraft_mod::export::export_mod_entries,
+        translation::list_translations,
+        translation::save_translation,
+        translation::submit_translation,
+        translation::check_translation,
+        translation::approve_translations,
+        translation::reject_translations,
+        translation::vote::list_ranked_suggestions,
+        translation::vote::vote_translation,
+        translation::vote::promote_top_suggestion,
+        translation::history::list_translation_history,
+        translation::history::revert_translation,
+        translation::memory::search_translation_memory,
+        translation::machine::list_machine_engines,
+        translation::machine::suggest_machine_translations,
+        glossary::list_terms,
+        glossary::create_term,
+        glossary::get_term,
+        glossary::update_term,
+        glossary::delete_term,
+        translation::exchange::import_translations,
+        stats::get_global_stats,
+    ),
+    components(schemas(
+        ApiError,
+        ErrorCode,
+        ArchiveProvider,
+        ArchiveResourceInfo,
+        ArchiveTask,
+        ArchiveTaskStage,
+        archive::task::CreateTaskPayload,
+        archive::task::ArchiveTaskInfo,
+        archive::vanilla::VanillaImportPayload,
+        archive::modpack::ModpackImportResult,
+        archive::modpack::ModpackInfo,
+        ModpackTask,
+        ModpackCoverage,
+        entity::minecraft::modpack::Model,
+        auth::RegisterPayload,
+        auth::LoginPayload,
+        auth::SessionResponse,
+        auth::CreateTokenPayload,
+        auth::CreatedTokenResponse,
+        entity::account::user::Model,
+        entity::account::access_token::Model,
+        entity::account::access_token::TokenKind,
+        entity::account::user_role::Model,
+        entity::account::user_role::Role,
+        user::role::GrantRolePayload,
+        minecraft_mod::ChangeStatusPayload,
+        ModMetadata,
+        TextEntry,
+        TextEntryVariant,
+        VersionedValue,
+        entity::minecraft::mod_loader::ModLoader,
+        OutdatedTranslation,
+        QaIssue,
+        QaIssueKind,
+        QaSeverity,
+        QaReportEntry,
+        FailedCheck,
+        MemoryMatch,
+        MachineSuggestion,
+        TranslationOrigin,
+        TranslationStats,
+        TranslatorStats,
+        GlobalStats,
+        ExchangeFormat,
+        ImportReport,
+        ChangedSource,
+        minecraft_mod::search::SearchModResponse,
+        minecraft_mod::search::SearchEntriesResponse,
+        entity::minecraft::minecraft_mod::Model,
+        entity::minecraft::minecraft_mod::ModStatus,
+        entity::minecraft::mod_provider::ModProviderType,
+        entity::entry::translation::Model,
+        entity::entry::translation::TranslationStatus,
+        entity::entry::translation_revision::Model,
+        entity::entry::translation_revision::RevisionAction,
+        translation::TranslationsResponse,
+        translation::SaveTranslationPayload,
+        translation::CheckTranslationPayload,
+        translation::ReviewPayload,
+        translation::vote::VotePayload,
+        translation::vote::PromotePayload,
+        entity::entry::glossary_term::Model,
+        entity::entry::glossary_term::PartOfSpeech,
+        glossary::GlossaryResponse,
+        glossary::GlossaryTermPayload,
+        GlossaryMatch,
+    )),
+    modifiers(&SecurityAddon),
+    tags(
+        (name = "archives", description = "Archive mods and modpacks from external providers or uploaded jars"),
+        (name = "auth", description = "Log in and manage access tokens"),
+        (name = "users", description = "User accounts"),
+        (name = "mods", description = "Browse archived mods and their entries"),
+        (name = "translations", description = "Translate entries and review translations"),
+        (name = "glossary", description = "Consistent renderings of terms per language"),
+        (name = "stats", description = "Translation progress"),
+    )
+)]
+pub struct ApiDoc;
+
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+        }
+    }
+}
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.service(openapi_json);
+    cfg.service(docs);
+}
+
+#[get("/openapi.json")]
+pub async fn openapi_json() -> web::Json<utoipa::openapi::OpenApi> {
+    web::Json(ApiDoc::openapi())
+}
+
+/// Interactive documentation rendered by Redoc from `/openapi.json`.
+#[get("/docs")]
+pub async fn docs() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(include_str!("../static/docs.html"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    /// Fails when the generated document drifts from the checked-in `openapi.json`.
+    /// Regenerate it with `UPDATE_OPENAPI_SNAPSHOT=1 cargo test -p api openapi` after
+    /// reviewing the changes.
+    #[test]
+    fn openapi_matches_snapshot() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("openapi.json");
+        let generated = ApiDoc::openapi().to_pretty_json().unwrap() + "\n";
+
+        if std::env::var_os("UPDATE_OPENAPI_SNAPSHOT").is_some() {
+            std::fs::write(&path, &generated).unwrap();
+            return;
+        }
+        let snapshot = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            generated == snapshot,
+            "The OpenAPI document differs from {}, regenerate it with UPDATE_OPENAPI_SNAPSHOT=1",
+            path.display()
+        );
+    }
+}
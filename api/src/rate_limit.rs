@@ -0,0 +1,262 @@
+use std::{
+    collections::HashMap,
+    future::{ready, Ready},
+    rc::Rc,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{
+        header::{self, HeaderValue},
+        Method,
+    },
+    web, HttpMessage,
+};
+use futures_util::future::LocalBoxFuture;
+use serde_json::json;
+
+use crate::{auth::AuthenticatedUser, error::ApiError, AppState};
+
+/// Buckets untouched for this long are full again and can be dropped.
+const IDLE_BUCKET_LIFETIME: Duration = Duration::from_secs(10 * 60);
+const MAX_BUCKETS_BEFORE_PRUNE: usize = 10_000;
+
+/// A token bucket limit, `per_minute` tokens are refilled every minute up to `burst`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub per_minute: u32,
+    pub burst: u32,
+}
+
+impl RateLimit {
+    pub const fn new(per_minute: u32, burst: u32) -> Self {
+        Self { per_minute, burst }
+    }
+
+    fn is_disabled(&self) -> bool {
+        self.per_minute == 0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimits {
+    /// Applied to every API request.
+    pub default: RateLimit,
+    /// Searching the archive providers, each search hits the provider API.
+    pub archive_search: RateLimit,
+    /// Creating archive tasks, each task downloads every version of a mod.
+    pub archive_tasks: RateLimit,
+    /// Machine translation, each request is billed by the engine.
+    pub machine_translation: RateLimit,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            default: RateLimit::new(300, 60),
+            archive_search: RateLimit::new(30, 10),
+            archive_tasks: RateLimit::new(5, 3),
+            machine_translation: RateLimit::new(60, 10),
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token buckets of all route groups, shared by all workers.
+#[derive(Default)]
+pub struct RateLimitStore {
+    buckets: Mutex<HashMap<(&'static str, String), Bucket>>,
+}
+
+impl RateLimitStore {
+    /// Takes a token from the bucket of every client key, returns how long to wait
+    /// if one of the buckets is empty. No token is taken unless all buckets have one.
+    fn acquire(
+        &self,
+        group: &'static str,
+        clients: Vec<String>,
+        limit: RateLimit,
+    ) -> Option<Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() > MAX_BUCKETS_BEFORE_PRUNE {
+            buckets.retain(|_, bucket| bucket.last_refill.elapsed() < IDLE_BUCKET_LIFETIME);
+        }
+
+        let now = Instant::now();
+        let refill_rate = limit.per_minute as f64 / 60.0;
+        let mut wait: Option<Duration> = None;
+        for client in &clients {
+            let bucket = buckets.entry((group, client.clone())).or_insert(Bucket {
+                tokens: limit.burst as f64,
+                last_refill: now,
+            });
+
+            let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * refill_rate).min(limit.burst as f64);
+            bucket.last_refill = now;
+
+            if bucket.tokens < 1.0 {
+                let bucket_wait = Duration::from_secs_f64((1.0 - bucket.tokens) / refill_rate);
+                wait = Some(wait.map_or(bucket_wait, |wait| wait.max(bucket_wait)));
+            }
+        }
+
+        if wait.is_none() {
+            for client in clients {
+                if let Some(bucket) = buckets.get_mut(&(group, client)) {
+                    bucket.tokens -= 1.0;
+                }
+            }
+        }
+        wait
+    }
+}
+
+/// Limits the requests of each client to a route group with a token bucket.
+/// Every client is limited per IP address, authenticated users are limited per user
+/// as well so that neither many accounts nor many addresses get around the limit.
+#[derive(Clone)]
+pub struct RateLimiter {
+    group: &'static str,
+    limit: RateLimit,
+    methods: Option<Vec<Method>>,
+    path_prefix: Option<&'static str>,
+    query_param: Option<&'static str>,
+}
+
+impl RateLimiter {
+    pub fn new(group: &'static str, limit: RateLimit) -> Self {
+        Self {
+            group,
+            limit,
+            methods: None,
+            path_prefix: None,
+            query_param: None,
+        }
+    }
+
+    /// Only limits requests with one of the methods.
+    pub fn for_methods(mut self, methods: &[Method]) -> Self {
+        self.methods = Some(methods.to_vec());
+        self
+    }
+
+    /// Only limits requests that are not read-only.
+    pub fn for_writes(self) -> Self {
+        self.for_methods(&[Method::POST, Method::PUT, Method::PATCH, Method::DELETE])
+    }
+
+    /// Only limits requests whose path starts with the prefix.
+    pub fn for_path(mut self, prefix: &'static str) -> Self {
+        self.path_prefix = Some(prefix);
+        self
+    }
+
+    /// Only limits requests with a non-empty value for the query parameter.
+    pub fn for_query_param(mut self, name: &'static str) -> Self {
+        self.query_param = Some(name);
+        self
+    }
+
+    fn applies_to(&self, req: &ServiceRequest) -> bool {
+        let method_matches = self
+            .methods
+            .as_ref()
+            .is_none_or(|methods| methods.contains(req.method()));
+        let path_matches = self
+            .path_prefix
+            .is_none_or(|prefix| req.path().starts_with(prefix));
+        let query_matches = self.query_param.is_none_or(|name| {
+            web::Query::<HashMap<String, String>>::from_query(req.query_string())
+                .is_ok_and(|query| query.get(name).is_some_and(|value| !value.is_empty()))
+        });
+
+        !self.limit.is_disabled() && method_matches && path_matches && query_matches
+    }
+
+    fn client_keys(req: &ServiceRequest, trust_proxy_headers: bool) -> Vec<String> {
+        let address = if trust_proxy_headers {
+            req.connection_info()
+                .realip_remote_addr()
+                .map(|addr| addr.to_string())
+        } else {
+            req.peer_addr().map(|addr| addr.ip().to_string())
+        };
+        let mut keys = vec![format!("ip:{}", address.unwrap_or_default())];
+
+        if let Some(user) = req.extensions().get::<AuthenticatedUser>() {
+            keys.push(format!("user:{}", user.user.id));
+        }
+        keys
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimiter
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Transform = RateLimiterMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimiterMiddleware {
+            service: Rc::new(service),
+            limiter: self.clone(),
+        }))
+    }
+}
+
+pub struct RateLimiterMiddleware<S> {
+    service: Rc<S>,
+    limiter: RateLimiter,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimiterMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let state = req.app_data::<web::Data<AppState>>().cloned();
+
+        if let (true, Some(state)) = (self.limiter.applies_to(&req), state) {
+            let clients = RateLimiter::client_keys(&req, state.config.trust_proxy_headers);
+            let wait = state
+                .rate_limits
+                .acquire(self.limiter.group, clients, self.limiter.limit);
+
+            if let Some(wait) = wait {
+                let retry_after = wait.as_secs_f64().ceil() as u64;
+                let err = ApiError::too_many_requests("Too many requests")
+                    .with_details(json!({ "retry_after_seconds": retry_after }));
+
+                let mut res = req.error_response(err);
+                res.headers_mut().insert(
+                    header::RETRY_AFTER,
+                    HeaderValue::from_str(&retry_after.to_string()).unwrap(),
+                );
+                return Box::pin(async move { Ok(res.map_into_right_body()) });
+            }
+        }
+
+        let service = Rc::clone(&self.service);
+        Box::pin(async move { service.call(req).await.map(|res| res.map_into_left_body()) })
+    }
+}
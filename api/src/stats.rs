@@ -0,0 +1,24 @@
+use actix_web::{get, web};
+use service::translation::stats::{find_global_stats, GlobalStats};
+
+use crate::{error::ApiResult, AppState};
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_global_stats);
+}
+
+/// Returns the translation progress across all mods, with the languages ranked by
+/// approved entries and the translators by approved translations. The stats are
+/// recomputed every few minutes.
+///
+/// ### Response
+/// * GlobalStats
+#[utoipa::path(
+    context_path = "/stats",
+    tag = "stats",
+    responses((status = 200, body = GlobalStats))
+)]
+#[get("")]
+pub async fn get_global_stats(app_state: web::Data<AppState>) -> ApiResult<web::Json<GlobalStats>> {
+    Ok(web::Json(find_global_stats(&app_state.db).await?))
+}
@@ -0,0 +1,29 @@
+use std::{fs::File, io::BufReader, path::Path};
+
+use anyhow::Context;
+
+use crate::config::TlsConfig;
+
+/// Reads the PEM encoded certificate chain and private key of the server.
+pub fn load_rustls_config(config: &TlsConfig) -> anyhow::Result<rustls::ServerConfig> {
+    let certificates = rustls_pemfile::certs(&mut open(&config.certificate)?)
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Failed to read {}", config.certificate.display()))?;
+    if certificates.is_empty() {
+        anyhow::bail!("No certificate found in {}", config.certificate.display());
+    }
+
+    let private_key = rustls_pemfile::private_key(&mut open(&config.private_key)?)
+        .with_context(|| format!("Failed to read {}", config.private_key.display()))?
+        .with_context(|| format!("No private key found in {}", config.private_key.display()))?;
+
+    rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certificates, private_key)
+        .context("Invalid certificate or private key")
+}
+
+fn open(path: &Path) -> anyhow::Result<BufReader<File>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    Ok(BufReader::new(file))
+}
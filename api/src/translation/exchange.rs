@@ -0,0 +1,96 @@
+use actix_web::{post, web};
+use entity::account::user_role::Role;
+use serde::Deserialize;
+use service::exchange::{
+    import_entries, parse_entries, ExchangeFormat, ImportOptions, ImportReport,
+};
+use utoipa::IntoParams;
+
+use super::{ensure_role, validate_language};
+use crate::{
+    auth::AuthenticatedUser,
+    error::{ApiError, ApiResult},
+    AppState,
+};
+
+/// Maximum size of an imported file.
+pub(crate) const MAX_IMPORT_SIZE: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ImportQuery {
+    /// Language of the translations (e.g. `zh_tw`).
+    lang: String,
+    #[param(inline)]
+    format: ExchangeFormat,
+    /// Submit the imported translations for review instead of saving drafts.
+    #[serde(default)]
+    submit: bool,
+    /// Only validate the file and report what would be imported.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+/// Imports translations of the mod's entries from a file exported for CAT tools,
+/// the file is the request body. Translations are saved for the current user.
+///
+/// Keys that are not entries of the mod and entries whose source changed since the
+/// export are reported instead of imported.
+///
+/// ### Query parameters
+/// * lang: String
+/// * format: ExchangeFormat
+/// * submit: bool (optional, defaults to false)
+/// * dry_run: bool (optional, defaults to false)
+///
+/// ### Response
+/// * ImportReport
+///
+/// ### Errors
+/// * 403: Not a translator of the language
+/// * 404: Mod not found
+/// * 422: Invalid file (details = parse error), language code or format
+#[utoipa::path(
+    context_path = "/translations/import",
+    tag = "translations",
+    params(("mod_id" = i32, Path, description = "ID of the mod"), ImportQuery),
+    request_body(content = String, description = "File in the format"),
+    responses(
+        (status = 200, body = ImportReport),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+        (status = 422, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[post("/{mod_id}")]
+pub async fn import_translations(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    mod_id: web::Path<i32>,
+    query: web::Query<ImportQuery>,
+    body: web::Bytes,
+) -> ApiResult<web::Json<ImportReport>> {
+    validate_language(&query.lang)?;
+    ensure_role(&user, &Role::Translator, &query.lang)?;
+
+    let entries = parse_entries(query.format, &body)
+        .map_err(|err| ApiError::validation("Invalid import file").with_details(err.to_string()))?;
+
+    let options = ImportOptions {
+        submit: query.submit,
+        dry_run: query.dry_run,
+    };
+    let report = import_entries(
+        &state.db,
+        *mod_id,
+        &query.lang,
+        user.user.id,
+        entries,
+        options,
+    )
+    .await?
+    .ok_or_else(|| ApiError::not_found("Mod not found"))?;
+
+    Ok(web::Json(report))
+}
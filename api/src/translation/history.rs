@@ -0,0 +1,93 @@
+use actix_web::{get, post, web};
+use entity::{
+    account::user_role::Role,
+    entry::{translation, translation_revision},
+};
+use serde::Deserialize;
+use service::translation::history::{find_revision, list_history, revert_to_revision};
+use utoipa::IntoParams;
+
+use super::{ensure_passes_qa, ensure_role, validate_language};
+use crate::{
+    auth::AuthenticatedUser,
+    error::{ApiError, ApiResult},
+    AppState,
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct HistoryQuery {
+    /// Key of the text entry.
+    key: String,
+    language: String,
+}
+
+/// Lists every change to the translations of a text entry in a language, the newest first:
+/// who changed it, when, and from which value.
+///
+/// ### Query parameters
+/// * key: String
+/// * language: String
+///
+/// ### Response
+/// * Vec<TranslationRevision>
+///
+/// ### Errors
+/// * 422: Invalid language code
+#[utoipa::path(
+    context_path = "/translations",
+    tag = "translations",
+    params(HistoryQuery),
+    responses(
+        (status = 200, body = Vec<TranslationRevision>),
+        (status = 422, body = ApiError),
+    )
+)]
+#[get("/history")]
+pub async fn list_translation_history(
+    state: web::Data<AppState>,
+    query: web::Query<HistoryQuery>,
+) -> ApiResult<web::Json<Vec<translation_revision::Model>>> {
+    validate_language(&query.language)?;
+
+    let history = list_history(&state.db, &query.key, &query.language).await?;
+    Ok(web::Json(history))
+}
+
+/// Makes the value of a previous revision the live translation again, e.g. to undo
+/// vandalism. The revert is approved immediately and recorded in the history.
+///
+/// ### Response
+/// * Translation: the new live translation
+///
+/// ### Errors
+/// * 403: Not a proofreader of the language
+/// * 404: Revision or text entry not found
+/// * 422: The revision fails the quality checks against the current source (details = issues)
+#[utoipa::path(
+    context_path = "/translations",
+    tag = "translations",
+    params(("revision_id" = i32, Path, description = "ID of the revision")),
+    responses(
+        (status = 200, body = Translation),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+        (status = 422, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[post("/revisions/{revision_id}/revert")]
+pub async fn revert_translation(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    revision_id: web::Path<i32>,
+) -> ApiResult<web::Json<translation::Model>> {
+    let revision = find_revision(&state.db, *revision_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Revision not found"))?;
+    ensure_role(&user, &Role::Proofreader, &revision.language)?;
+    ensure_passes_qa(&state.db, &revision.key, &revision.value).await?;
+
+    let translation = revert_to_revision(&state.db, revision, user.user.id).await?;
+    Ok(web::Json(translation))
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use actix_web::{get, web};
+use entity::account::user_role::Role;
+use log::warn;
+use serde::Deserialize;
+use service::translation::machine::{
+    machine_translate_entry, DeepL, GoogleTranslate, LibreTranslate, MachineSuggestion,
+    MachineTranslator, OpenAiCompatible,
+};
+use utoipa::IntoParams;
+
+use super::{ensure_role, validate_language};
+use crate::{
+    auth::AuthenticatedUser,
+    config::ServerConfig,
+    error::{ApiError, ApiResult},
+    AppState,
+};
+
+pub type MachineTranslators = HashMap<&'static str, Box<dyn MachineTranslator>>;
+
+/// Creates the machine translation engines enabled in the config.
+pub fn create_machine_translators(config: &ServerConfig) -> MachineTranslators {
+    let mut translators: Vec<Box<dyn MachineTranslator>> = Vec::new();
+
+    if let Some(deepl) = &config.deepl {
+        translators.push(Box::new(DeepL::new(deepl.clone())));
+    }
+    if let Some(google) = &config.google_translate {
+        translators.push(Box::new(GoogleTranslate::new(google.clone())));
+    }
+    if let Some(openai) = &config.openai {
+        translators.push(Box::new(OpenAiCompatible::new(openai.clone())));
+    }
+    if let Some(libretranslate) = &config.libretranslate {
+        translators.push(Box::new(LibreTranslate::new(libretranslate.clone())));
+    }
+
+    translators
+        .into_iter()
+        .map(|translator| (translator.name(), translator))
+        .collect()
+}
+
+/// Returns the enabled engine with the given name.
+pub(crate) fn find_translator<'a>(
+    state: &'a AppState,
+    engine: &str,
+) -> ApiResult<&'a dyn MachineTranslator> {
+    state
+        .machine_translators
+        .get(engine)
+        .map(|translator| translator.as_ref())
+        .ok_or_else(|| {
+            ApiError::not_found("Machine translation engine not found").with_details(engine)
+        })
+}
+
+/// Lists the names of the enabled machine translation engines.
+///
+/// ### Response
+/// * Vec<String>
+#[utoipa::path(
+    context_path = "/translations",
+    tag = "translations",
+    responses((status = 200, body = Vec<String>))
+)]
+#[get("/machine/engines")]
+pub async fn list_machine_engines(state: web::Data<AppState>) -> web::Json<Vec<&'static str>> {
+    let mut engines: Vec<&'static str> = state.machine_translators.keys().copied().collect();
+    engines.sort();
+    web::Json(engines)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MachineQuery {
+    /// Key of the text entry.
+    key: String,
+    language: String,
+    /// Name of the engine to use, all enabled engines are used if not set.
+    engine: Option<String>,
+}
+
+/// Machine translates the source value of a text entry. The suggestions are
+/// cached and marked as machine made, they still have to be saved, submitted
+/// and reviewed like any other translation.
+///
+/// ### Query parameters
+/// * key: String
+/// * language: String
+/// * engine: String (optional)
+///
+/// ### Response
+/// * Vec<MachineSuggestion>: one per engine that succeeded
+///
+/// ### Errors
+/// * 403: Not a translator of the language
+/// * 404: Text entry or engine not found
+/// * 422: Invalid language code
+/// * 502: Every engine failed
+#[utoipa::path(
+    context_path = "/translations",
+    tag = "translations",
+    params(MachineQuery),
+    responses(
+        (status = 200, body = Vec<MachineSuggestion>),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+        (status = 422, body = ApiError),
+        (status = 502, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[get("/machine")]
+pub async fn suggest_machine_translations(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    query: web::Query<MachineQuery>,
+) -> ApiResult<web::Json<Vec<MachineSuggestion>>> {
+    validate_language(&query.language)?;
+    ensure_role(&user, &Role::Translator, &query.language)?;
+
+    let translators: Vec<&dyn MachineTranslator> = match &query.engine {
+        Some(engine) => vec![find_translator(&state, engine)?],
+        None => {
+            let mut translators: Vec<_> = state
+                .machine_translators
+                .values()
+                .map(|translator| translator.as_ref())
+                .collect();
+            translators.sort_by_key(|translator| translator.name());
+            translators
+        }
+    };
+    if translators.is_empty() {
+        return Err(ApiError::not_found(
+            "No machine translation engine is enabled",
+        ));
+    }
+
+    let results = machine_translate_entry(&state.db, &translators, &query.key, &query.language)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Text entry not found"))?;
+
+    let mut suggestions = Vec::new();
+    for (translator, result) in translators.iter().zip(results) {
+        match result {
+            Ok(suggestion) => suggestions.push(suggestion),
+            Err(err) => warn!(
+                "Machine translation with {} failed: {:?}",
+                translator.name(),
+                err
+            ),
+        }
+    }
+    if suggestions.is_empty() {
+        return Err(ApiError::upstream_failed("Machine translation failed"));
+    }
+    Ok(web::Json(suggestions))
+}
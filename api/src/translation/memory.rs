@@ -0,0 +1,64 @@
+use actix_web::{get, web};
+use serde::Deserialize;
+use service::translation::memory::{find_memory_matches, MemoryMatch, MAX_SOURCE_LENGTH};
+use utoipa::IntoParams;
+
+use super::validate_language;
+use crate::{
+    error::{ApiError, ApiResult},
+    AppState,
+};
+
+const DEFAULT_MEMORY_MATCHES: usize = 5;
+const MAX_MEMORY_MATCHES: usize = 20;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MemoryQuery {
+    /// The string to find similar translated strings for.
+    source: String,
+    language: String,
+    /// At most 20, defaults to 5.
+    limit: Option<usize>,
+}
+
+/// Searches the translation memory, made of the approved translations of all mods,
+/// for strings similar to the source. The most similar matches come first.
+///
+/// ### Query parameters
+/// * source: String
+/// * language: String
+/// * limit: usize (optional)
+///
+/// ### Response
+/// * Vec<MemoryMatch>
+///
+/// ### Errors
+/// * 422: Invalid language code, or the source is too long
+#[utoipa::path(
+    context_path = "/translations",
+    tag = "translations",
+    params(MemoryQuery),
+    responses(
+        (status = 200, body = Vec<MemoryMatch>),
+        (status = 422, body = ApiError),
+    )
+)]
+#[get("/memory")]
+pub async fn search_translation_memory(
+    state: web::Data<AppState>,
+    query: web::Query<MemoryQuery>,
+) -> ApiResult<web::Json<Vec<MemoryMatch>>> {
+    validate_language(&query.language)?;
+    if query.source.chars().count() > MAX_SOURCE_LENGTH {
+        return Err(ApiError::validation("Source is too long")
+            .with_details(format!("At most {} characters", MAX_SOURCE_LENGTH)));
+    }
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_MEMORY_MATCHES)
+        .clamp(1, MAX_MEMORY_MATCHES);
+    let matches =
+        find_memory_matches(&state.db, &query.source, &query.language, None, limit).await?;
+    Ok(web::Json(matches))
+}
@@ -0,0 +1,474 @@
+pub(crate) mod exchange;
+pub(crate) mod history;
+pub(crate) mod machine;
+pub(crate) mod memory;
+pub(crate) mod vote;
+
+use actix_web::{get, post, web};
+use entity::{
+    account::user_role::Role,
+    entry::translation::{self, TranslationStatus},
+};
+use serde::{Deserialize, Serialize};
+use service::{
+    qa::{has_errors, report::check_entry_translation, QaIssue},
+    sea_orm::DatabaseConnection,
+    translation::{
+        edit::{self, find_translation, find_translations, TranslationFilter},
+        review::{review_translations, ReviewDecision},
+    },
+};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{ApiError, ApiResult},
+    AppState,
+};
+
+/// Maximum number of translations reviewed in one request.
+const MAX_REVIEW_BATCH: usize = 500;
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.service(vote::list_ranked_suggestions);
+    cfg.service(vote::promote_top_suggestion);
+    cfg.service(list_translations);
+    cfg.service(save_translation);
+    cfg.service(submit_translation);
+    cfg.service(check_translation);
+    cfg.service(approve_translations);
+    cfg.service(reject_translations);
+    cfg.service(vote::vote_translation);
+    cfg.service(history::list_translation_history);
+    cfg.service(history::revert_translation);
+    cfg.service(memory::search_translation_memory);
+    cfg.service(machine::list_machine_engines);
+    cfg.service(machine::suggest_machine_translations);
+    // Only imports accept files larger than the default payload limit.
+    cfg.service(
+        web::scope("/import")
+            .app_data(web::PayloadConfig::new(exchange::MAX_IMPORT_SIZE))
+            .service(exchange::import_translations),
+    );
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct TranslationsFilter {
+    key: Option<String>,
+    language: Option<String>,
+    #[param(inline)]
+    status: Option<TranslationStatus>,
+    translator_id: Option<i32>,
+    page: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TranslationsResponse {
+    total_pages: u64,
+    #[schema(value_type = Vec<Translation>)]
+    translations: Vec<translation::Model>,
+}
+
+/// Lists translations, the most recently updated first.
+/// Reviewers use `status=submitted` to find the translations waiting for review.
+///
+/// ### Query parameters
+/// * key: String (optional)
+/// * language: String (optional)
+/// * status: TranslationStatus (optional)
+/// * translator_id: i32 (optional)
+/// * page: u64 (optional)
+///
+/// ### Response
+/// * TranslationsResponse
+#[utoipa::path(
+    context_path = "/translations",
+    tag = "translations",
+    params(TranslationsFilter),
+    responses(
+        (status = 200, body = TranslationsResponse),
+        (status = 422, body = ApiError),
+    )
+)]
+#[get("")]
+pub async fn list_translations(
+    state: web::Data<AppState>,
+    filter: web::Query<TranslationsFilter>,
+) -> ApiResult<web::Json<TranslationsResponse>> {
+    let filter = filter.into_inner();
+    let (total_pages, translations) = edit::list_translations(
+        &state.db,
+        TranslationFilter {
+            key: filter.key,
+            language: filter.language,
+            status: filter.status,
+            translator_id: filter.translator_id,
+        },
+        filter.page.unwrap_or(0),
+    )
+    .await?;
+
+    Ok(web::Json(TranslationsResponse {
+        total_pages,
+        translations,
+    }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SaveTranslationPayload {
+    /// Key of the text entry.
+    pub key: String,
+    /// Minecraft language code (e.g. `zh_tw`).
+    pub language: String,
+    pub value: String,
+    /// Submit the translation for review instead of saving a draft.
+    #[serde(default)]
+    pub submit: bool,
+}
+
+/// Saves the translation of the current user for a text entry, as a draft or
+/// submitted for review. Translations are not used until they are approved.
+///
+/// ### Payload
+/// * key: String
+/// * language: String
+/// * value: String
+/// * submit: bool (optional, defaults to false)
+///
+/// ### Response
+/// * Translation
+///
+/// ### Errors
+/// * 403: Not a translator of the language
+/// * 404: Text entry not found
+/// * 422: Invalid language code, or the submitted translation failed quality checks
+///   (details = Vec<QaIssue>)
+#[utoipa::path(
+    context_path = "/translations",
+    tag = "translations",
+    request_body = SaveTranslationPayload,
+    responses(
+        (status = 200, body = Translation),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+        (status = 422, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[post("")]
+pub async fn save_translation(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    payload: web::Json<SaveTranslationPayload>,
+) -> ApiResult<web::Json<translation::Model>> {
+    let payload = payload.into_inner();
+    validate_language(&payload.language)?;
+    ensure_role(&user, &Role::Translator, &payload.language)?;
+    if payload.submit {
+        ensure_passes_qa(&state.db, &payload.key, &payload.value).await?;
+    }
+
+    let translation = edit::save_translation(
+        &state.db,
+        user.user.id,
+        payload.key,
+        payload.language,
+        payload.value,
+        payload.submit,
+    )
+    .await?
+    .ok_or_else(|| ApiError::not_found("Text entry not found"))?;
+
+    Ok(web::Json(translation))
+}
+
+/// Submits a draft or rejected translation of the current user for review.
+///
+/// ### Response
+/// * Translation
+///
+/// ### Errors
+/// * 403: Not the translator of the translation
+/// * 404: Translation not found
+/// * 409: Translation is already submitted or approved
+/// * 422: Translation failed quality checks (details = Vec<QaIssue>)
+#[utoipa::path(
+    context_path = "/translations",
+    tag = "translations",
+    params(("translation_id" = i32, Path, description = "ID of the translation")),
+    responses(
+        (status = 200, body = Translation),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+        (status = 409, body = ApiError),
+        (status = 422, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[post("/{translation_id}/submit")]
+pub async fn submit_translation(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    translation_id: web::Path<i32>,
+) -> ApiResult<web::Json<translation::Model>> {
+    let translation = find_translation(&state.db, *translation_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Translation not found"))?;
+
+    if translation.translator_id != user.user.id {
+        return Err(ApiError::forbidden(
+            "Only the translator can submit the translation",
+        ));
+    }
+    if !matches!(
+        translation.status,
+        TranslationStatus::Draft | TranslationStatus::Rejected
+    ) {
+        return Err(ApiError::conflict("Translation is already submitted")
+            .with_details(&translation.status));
+    }
+    ensure_passes_qa(&state.db, &translation.key, &translation.value).await?;
+
+    let translation = edit::submit_translation(&state.db, translation).await?;
+    Ok(web::Json(translation))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CheckTranslationPayload {
+    /// Key of the text entry.
+    pub key: String,
+    pub value: String,
+    /// Language of the translation, its glossary is only checked if given.
+    pub language: Option<String>,
+}
+
+/// Runs the quality checks on a translation of a text entry without saving it,
+/// translations with errors cannot be submitted.
+///
+/// ### Payload
+/// * key: String
+/// * value: String
+/// * language: String (optional)
+///
+/// ### Response
+/// * Vec<QaIssue>
+///
+/// ### Errors
+/// * 404: Text entry not found
+/// * 422: Invalid language code
+#[utoipa::path(
+    context_path = "/translations",
+    tag = "translations",
+    request_body = CheckTranslationPayload,
+    responses(
+        (status = 200, body = Vec<QaIssue>),
+        (status = 404, body = ApiError),
+        (status = 422, body = ApiError),
+    )
+)]
+#[post("/check")]
+pub async fn check_translation(
+    state: web::Data<AppState>,
+    payload: web::Json<CheckTranslationPayload>,
+) -> ApiResult<web::Json<Vec<QaIssue>>> {
+    if let Some(language) = &payload.language {
+        validate_language(language)?;
+    }
+
+    let issues = check_entry_translation(
+        &state.db,
+        &payload.key,
+        &payload.value,
+        payload.language.as_deref(),
+    )
+    .await?
+    .ok_or_else(|| ApiError::not_found("Text entry not found"))?;
+
+    Ok(web::Json(issues))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReviewPayload {
+    /// IDs of the submitted translations to review.
+    pub ids: Vec<i32>,
+    pub comment: Option<String>,
+}
+
+/// Approves submitted translations in bulk, approved translations are used for
+/// previews and exports. Either all translations are approved or none.
+///
+/// ### Payload
+/// * ids: Vec<i32>
+/// * comment: String (optional)
+///
+/// ### Response
+/// * Vec<Translation>
+///
+/// ### Errors
+/// * 403: Not a proofreader of the language of a translation
+/// * 404: Translation not found (details = missing IDs)
+/// * 409: Translation is not submitted (details = IDs)
+/// * 422: No or too many IDs
+#[utoipa::path(
+    context_path = "/translations",
+    tag = "translations",
+    request_body = ReviewPayload,
+    responses(
+        (status = 200, body = Vec<Translation>),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+        (status = 409, body = ApiError),
+        (status = 422, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[post("/approve")]
+pub async fn approve_translations(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    payload: web::Json<ReviewPayload>,
+) -> ApiResult<web::Json<Vec<translation::Model>>> {
+    review(&state, &user, payload.into_inner(), ReviewDecision::Approve).await
+}
+
+/// Rejects submitted translations in bulk, the comment should explain why.
+/// Either all translations are rejected or none.
+///
+/// ### Payload
+/// * ids: Vec<i32>
+/// * comment: String (optional)
+///
+/// ### Response
+/// * Vec<Translation>
+///
+/// ### Errors
+/// * 403: Not a proofreader of the language of a translation
+/// * 404: Translation not found (details = missing IDs)
+/// * 409: Translation is not submitted (details = IDs)
+/// * 422: No or too many IDs
+#[utoipa::path(
+    context_path = "/translations",
+    tag = "translations",
+    request_body = ReviewPayload,
+    responses(
+        (status = 200, body = Vec<Translation>),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+        (status = 409, body = ApiError),
+        (status = 422, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[post("/reject")]
+pub async fn reject_translations(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    payload: web::Json<ReviewPayload>,
+) -> ApiResult<web::Json<Vec<translation::Model>>> {
+    review(&state, &user, payload.into_inner(), ReviewDecision::Reject).await
+}
+
+async fn review(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    mut payload: ReviewPayload,
+    decision: ReviewDecision,
+) -> ApiResult<web::Json<Vec<translation::Model>>> {
+    payload.ids.sort_unstable();
+    payload.ids.dedup();
+    if payload.ids.is_empty() || payload.ids.len() > MAX_REVIEW_BATCH {
+        return Err(ApiError::validation(format!(
+            "Between 1 and {} translations can be reviewed at once",
+            MAX_REVIEW_BATCH
+        )));
+    }
+
+    let translations = find_translations(&state.db, &payload.ids).await?;
+    if translations.len() != payload.ids.len() {
+        let missing: Vec<i32> = payload
+            .ids
+            .iter()
+            .filter(|id| {
+                !translations
+                    .iter()
+                    .any(|translation| translation.id == **id)
+            })
+            .copied()
+            .collect();
+        return Err(ApiError::not_found("Translation not found").with_details(missing));
+    }
+
+    for translation in &translations {
+        ensure_role(user, &Role::Proofreader, &translation.language)?;
+    }
+
+    let not_submitted: Vec<i32> = translations
+        .iter()
+        .filter(|translation| translation.status != TranslationStatus::Submitted)
+        .map(|translation| translation.id)
+        .collect();
+    if !not_submitted.is_empty() {
+        return Err(
+            ApiError::conflict("Only submitted translations can be reviewed")
+                .with_details(not_submitted),
+        );
+    }
+
+    let reviewed = review_translations(
+        &state.db,
+        translations,
+        Some(user.user.id),
+        decision,
+        payload.comment,
+    )
+    .await?;
+    Ok(web::Json(reviewed))
+}
+
+pub(crate) fn ensure_role(user: &AuthenticatedUser, role: &Role, language: &str) -> ApiResult<()> {
+    if user.has_role(role, Some(language)) {
+        Ok(())
+    } else {
+        Err(
+            ApiError::forbidden("Insufficient permissions for this language")
+                .with_details(language),
+        )
+    }
+}
+
+/// Submitted translations must not break the placeholders of the source string,
+/// the error details list all issues found.
+pub(crate) async fn ensure_passes_qa(
+    db: &DatabaseConnection,
+    key: &str,
+    value: &str,
+) -> ApiResult<()> {
+    // Glossary checks only warn, they cannot fail the translation.
+    let issues = check_entry_translation(db, key, value, None)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Text entry not found"))?;
+
+    if has_errors(&issues) {
+        Err(ApiError::validation("Translation failed quality checks").with_details(issues))
+    } else {
+        Ok(())
+    }
+}
+
+/// Minecraft language codes are lowercase, e.g. `en_us`, `zh_tw` or `lol_us`.
+pub(crate) fn validate_language(language: &str) -> ApiResult<()> {
+    let valid = (2..=16).contains(&language.len())
+        && language
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(ApiError::validation("Invalid language code").with_details(language))
+    }
+}
@@ -0,0 +1,209 @@
+use actix_web::{get, post, put, web};
+use entity::{
+    account::user_role::Role,
+    entry::translation::{self, TranslationStatus},
+};
+use log::info;
+use serde::Deserialize;
+use service::translation::{
+    edit::find_translation,
+    review::{review_translations, ReviewDecision},
+    vote::{cast_vote, find_top_suggestion, list_suggestions},
+};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{ensure_role, validate_language};
+use crate::{
+    auth::AuthenticatedUser,
+    error::{ApiError, ApiResult},
+    AppState,
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SuggestionsQuery {
+    /// Key of the text entry.
+    key: String,
+    language: String,
+}
+
+/// Lists the suggestions for a text entry in a language, ranked by score.
+/// Suggestions are submitted or approved translations, new suggestions are
+/// made by saving a translation with `submit` set.
+///
+/// ### Query parameters
+/// * key: String
+/// * language: String
+///
+/// ### Response
+/// * Vec<Translation>
+///
+/// ### Errors
+/// * 422: Invalid language code
+#[utoipa::path(
+    context_path = "/translations",
+    tag = "translations",
+    params(SuggestionsQuery),
+    responses(
+        (status = 200, body = Vec<Translation>),
+        (status = 422, body = ApiError),
+    )
+)]
+#[get("/suggestions")]
+pub async fn list_ranked_suggestions(
+    state: web::Data<AppState>,
+    query: web::Query<SuggestionsQuery>,
+) -> ApiResult<web::Json<Vec<translation::Model>>> {
+    validate_language(&query.language)?;
+
+    let suggestions = list_suggestions(&state.db, &query.key, &query.language).await?;
+    Ok(web::Json(suggestions))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VotePayload {
+    /// `1` to upvote, `-1` to downvote, `0` to remove the vote.
+    pub value: i32,
+}
+
+/// Votes on a suggestion, replacing the previous vote of the current user.
+/// A submitted suggestion is approved automatically once its score reaches
+/// the threshold configured for the server.
+///
+/// ### Payload
+/// * value: i32 (`1`, `-1` or `0`)
+///
+/// ### Response
+/// * Translation: with the recomputed score
+///
+/// ### Errors
+/// * 403: Not a translator of the language, or voting on own suggestion
+/// * 404: Translation not found
+/// * 409: Translation is not a suggestion (draft or rejected)
+/// * 422: Invalid vote value
+#[utoipa::path(
+    context_path = "/translations",
+    tag = "translations",
+    params(("translation_id" = i32, Path, description = "ID of the translation")),
+    request_body = VotePayload,
+    responses(
+        (status = 200, body = Translation),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+        (status = 409, body = ApiError),
+        (status = 422, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[put("/{translation_id}/vote")]
+pub async fn vote_translation(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    translation_id: web::Path<i32>,
+    payload: web::Json<VotePayload>,
+) -> ApiResult<web::Json<translation::Model>> {
+    if !(-1..=1).contains(&payload.value) {
+        return Err(ApiError::validation("Vote must be 1, -1 or 0"));
+    }
+
+    let translation = find_translation(&state.db, *translation_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Translation not found"))?;
+    ensure_role(&user, &Role::Translator, &translation.language)?;
+
+    if translation.translator_id == user.user.id {
+        return Err(ApiError::forbidden("Cannot vote on own suggestion"));
+    }
+    if !matches!(
+        translation.status,
+        TranslationStatus::Submitted | TranslationStatus::Approved
+    ) {
+        return Err(ApiError::conflict("Only suggestions can be voted on")
+            .with_details(&translation.status));
+    }
+
+    let translation = cast_vote(&state.db, translation, user.user.id, payload.value).await?;
+
+    let reached_threshold = state
+        .config
+        .auto_approve_score
+        .is_some_and(|threshold| translation.score >= threshold);
+    if translation.status == TranslationStatus::Submitted && reached_threshold {
+        info!(
+            "Suggestion {} approved by votes with score {}",
+            translation.id, translation.score
+        );
+        let mut approved = review_translations(
+            &state.db,
+            vec![translation],
+            None,
+            ReviewDecision::Approve,
+            Some("Approved by votes".to_string()),
+        )
+        .await?;
+        return Ok(web::Json(approved.remove(0)));
+    }
+
+    Ok(web::Json(translation))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PromotePayload {
+    /// Key of the text entry.
+    pub key: String,
+    pub language: String,
+    pub comment: Option<String>,
+}
+
+/// Approves the best submitted suggestion for a text entry in a language.
+/// Reviewers can also approve any other suggestion with the approve endpoint.
+///
+/// ### Payload
+/// * key: String
+/// * language: String
+/// * comment: String (optional)
+///
+/// ### Response
+/// * Translation: the approved suggestion
+///
+/// ### Errors
+/// * 403: Not a proofreader of the language
+/// * 404: No submitted suggestion
+/// * 422: Invalid language code
+#[utoipa::path(
+    context_path = "/translations",
+    tag = "translations",
+    request_body = PromotePayload,
+    responses(
+        (status = 200, body = Translation),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+        (status = 422, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[post("/promote")]
+pub async fn promote_top_suggestion(
+    state: web::Data<AppState>,
+    user: AuthenticatedUser,
+    payload: web::Json<PromotePayload>,
+) -> ApiResult<web::Json<translation::Model>> {
+    let payload = payload.into_inner();
+    validate_language(&payload.language)?;
+    ensure_role(&user, &Role::Proofreader, &payload.language)?;
+
+    let suggestion = find_top_suggestion(&state.db, &payload.key, &payload.language)
+        .await?
+        .ok_or_else(|| ApiError::not_found("No submitted suggestion"))?;
+
+    let mut approved = review_translations(
+        &state.db,
+        vec![suggestion],
+        Some(user.user.id),
+        ReviewDecision::Approve,
+        payload.comment,
+    )
+    .await?;
+    Ok(web::Json(approved.remove(0)))
+}
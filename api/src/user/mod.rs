@@ -0,0 +1,25 @@
+pub(crate) mod role;
+
+use actix_web::{get, web};
+use entity::account::user;
+
+use crate::{auth::AuthenticatedUser, error::ApiResult};
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_current_user);
+}
+
+/// Returns the user authenticated by the request token.
+#[utoipa::path(
+    context_path = "/users",
+    tag = "users",
+    responses(
+        (status = 200, body = User),
+        (status = 401, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[get("/me")]
+pub async fn get_current_user(user: AuthenticatedUser) -> ApiResult<web::Json<user::Model>> {
+    Ok(web::Json(user.user))
+}
@@ -0,0 +1,151 @@
+use actix_web::{delete, get, post, web, HttpResponse};
+use entity::account::user_role::{self, Role};
+use serde::Deserialize;
+use service::account::{
+    role::{find_user_roles, grant_role, revoke_role},
+    user::find_user,
+};
+use utoipa::ToSchema;
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{ApiError, ApiResult},
+    AppState,
+};
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.service(list_user_roles);
+    cfg.service(grant_user_role);
+    cfg.service(revoke_user_role);
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GrantRolePayload {
+    #[schema(inline)]
+    pub role: Role,
+    /// Language the role applies to, or all languages if not set.
+    pub language: Option<String>,
+}
+
+/// Lists the roles assigned to the user.
+#[utoipa::path(
+    context_path = "/users/{user_id}/roles",
+    tag = "users",
+    params(("user_id" = i32, Path, description = "ID of the user")),
+    responses(
+        (status = 200, body = Vec<UserRole>),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[get("")]
+pub async fn list_user_roles(
+    state: web::Data<AppState>,
+    user_id: web::Path<i32>,
+) -> ApiResult<web::Json<Vec<user_role::Model>>> {
+    let roles = find_user_roles(&state.db, *user_id).await?;
+    Ok(web::Json(roles))
+}
+
+/// Grants a role to the user, globally or for a language.
+///
+/// Admins can grant any role, language managers can grant translator and
+/// proofreader roles for the languages they manage.
+///
+/// ### Payload
+/// * role: Role
+/// * language: String (optional)
+///
+/// ### Errors
+/// * 403: Not allowed to grant the role
+/// * 404: User not found
+#[utoipa::path(
+    context_path = "/users/{user_id}/roles",
+    tag = "users",
+    params(("user_id" = i32, Path, description = "ID of the user")),
+    request_body = GrantRolePayload,
+    responses(
+        (status = 200, body = UserRole),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[post("")]
+pub async fn grant_user_role(
+    state: web::Data<AppState>,
+    actor: AuthenticatedUser,
+    user_id: web::Path<i32>,
+    payload: web::Json<GrantRolePayload>,
+) -> ApiResult<web::Json<user_role::Model>> {
+    let payload = payload.into_inner();
+    ensure_can_manage(&actor, &payload.role, payload.language.as_deref())?;
+
+    if find_user(&state.db, *user_id).await?.is_none() {
+        return Err(ApiError::not_found("User not found"));
+    }
+
+    let role = grant_role(&state.db, *user_id, payload.role, payload.language).await?;
+    Ok(web::Json(role))
+}
+
+/// Revokes a role assignment of the user.
+///
+/// ### Errors
+/// * 403: Not allowed to revoke the role
+/// * 404: Role assignment not found
+#[utoipa::path(
+    context_path = "/users/{user_id}/roles",
+    tag = "users",
+    params(
+        ("user_id" = i32, Path, description = "ID of the user"),
+        ("role_id" = i32, Path, description = "ID of the role assignment"),
+    ),
+    responses(
+        (status = 204),
+        (status = 401, body = ApiError),
+        (status = 403, body = ApiError),
+        (status = 404, body = ApiError),
+    ),
+    security(("bearer" = []))
+)]
+#[delete("/{role_id}")]
+pub async fn revoke_user_role(
+    state: web::Data<AppState>,
+    actor: AuthenticatedUser,
+    path: web::Path<(i32, i32)>,
+) -> ApiResult<HttpResponse> {
+    let (user_id, role_id) = path.into_inner();
+    let assigned = find_user_roles(&state.db, user_id)
+        .await?
+        .into_iter()
+        .find(|assigned| assigned.id == role_id)
+        .ok_or_else(|| ApiError::not_found("Role assignment not found"))?;
+    ensure_can_manage(&actor, &assigned.role, assigned.language.as_deref())?;
+
+    revoke_role(&state.db, user_id, role_id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+fn ensure_can_manage(
+    actor: &AuthenticatedUser,
+    role: &Role,
+    language: Option<&str>,
+) -> ApiResult<()> {
+    let allowed = if actor.is_admin() {
+        true
+    } else {
+        // Language managers only manage the roles below them in their own languages.
+        language.is_some()
+            && !role.includes(&Role::LanguageManager)
+            && actor.has_role(&Role::LanguageManager, language)
+    };
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(ApiError::forbidden("Not allowed to manage this role"))
+    }
+}
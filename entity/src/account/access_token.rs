@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{entity::prelude::*, DatabaseBackend, Schema, Set, Statement};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::database_initializer::DatabaseInitializer;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "access_token")]
+#[schema(as = AccessToken)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    /// SHA-256 hash of the token, the token itself is never stored.
+    #[sea_orm(unique)]
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub kind: TokenKind,
+    pub name: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+
+    pub user_id: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::account::user::Entity",
+        from = "Column::UserId",
+        to = "crate::account::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<crate::account::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            self.created_at = Set(Utc::now());
+        }
+
+        Ok(self)
+    }
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash, EnumIter, DeriveActiveEnum, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "token_kind")]
+#[serde(rename_all = "snake_case")]
+pub enum TokenKind {
+    /// Issued on login, expires after a while.
+    #[sea_orm(string_value = "Session")]
+    Session,
+    /// Created by the user for scripts and bots.
+    #[sea_orm(string_value = "Api")]
+    Api,
+}
+
+impl DatabaseInitializer for Entity {
+    fn initialize(builder: &DatabaseBackend) -> Statement {
+        let schema = Schema::new(*builder);
+        let mut statement = schema.create_table_from_entity(Self);
+
+        builder.build(statement.if_not_exists())
+    }
+}
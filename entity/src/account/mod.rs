@@ -0,0 +1,4 @@
+pub mod access_token;
+pub mod oauth_account;
+pub mod user;
+pub mod user_role;
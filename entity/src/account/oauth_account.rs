@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{entity::prelude::*, DatabaseBackend, Schema, Set, Statement};
+use serde::{Deserialize, Serialize};
+
+use crate::database_initializer::DatabaseInitializer;
+
+/// Links an account of an external OAuth provider (e.g. GitHub) to a user.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "oauth_account")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub provider: String,
+    /// ID of the account at the provider.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub subject: String,
+
+    pub created_at: DateTime<Utc>,
+
+    pub user_id: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::account::user::Entity",
+        from = "Column::UserId",
+        to = "crate::account::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<crate::account::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            self.created_at = Set(Utc::now());
+        }
+
+        Ok(self)
+    }
+}
+
+impl DatabaseInitializer for Entity {
+    fn initialize(builder: &DatabaseBackend) -> Statement {
+        let schema = Schema::new(*builder);
+        let mut statement = schema.create_table_from_entity(Self);
+
+        builder.build(statement.if_not_exists())
+    }
+}
@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{entity::prelude::*, DatabaseBackend, Schema, Set, Statement};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::database_initializer::DatabaseInitializer;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "user")]
+#[schema(as = User)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    #[sea_orm(unique)]
+    pub username: String,
+    pub display_name: String,
+    /// Argon2 hash of the password, users signed up with OAuth only have no password.
+    #[serde(skip_serializing)]
+    pub password_hash: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "crate::account::access_token::Entity")]
+    AccessToken,
+    #[sea_orm(has_many = "crate::account::oauth_account::Entity")]
+    OAuthAccount,
+    #[sea_orm(has_many = "crate::account::user_role::Entity")]
+    UserRole,
+}
+
+impl Related<crate::account::access_token::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::AccessToken.def()
+    }
+}
+
+impl Related<crate::account::oauth_account::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OAuthAccount.def()
+    }
+}
+
+impl Related<crate::account::user_role::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserRole.def()
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let now = Utc::now();
+        if insert {
+            self.created_at = Set(now);
+        }
+        self.updated_at = Set(now);
+
+        Ok(self)
+    }
+}
+
+impl DatabaseInitializer for Entity {
+    fn initialize(builder: &DatabaseBackend) -> Statement {
+        let schema = Schema::new(*builder);
+        let mut statement = schema.create_table_from_entity(Self);
+
+        builder.build(statement.if_not_exists())
+    }
+}
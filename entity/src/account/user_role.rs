@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{entity::prelude::*, DatabaseBackend, Schema, Set, Statement};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::database_initializer::DatabaseInitializer;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "user_role")]
+#[schema(as = UserRole)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub role: Role,
+    /// The language the role applies to (e.g. `zh_tw`), or all languages if not set.
+    pub language: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+
+    pub user_id: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::account::user::Entity",
+        from = "Column::UserId",
+        to = "crate::account::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<crate::account::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            self.created_at = Set(Utc::now());
+        }
+
+        Ok(self)
+    }
+}
+
+/// Roles ordered by trust, a role includes the permissions of all roles before it.
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash, EnumIter, DeriveActiveEnum, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "role")]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    #[sea_orm(string_value = "Translator")]
+    Translator,
+    #[sea_orm(string_value = "Proofreader")]
+    Proofreader,
+    #[sea_orm(string_value = "LanguageManager")]
+    LanguageManager,
+    #[sea_orm(string_value = "Admin")]
+    Admin,
+}
+
+impl Role {
+    pub fn level(&self) -> u8 {
+        match self {
+            Role::Translator => 0,
+            Role::Proofreader => 1,
+            Role::LanguageManager => 2,
+            Role::Admin => 3,
+        }
+    }
+
+    /// Whether this role grants the permissions of the other role.
+    pub fn includes(&self, other: &Role) -> bool {
+        self.level() >= other.level()
+    }
+}
+
+impl DatabaseInitializer for Entity {
+    fn initialize(builder: &DatabaseBackend) -> Statement {
+        let schema = Schema::new(*builder);
+        let mut statement = schema.create_table_from_entity(Self);
+
+        builder.build(statement.if_not_exists())
+    }
+}
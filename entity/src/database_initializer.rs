@@ -1,8 +1,12 @@
 use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, Statement};
 
 use crate::{
-    entry::text_entry,
-    minecraft::{minecraft_mod, mod_provider},
+    account::{access_token, oauth_account, user, user_role},
+    entry::{
+        glossary_term, text_entry, text_entry_revision, text_entry_variant, translation,
+        translation_revision, translation_vote,
+    },
+    minecraft::{archive_failure, minecraft_mod, mod_provider, modpack, modpack_mod},
 };
 
 pub trait DatabaseInitializer {
@@ -14,7 +18,20 @@ pub async fn initialize_database(db: &DatabaseConnection) -> Result<(), sea_orm:
     let statements = vec![
         minecraft_mod::Entity::initialize(&builder),
         mod_provider::Entity::initialize(&builder),
+        modpack::Entity::initialize(&builder),
+        modpack_mod::Entity::initialize(&builder),
+        archive_failure::Entity::initialize(&builder),
         text_entry::Entity::initialize(&builder),
+        text_entry_revision::Entity::initialize(&builder),
+        text_entry_variant::Entity::initialize(&builder),
+        user::Entity::initialize(&builder),
+        access_token::Entity::initialize(&builder),
+        oauth_account::Entity::initialize(&builder),
+        user_role::Entity::initialize(&builder),
+        translation::Entity::initialize(&builder),
+        translation_vote::Entity::initialize(&builder),
+        translation_revision::Entity::initialize(&builder),
+        glossary_term::Entity::initialize(&builder),
     ];
 
     for statement in statements {
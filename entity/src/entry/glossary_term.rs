@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{entity::prelude::*, DatabaseBackend, Schema, Set, Statement};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::database_initializer::DatabaseInitializer;
+
+/// How a term is rendered in a language, so that it is translated consistently.
+/// A term scoped to a mod takes precedence over the global term in that mod.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "glossary_term")]
+#[schema(as = GlossaryTerm)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    /// The term as written in source values, matched regardless of case.
+    pub term: String,
+    /// Minecraft language code (e.g. `zh_tw`).
+    pub language: String,
+    /// How the term is rendered in the language.
+    pub translation: String,
+    pub part_of_speech: Option<PartOfSpeech>,
+    /// Usage notes for translators.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub note: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    /// The mod the term applies to, or all mods if not set.
+    pub mod_id: Option<i32>,
+    pub creator_id: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::minecraft::minecraft_mod::Entity",
+        from = "Column::ModId",
+        to = "crate::minecraft::minecraft_mod::Column::Id"
+    )]
+    MinecraftMod,
+    #[sea_orm(
+        belongs_to = "crate::account::user::Entity",
+        from = "Column::CreatorId",
+        to = "crate::account::user::Column::Id"
+    )]
+    Creator,
+}
+
+impl Related<crate::minecraft::minecraft_mod::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MinecraftMod.def()
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let now = Utc::now();
+        if insert {
+            self.created_at = Set(now);
+        }
+        self.updated_at = Set(now);
+
+        Ok(self)
+    }
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash, EnumIter, DeriveActiveEnum, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "part_of_speech")]
+#[serde(rename_all = "snake_case")]
+pub enum PartOfSpeech {
+    #[sea_orm(string_value = "Noun")]
+    Noun,
+    #[sea_orm(string_value = "ProperNoun")]
+    ProperNoun,
+    #[sea_orm(string_value = "Verb")]
+    Verb,
+    #[sea_orm(string_value = "Adjective")]
+    Adjective,
+    #[sea_orm(string_value = "Adverb")]
+    Adverb,
+    #[sea_orm(string_value = "Other")]
+    Other,
+}
+
+impl DatabaseInitializer for Entity {
+    fn initialize(builder: &DatabaseBackend) -> Statement {
+        let schema = Schema::new(*builder);
+        let mut statement = schema.create_table_from_entity(Self);
+
+        builder.build(statement.if_not_exists())
+    }
+}
@@ -1 +1,7 @@
-pub mod text_entry;
\ No newline at end of file
+pub mod glossary_term;
+pub mod text_entry;
+pub mod text_entry_revision;
+pub mod text_entry_variant;
+pub mod translation;
+pub mod translation_revision;
+pub mod translation_vote;
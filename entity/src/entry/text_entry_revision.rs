@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{entity::prelude::*, DatabaseBackend, Schema, Set, Statement};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::database_initializer::DatabaseInitializer;
+
+/// A previous source value of a text entry, kept when a mod update rewords the entry.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "text_entry_revision")]
+#[schema(as = TextEntryRevision)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    #[sea_orm(column_type = "Text")]
+    pub key: String,
+    /// The source value before it was replaced.
+    #[sea_orm(column_type = "Text")]
+    pub value: String,
+
+    /// When the value was replaced.
+    pub created_at: DateTime<Utc>,
+
+    pub mod_id: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::entry::text_entry::Entity",
+        from = "Column::Key",
+        to = "crate::entry::text_entry::Column::Key"
+    )]
+    TextEntry,
+}
+
+impl Related<crate::entry::text_entry::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TextEntry.def()
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            self.created_at = Set(Utc::now());
+        }
+
+        Ok(self)
+    }
+}
+
+impl DatabaseInitializer for Entity {
+    fn initialize(builder: &DatabaseBackend) -> Statement {
+        let schema = Schema::new(*builder);
+        let mut statement = schema.create_table_from_entity(Self);
+
+        builder.build(statement.if_not_exists())
+    }
+}
@@ -0,0 +1,51 @@
+use sea_orm::{entity::prelude::*, DatabaseBackend, Schema, Statement};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    database_initializer::DatabaseInitializer, minecraft::mod_loader::ModLoader, misc::StringVec,
+};
+
+/// A distinct source value of a text entry for a loader, and the game versions the mod
+/// releases for that loader containing the value support.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "text_entry_variant")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    #[sea_orm(column_type = "Text")]
+    pub key: String,
+    #[sea_orm(column_type = "Text")]
+    pub value: String,
+    pub game_versions: StringVec,
+    /// Not set for the vanilla game.
+    pub loader: Option<ModLoader>,
+
+    pub mod_id: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::entry::text_entry::Entity",
+        from = "Column::Key",
+        to = "crate::entry::text_entry::Column::Key"
+    )]
+    TextEntry,
+}
+
+impl Related<crate::entry::text_entry::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TextEntry.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl DatabaseInitializer for Entity {
+    fn initialize(builder: &DatabaseBackend) -> Statement {
+        let schema = Schema::new(*builder);
+        let mut statement = schema.create_table_from_entity(Self);
+
+        builder.build(statement.if_not_exists())
+    }
+}
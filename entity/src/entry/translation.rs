@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{entity::prelude::*, DatabaseBackend, Schema, Set, Statement};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::database_initializer::DatabaseInitializer;
+
+/// A translation of a text entry into a language by a translator.
+/// Submitted translations are suggestions that compete by votes,
+/// only approved translations are used for previews and exports.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "translation")]
+#[schema(as = Translation)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    /// Key of the translated text entry.
+    #[sea_orm(column_type = "Text")]
+    pub key: String,
+    /// Minecraft language code (e.g. `zh_tw`).
+    pub language: String,
+    #[sea_orm(column_type = "Text")]
+    pub value: String,
+    pub status: TranslationStatus,
+    /// Sum of the votes on the translation.
+    pub score: i32,
+    /// The source value of the entry changed after the translation was made.
+    pub source_changed: bool,
+
+    pub review_comment: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    pub translator_id: i32,
+    pub reviewer_id: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::entry::text_entry::Entity",
+        from = "Column::Key",
+        to = "crate::entry::text_entry::Column::Key"
+    )]
+    TextEntry,
+    #[sea_orm(
+        belongs_to = "crate::account::user::Entity",
+        from = "Column::TranslatorId",
+        to = "crate::account::user::Column::Id"
+    )]
+    Translator,
+    #[sea_orm(
+        belongs_to = "crate::account::user::Entity",
+        from = "Column::ReviewerId",
+        to = "crate::account::user::Column::Id"
+    )]
+    Reviewer,
+    #[sea_orm(has_many = "crate::entry::translation_vote::Entity")]
+    TranslationVote,
+    #[sea_orm(has_many = "crate::entry::translation_revision::Entity")]
+    TranslationRevision,
+}
+
+impl Related<crate::entry::text_entry::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TextEntry.def()
+    }
+}
+
+impl Related<crate::entry::translation_vote::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TranslationVote.def()
+    }
+}
+
+impl Related<crate::entry::translation_revision::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TranslationRevision.def()
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let now = Utc::now();
+        if insert {
+            self.created_at = Set(now);
+        }
+        self.updated_at = Set(now);
+
+        Ok(self)
+    }
+}
+
+/// Translations start as drafts, are submitted for review, then approved or rejected.
+/// A rejected translation can be edited and submitted again.
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash, EnumIter, DeriveActiveEnum, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "translation_status")]
+#[serde(rename_all = "snake_case")]
+pub enum TranslationStatus {
+    #[sea_orm(string_value = "Draft")]
+    Draft,
+    #[sea_orm(string_value = "Submitted")]
+    Submitted,
+    #[sea_orm(string_value = "Approved")]
+    Approved,
+    #[sea_orm(string_value = "Rejected")]
+    Rejected,
+}
+
+impl DatabaseInitializer for Entity {
+    fn initialize(builder: &DatabaseBackend) -> Statement {
+        let schema = Schema::new(*builder);
+        let mut statement = schema.create_table_from_entity(Self);
+
+        builder.build(statement.if_not_exists())
+    }
+}
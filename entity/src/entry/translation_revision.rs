@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{entity::prelude::*, DatabaseBackend, Schema, Set, Statement};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{database_initializer::DatabaseInitializer, entry::translation::TranslationStatus};
+
+/// A change to a translation, revisions are never modified or deleted.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "translation_revision")]
+#[schema(as = TranslationRevision)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    /// Key of the translated text entry.
+    #[sea_orm(column_type = "Text")]
+    pub key: String,
+    pub language: String,
+    pub action: RevisionAction,
+    /// Value of the translation after the change.
+    #[sea_orm(column_type = "Text")]
+    pub value: String,
+    /// Value of the translation before an edit. For approvals and reverts, the value
+    /// of the entry that was live before.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub previous_value: Option<String>,
+    pub status: TranslationStatus,
+    pub comment: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+
+    pub translation_id: i32,
+    /// The user who made the change, not set for automatic changes (e.g. approval by votes).
+    pub user_id: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::entry::translation::Entity",
+        from = "Column::TranslationId",
+        to = "crate::entry::translation::Column::Id"
+    )]
+    Translation,
+    #[sea_orm(
+        belongs_to = "crate::account::user::Entity",
+        from = "Column::UserId",
+        to = "crate::account::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<crate::entry::translation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Translation.def()
+    }
+}
+
+impl Related<crate::account::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            self.created_at = Set(Utc::now());
+        }
+
+        Ok(self)
+    }
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash, EnumIter, DeriveActiveEnum, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "revision_action")]
+#[serde(rename_all = "snake_case")]
+pub enum RevisionAction {
+    #[sea_orm(string_value = "Created")]
+    Created,
+    #[sea_orm(string_value = "Edited")]
+    Edited,
+    #[sea_orm(string_value = "Submitted")]
+    Submitted,
+    #[sea_orm(string_value = "Approved")]
+    Approved,
+    #[sea_orm(string_value = "Rejected")]
+    Rejected,
+    /// A previous revision was made live again.
+    #[sea_orm(string_value = "Reverted")]
+    Reverted,
+}
+
+impl DatabaseInitializer for Entity {
+    fn initialize(builder: &DatabaseBackend) -> Statement {
+        let schema = Schema::new(*builder);
+        let mut statement = schema.create_table_from_entity(Self);
+
+        builder.build(statement.if_not_exists())
+    }
+}
@@ -0,0 +1,76 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{entity::prelude::*, DatabaseBackend, Schema, Set, Statement};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::database_initializer::DatabaseInitializer;
+
+/// A vote of a user on a translation suggestion, each user has one vote per suggestion.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "translation_vote")]
+#[schema(as = TranslationVote)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub translation_id: i32,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: i32,
+    /// `1` for an upvote, `-1` for a downvote.
+    pub value: i32,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::entry::translation::Entity",
+        from = "Column::TranslationId",
+        to = "crate::entry::translation::Column::Id"
+    )]
+    Translation,
+    #[sea_orm(
+        belongs_to = "crate::account::user::Entity",
+        from = "Column::UserId",
+        to = "crate::account::user::Column::Id"
+    )]
+    User,
+}
+
+impl Related<crate::entry::translation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Translation.def()
+    }
+}
+
+impl Related<crate::account::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let now = Utc::now();
+        if insert {
+            self.created_at = Set(now);
+        }
+        self.updated_at = Set(now);
+
+        Ok(self)
+    }
+}
+
+impl DatabaseInitializer for Entity {
+    fn initialize(builder: &DatabaseBackend) -> Statement {
+        let schema = Schema::new(*builder);
+        let mut statement = schema.create_table_from_entity(Self);
+
+        builder.build(statement.if_not_exists())
+    }
+}
@@ -1,4 +1,5 @@
+pub mod account;
 pub mod database_initializer;
 pub mod entry;
 pub mod minecraft;
-pub mod misc;
\ No newline at end of file
+pub mod misc;
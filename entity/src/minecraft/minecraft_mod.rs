@@ -2,11 +2,13 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use sea_orm::{entity::prelude::*, DatabaseBackend, Schema, Set, Statement};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::database_initializer::DatabaseInitializer;
 
-#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
 #[sea_orm(table_name = "minecraft_mod")]
+#[schema(as = MinecraftMod)]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
@@ -54,7 +56,9 @@ impl ActiveModelBehavior for ActiveModel {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash, EnumIter, DeriveActiveEnum)]
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash, EnumIter, DeriveActiveEnum, ToSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "mod_status")]
 #[serde(rename_all = "snake_case")]
 pub enum ModStatus {
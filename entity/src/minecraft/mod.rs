@@ -1,3 +1,6 @@
-pub mod mod_loader;
+pub mod archive_failure;
 pub mod minecraft_mod;
-pub mod mod_provider;
\ No newline at end of file
+pub mod mod_loader;
+pub mod mod_provider;
+pub mod modpack;
+pub mod modpack_mod;
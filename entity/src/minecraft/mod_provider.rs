@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use sea_orm::{entity::prelude::*, DatabaseBackend, Schema, Set, Statement};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::database_initializer::DatabaseInitializer;
 
@@ -71,7 +72,9 @@ impl ActiveModelBehavior for ActiveModel {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash, EnumIter, DeriveActiveEnum)]
+#[derive(
+    Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Hash, EnumIter, DeriveActiveEnum, ToSchema,
+)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "mod_provider_type")]
 #[serde(rename_all = "lowercase")]
 pub enum ModProviderType {
@@ -81,6 +84,9 @@ pub enum ModProviderType {
     Modrinth,
     #[sea_orm(string_value = "Custom")]
     Custom,
+    /// The vanilla game, imported from local game assets.
+    #[sea_orm(string_value = "Vanilla")]
+    Vanilla,
 }
 
 impl DatabaseInitializer for Entity {
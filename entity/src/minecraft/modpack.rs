@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::{entity::prelude::*, DatabaseBackend, Schema, Set, Statement};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::database_initializer::DatabaseInitializer;
+
+use super::{mod_loader::ModLoader, mod_provider::ModProviderType};
+
+/// A modpack imported from its Modrinth or CurseForge manifest, linked to the mods it contains.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize, ToSchema)]
+#[sea_orm(table_name = "modpack")]
+#[schema(as = Modpack)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub name: String,
+    /// Version of the modpack itself, as given by the manifest.
+    pub version: Option<String>,
+    pub provider_type: ModProviderType,
+    pub game_version: String,
+    pub loader: Option<ModLoader>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "crate::minecraft::modpack_mod::Entity")]
+    ModpackMod,
+}
+
+impl Related<crate::minecraft::modpack_mod::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ModpackMod.def()
+    }
+}
+
+impl Related<crate::minecraft::minecraft_mod::Entity> for Entity {
+    fn to() -> RelationDef {
+        crate::minecraft::modpack_mod::Relation::MinecraftMod.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(crate::minecraft::modpack_mod::Relation::Modpack.def().rev())
+    }
+}
+
+#[async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let now = Utc::now();
+        if insert {
+            self.created_at = Set(now);
+        }
+        self.updated_at = Set(now);
+
+        Ok(self)
+    }
+}
+
+impl DatabaseInitializer for Entity {
+    fn initialize(builder: &DatabaseBackend) -> Statement {
+        let schema = Schema::new(*builder);
+        let mut statement = schema.create_table_from_entity(Self);
+
+        builder.build(statement.if_not_exists())
+    }
+}
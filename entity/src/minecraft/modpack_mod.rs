@@ -0,0 +1,53 @@
+use sea_orm::{entity::prelude::*, DatabaseBackend, Schema, Statement};
+use serde::{Deserialize, Serialize};
+
+use crate::database_initializer::DatabaseInitializer;
+
+/// A mod contained in a modpack.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "modpack_mod")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub modpack_id: i32,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub mod_id: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::minecraft::modpack::Entity",
+        from = "Column::ModpackId",
+        to = "crate::minecraft::modpack::Column::Id"
+    )]
+    Modpack,
+    #[sea_orm(
+        belongs_to = "crate::minecraft::minecraft_mod::Entity",
+        from = "Column::ModId",
+        to = "crate::minecraft::minecraft_mod::Column::Id"
+    )]
+    MinecraftMod,
+}
+
+impl Related<crate::minecraft::modpack::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Modpack.def()
+    }
+}
+
+impl Related<crate::minecraft::minecraft_mod::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MinecraftMod.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl DatabaseInitializer for Entity {
+    fn initialize(builder: &DatabaseBackend) -> Statement {
+        let schema = Schema::new(*builder);
+        let mut statement = schema.create_table_from_entity(Self);
+
+        builder.build(statement.if_not_exists())
+    }
+}
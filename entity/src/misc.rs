@@ -2,4 +2,4 @@ use sea_orm::FromJsonQueryResult;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, FromJsonQueryResult)]
-pub struct StringVec(pub Vec<String>);
\ No newline at end of file
+pub struct StringVec(pub Vec<String>);
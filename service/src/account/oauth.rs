@@ -0,0 +1,284 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use entity::account::{oauth_account, user};
+use lazy_static::lazy_static;
+use rand::{distributions::Alphanumeric, rngs::OsRng, Rng};
+use reqwest::Url;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use serde::Deserialize;
+
+use super::user::{available_username, create_user, find_user};
+
+lazy_static! {
+    /// States handed out with authorization URLs, used to reject forged callbacks.
+    static ref PENDING_STATES: Mutex<HashMap<String, Instant>> = Mutex::new(HashMap::new());
+}
+
+const STATE_LIFETIME: Duration = Duration::from_secs(10 * 60);
+
+/// The account of a user at an OAuth provider.
+#[derive(Debug, Clone)]
+pub struct OAuthIdentity {
+    /// Stable ID of the account at the provider.
+    pub subject: String,
+    pub username: String,
+    pub display_name: Option<String>,
+}
+
+/// An external login provider using the OAuth 2.0 authorization code flow.
+#[async_trait]
+pub trait OAuthProvider: Send + Sync {
+    /// Name used in URLs and stored with linked accounts, e.g. `github`.
+    fn name(&self) -> &'static str;
+
+    fn authorize_url(&self, redirect_uri: &str, state: &str) -> String;
+
+    async fn exchange_code(&self, code: &str, redirect_uri: &str) -> anyhow::Result<OAuthIdentity>;
+}
+
+#[derive(Debug, Clone)]
+pub struct OAuthClientConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    /// Base URL of the authorization pages, configurable to run against a local stand-in.
+    pub base_url: String,
+    /// Base URL of the provider API.
+    pub api_base_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+pub struct GitHubOAuth {
+    config: OAuthClientConfig,
+    client: reqwest::Client,
+}
+
+impl GitHubOAuth {
+    pub fn new(config: OAuthClientConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubUser {
+    id: u64,
+    login: String,
+    name: Option<String>,
+}
+
+#[async_trait]
+impl OAuthProvider for GitHubOAuth {
+    fn name(&self) -> &'static str {
+        "github"
+    }
+
+    fn authorize_url(&self, redirect_uri: &str, state: &str) -> String {
+        let url = format!("{}/login/oauth/authorize", self.config.base_url);
+        Url::parse_with_params(
+            &url,
+            &[
+                ("client_id", self.config.client_id.as_str()),
+                ("redirect_uri", redirect_uri),
+                ("scope", "read:user"),
+                ("state", state),
+            ],
+        )
+        .map(String::from)
+        .unwrap_or(url)
+    }
+
+    async fn exchange_code(&self, code: &str, redirect_uri: &str) -> anyhow::Result<OAuthIdentity> {
+        let token = self
+            .client
+            .post(format!("{}/login/oauth/access_token", self.config.base_url))
+            .header(reqwest::header::ACCEPT, "application/json")
+            .form(&[
+                ("client_id", self.config.client_id.as_str()),
+                ("client_secret", self.config.client_secret.as_str()),
+                ("code", code),
+                ("redirect_uri", redirect_uri),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<TokenResponse>()
+            .await
+            .context("Failed to exchange GitHub authorization code")?;
+
+        let user = self
+            .client
+            .get(format!("{}/user", self.config.api_base_url))
+            .bearer_auth(token.access_token)
+            .header(reqwest::header::USER_AGENT, "RTranslator")
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<GitHubUser>()
+            .await
+            .context("Failed to get GitHub user")?;
+
+        Ok(OAuthIdentity {
+            subject: user.id.to_string(),
+            username: user.login,
+            display_name: user.name,
+        })
+    }
+}
+
+pub struct DiscordOAuth {
+    config: OAuthClientConfig,
+    client: reqwest::Client,
+}
+
+impl DiscordOAuth {
+    pub fn new(config: OAuthClientConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DiscordUser {
+    id: String,
+    username: String,
+    global_name: Option<String>,
+}
+
+#[async_trait]
+impl OAuthProvider for DiscordOAuth {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    fn authorize_url(&self, redirect_uri: &str, state: &str) -> String {
+        let url = format!("{}/oauth2/authorize", self.config.base_url);
+        Url::parse_with_params(
+            &url,
+            &[
+                ("client_id", self.config.client_id.as_str()),
+                ("redirect_uri", redirect_uri),
+                ("response_type", "code"),
+                ("scope", "identify"),
+                ("state", state),
+            ],
+        )
+        .map(String::from)
+        .unwrap_or(url)
+    }
+
+    async fn exchange_code(&self, code: &str, redirect_uri: &str) -> anyhow::Result<OAuthIdentity> {
+        let token = self
+            .client
+            .post(format!("{}/oauth2/token", self.config.api_base_url))
+            .form(&[
+                ("client_id", self.config.client_id.as_str()),
+                ("client_secret", self.config.client_secret.as_str()),
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", redirect_uri),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<TokenResponse>()
+            .await
+            .context("Failed to exchange Discord authorization code")?;
+
+        let user = self
+            .client
+            .get(format!("{}/users/@me", self.config.api_base_url))
+            .bearer_auth(token.access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<DiscordUser>()
+            .await
+            .context("Failed to get Discord user")?;
+
+        Ok(OAuthIdentity {
+            subject: user.id,
+            username: user.username,
+            display_name: user.global_name,
+        })
+    }
+}
+
+/// Creates a new state for an authorization URL.
+pub fn create_state() -> String {
+    let state: String = OsRng
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect();
+
+    let mut states = PENDING_STATES.lock().unwrap();
+    states.retain(|_, created_at| created_at.elapsed() < STATE_LIFETIME);
+    states.insert(state.clone(), Instant::now());
+
+    state
+}
+
+/// Returns `true` if the state was handed out by [`create_state`] and has not expired,
+/// each state can only be used once.
+pub fn consume_state(state: &str) -> bool {
+    let mut states = PENDING_STATES.lock().unwrap();
+    states
+        .remove(state)
+        .is_some_and(|created_at| created_at.elapsed() < STATE_LIFETIME)
+}
+
+/// Returns the user linked to the identity, a new user is created on the first login.
+pub async fn login_with_oauth(
+    db: &DatabaseConnection,
+    provider: &str,
+    identity: OAuthIdentity,
+) -> anyhow::Result<user::Model> {
+    let account =
+        oauth_account::Entity::find_by_id((provider.to_string(), identity.subject.clone()))
+            .one(db)
+            .await?;
+
+    if let Some(account) = &account {
+        if let Some(user) = find_user(db, account.user_id).await? {
+            return Ok(user);
+        }
+    }
+
+    let username = available_username(db, &identity.username).await?;
+    let display_name = identity.display_name.unwrap_or(identity.username);
+    let user = create_user(db, username, display_name, None).await?;
+
+    match account {
+        // The user of the account was deleted, the account now belongs to the new user.
+        Some(account) => {
+            let mut account: oauth_account::ActiveModel = account.into();
+            account.user_id = Set(user.id);
+            account.update(db).await?;
+        }
+        None => {
+            let account = oauth_account::ActiveModel {
+                provider: Set(provider.to_string()),
+                subject: Set(identity.subject),
+                user_id: Set(user.id),
+                ..Default::default()
+            };
+            account.insert(db).await?;
+        }
+    }
+
+    Ok(user)
+}
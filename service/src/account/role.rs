@@ -0,0 +1,78 @@
+use entity::account::user_role::{self, Role};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, Set,
+};
+
+pub async fn find_user_roles(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<user_role::Model>, DbErr> {
+    user_role::Entity::find()
+        .filter(user_role::Column::UserId.eq(user_id))
+        .order_by_asc(user_role::Column::Id)
+        .all(db)
+        .await
+}
+
+/// Grants the role to the user, granting an already assigned role does nothing.
+pub async fn grant_role(
+    db: &DatabaseConnection,
+    user_id: i32,
+    role: Role,
+    language: Option<String>,
+) -> Result<user_role::Model, DbErr> {
+    let existing = find_user_roles(db, user_id)
+        .await?
+        .into_iter()
+        .find(|assigned| assigned.role == role && assigned.language == language);
+    if let Some(existing) = existing {
+        return Ok(existing);
+    }
+
+    let model = user_role::ActiveModel {
+        id: NotSet,
+        role: Set(role),
+        language: Set(language),
+        user_id: Set(user_id),
+        ..Default::default()
+    };
+    model.insert(db).await
+}
+
+/// Revokes a role assignment of the user, returns `false` if there is no such assignment.
+pub async fn revoke_role(
+    db: &DatabaseConnection,
+    user_id: i32,
+    role_id: i32,
+) -> Result<bool, DbErr> {
+    let result = user_role::Entity::delete_many()
+        .filter(user_role::Column::Id.eq(role_id))
+        .filter(user_role::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
+
+/// Whether the assigned roles grant the permissions of the role.
+///
+/// With a language, only global assignments and assignments for that language count.
+/// Without a language, an assignment for any language is enough.
+pub fn has_role(roles: &[user_role::Model], role: &Role, language: Option<&str>) -> bool {
+    roles.iter().any(|assigned| {
+        let applies = match (&assigned.language, language) {
+            (None, _) | (_, None) => true,
+            (Some(assigned), Some(language)) => assigned == language,
+        };
+
+        applies && assigned.role.includes(role)
+    })
+}
+
+/// Whether the roles assigned for all languages grant the permissions of the role.
+pub fn has_global_role(roles: &[user_role::Model], role: &Role) -> bool {
+    roles
+        .iter()
+        .any(|assigned| assigned.language.is_none() && assigned.role.includes(role))
+}
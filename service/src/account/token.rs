@@ -0,0 +1,104 @@
+use chrono::{Duration, Utc};
+use entity::account::{
+    access_token::{self, TokenKind},
+    user,
+};
+use rand::{rngs::OsRng, RngCore};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    ModelTrait, QueryFilter, QueryOrder, Set,
+};
+use sha2::{Digest, Sha256};
+
+const TOKEN_PREFIX: &str = "rt_";
+
+pub struct IssuedToken {
+    /// The plain token, only available right after it is issued.
+    pub token: String,
+    pub model: access_token::Model,
+}
+
+pub async fn issue_token(
+    db: &DatabaseConnection,
+    user_id: i32,
+    kind: TokenKind,
+    name: Option<String>,
+    ttl: Option<Duration>,
+) -> Result<IssuedToken, DbErr> {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let token = format!("{}{}", TOKEN_PREFIX, to_hex(&bytes));
+
+    let model = access_token::ActiveModel {
+        id: NotSet,
+        token_hash: Set(hash_token(&token)),
+        kind: Set(kind),
+        name: Set(name),
+        expires_at: Set(ttl.map(|ttl| Utc::now() + ttl)),
+        user_id: Set(user_id),
+        ..Default::default()
+    };
+    let model = model.insert(db).await?;
+
+    Ok(IssuedToken { token, model })
+}
+
+/// Looks up the token and its user, expired tokens are deleted and treated as unknown.
+pub async fn authenticate_token(
+    db: &DatabaseConnection,
+    token: &str,
+) -> Result<Option<(access_token::Model, user::Model)>, DbErr> {
+    let result = access_token::Entity::find()
+        .filter(access_token::Column::TokenHash.eq(hash_token(token)))
+        .find_also_related(user::Entity)
+        .one(db)
+        .await?;
+
+    match result {
+        Some((token, Some(user))) => {
+            if token.expires_at.is_some_and(|time| time < Utc::now()) {
+                token.delete(db).await?;
+                return Ok(None);
+            }
+
+            Ok(Some((token, user)))
+        }
+        _ => Ok(None),
+    }
+}
+
+pub async fn list_tokens(
+    db: &DatabaseConnection,
+    user_id: i32,
+    kind: TokenKind,
+) -> Result<Vec<access_token::Model>, DbErr> {
+    access_token::Entity::find()
+        .filter(access_token::Column::UserId.eq(user_id))
+        .filter(access_token::Column::Kind.eq(kind))
+        .order_by_asc(access_token::Column::Id)
+        .all(db)
+        .await
+}
+
+/// Revokes a token of the user, returns `false` if the user has no such token.
+pub async fn revoke_token(
+    db: &DatabaseConnection,
+    user_id: i32,
+    token_id: i32,
+) -> Result<bool, DbErr> {
+    let result = access_token::Entity::delete_many()
+        .filter(access_token::Column::Id.eq(token_id))
+        .filter(access_token::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
+
+fn hash_token(token: &str) -> String {
+    to_hex(&Sha256::digest(token.as_bytes()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,137 @@
+use anyhow::anyhow;
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use entity::account::{user, user_role::Role};
+use rand::rngs::OsRng;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, Set, SqlErr,
+};
+
+use super::role::grant_role;
+
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+/// Granted for all languages to every new user, so that anyone can suggest translations.
+pub const DEFAULT_ROLE: Role = Role::Translator;
+const MAX_USERNAME_LENGTH: usize = 32;
+
+/// Usernames are 3-32 characters of letters, digits, `_`, `-` or `.`.
+pub fn is_valid_username(username: &str) -> bool {
+    let length = username.chars().count();
+    let valid_chars = username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+
+    (3..=MAX_USERNAME_LENGTH).contains(&length) && valid_chars
+}
+
+pub async fn find_user(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Option<user::Model>, DbErr> {
+    user::Entity::find_by_id(user_id).one(db).await
+}
+
+pub async fn find_user_by_username(
+    db: &DatabaseConnection,
+    username: &str,
+) -> Result<Option<user::Model>, DbErr> {
+    user::Entity::find()
+        .filter(user::Column::Username.eq(username))
+        .one(db)
+        .await
+}
+
+/// Creates a new user, the password is optional for users signing up with OAuth.
+pub async fn create_user(
+    db: &DatabaseConnection,
+    username: String,
+    display_name: String,
+    password: Option<&str>,
+) -> anyhow::Result<user::Model> {
+    let password_hash = password.map(hash_password).transpose()?;
+
+    let model = user::ActiveModel {
+        id: NotSet,
+        username: Set(username),
+        display_name: Set(display_name),
+        password_hash: Set(password_hash),
+        ..Default::default()
+    };
+    let user = model.insert(db).await?;
+    grant_role(db, user.id, DEFAULT_ROLE, None).await?;
+
+    Ok(user)
+}
+
+/// Returns the user if the username exists and the password matches.
+pub async fn verify_password(
+    db: &DatabaseConnection,
+    username: &str,
+    password: &str,
+) -> anyhow::Result<Option<user::Model>> {
+    let user = find_user_by_username(db, username).await?;
+
+    if let Some(user) = user {
+        if let Some(password_hash) = &user.password_hash {
+            let parsed_hash = PasswordHash::new(password_hash).map_err(|err| anyhow!(err))?;
+            if Argon2::default()
+                .verify_password(password.as_bytes(), &parsed_hash)
+                .is_ok()
+            {
+                return Ok(Some(user));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Returns an available username based on the preferred one, used when users sign up
+/// with OAuth and their name at the provider is already taken here.
+pub async fn available_username(db: &DatabaseConnection, preferred: &str) -> Result<String, DbErr> {
+    // Names from elsewhere may contain characters or lengths usernames don't allow,
+    // room is left for the suffix.
+    let mut base: String = preferred
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(MAX_USERNAME_LENGTH - 4)
+        .collect();
+    if base.len() < 3 {
+        base = format!("user_{}", base).trim_end_matches('_').to_string();
+    }
+
+    let mut username = base.clone();
+    let mut suffix = 1;
+    while find_user_by_username(db, &username).await?.is_some() {
+        suffix += 1;
+        username = format!("{}{}", base, suffix);
+    }
+
+    Ok(username)
+}
+
+/// Whether creating a user failed because the username was taken in the meantime.
+pub fn is_username_taken(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<DbErr>().and_then(DbErr::sql_err),
+        Some(SqlErr::UniqueConstraintViolation(_))
+    )
+}
+
+fn hash_password(password: &str) -> anyhow::Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|err| anyhow!(err))?;
+
+    Ok(hash.to_string())
+}
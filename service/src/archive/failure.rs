@@ -0,0 +1,62 @@
+use entity::minecraft::archive_failure;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, Set,
+};
+
+use super::resource::ArchiveProvider;
+
+/// Records why archiving the mod failed, replacing its previous failure.
+pub async fn record_archive_failure(
+    db: &DatabaseConnection,
+    provider: &ArchiveProvider,
+    identifier: &str,
+    error: &anyhow::Error,
+    requester: Option<String>,
+) -> Result<archive_failure::Model, DbErr> {
+    clear_archive_failure(db, provider, identifier).await?;
+
+    let model = archive_failure::ActiveModel {
+        id: NotSet,
+        provider_type: Set(provider.to_mod_provider_type()),
+        identifier: Set(identifier.to_string()),
+        error: Set(format!("{:#}", error)),
+        requester: Set(requester),
+        ..Default::default()
+    };
+    model.insert(db).await
+}
+
+/// Removes the recorded failure once the mod is archived.
+pub async fn clear_archive_failure(
+    db: &DatabaseConnection,
+    provider: &ArchiveProvider,
+    identifier: &str,
+) -> Result<(), DbErr> {
+    archive_failure::Entity::delete_many()
+        .filter(archive_failure::Column::ProviderType.eq(provider.to_mod_provider_type()))
+        .filter(archive_failure::Column::Identifier.eq(identifier))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Lists the mods that failed to be archived, the most recent failure first.
+pub async fn list_archive_failures(
+    db: &DatabaseConnection,
+) -> Result<Vec<archive_failure::Model>, DbErr> {
+    archive_failure::Entity::find()
+        .order_by_desc(archive_failure::Column::FailedAt)
+        .all(db)
+        .await
+}
+
+pub async fn find_archive_failure(
+    db: &DatabaseConnection,
+    failure_id: i32,
+) -> Result<Option<archive_failure::Model>, DbErr> {
+    archive_failure::Entity::find_by_id(failure_id)
+        .one(db)
+        .await
+}
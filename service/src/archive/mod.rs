@@ -1,2 +1,6 @@
+pub mod failure;
+pub mod modpack;
 pub mod resource;
-pub mod task;
\ No newline at end of file
+pub mod task;
+pub mod upload;
+pub mod vanilla;
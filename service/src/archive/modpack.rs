@@ -0,0 +1,461 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Cursor, Read},
+    sync::Mutex,
+    time::Duration,
+};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use entity::{
+    entry::{
+        text_entry,
+        translation::{self, TranslationStatus},
+    },
+    minecraft::{minecraft_mod, mod_loader::ModLoader, mod_provider, modpack, modpack_mod},
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    ModelTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, TryIntoModel,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use zip::ZipArchive;
+
+use super::{
+    resource::{parse_loader, ArchiveProvider},
+    task::{get_task, ArchiveTaskStage},
+};
+
+lazy_static! {
+    static ref MODPACK_TASKS: Mutex<HashMap<String, ModpackTask>> = Mutex::new(HashMap::new());
+    static ref MODRINTH_CDN_URL: Regex =
+        Regex::new(r"^https://cdn\.modrinth\.com/data/([A-Za-z0-9]+)/").unwrap();
+}
+
+const FINISHED_TASK_RETENTION: Duration = Duration::from_secs(10 * 60);
+
+/// The mods of a modpack, read from a Modrinth `modrinth.index.json` or a
+/// CurseForge `manifest.json`.
+#[derive(Debug)]
+pub struct ModpackManifest {
+    pub name: String,
+    pub version: Option<String>,
+    pub provider: ArchiveProvider,
+    pub game_version: String,
+    pub loader: Option<ModLoader>,
+    pub mods: Vec<ModpackEntry>,
+}
+
+#[derive(Debug)]
+pub struct ModpackEntry {
+    /// Project ID of the mod on the provider, if it could be resolved.
+    pub identifier: Option<String>,
+    /// File path or file ID of the mod in the manifest, to report unresolved mods.
+    pub file: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModrinthIndex {
+    name: String,
+    version_id: Option<String>,
+    files: Vec<ModrinthIndexFile>,
+    dependencies: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct ModrinthIndexFile {
+    path: String,
+    downloads: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeManifest {
+    name: String,
+    version: Option<String>,
+    minecraft: CurseForgeMinecraft,
+    files: Vec<CurseForgeFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeMinecraft {
+    version: String,
+    #[serde(default)]
+    mod_loaders: Vec<CurseForgeModLoader>,
+}
+
+#[derive(Deserialize)]
+struct CurseForgeModLoader {
+    /// The loader and its version, e.g. `forge-47.1.0`.
+    id: String,
+    #[serde(default)]
+    primary: bool,
+}
+
+#[derive(Deserialize)]
+struct CurseForgeFile {
+    #[serde(rename = "projectID")]
+    project_id: u64,
+    #[serde(rename = "fileID")]
+    file_id: u64,
+}
+
+/// Parses a `.mrpack` or CurseForge modpack zip, or the bare manifest of either.
+pub fn parse_modpack(data: &[u8]) -> anyhow::Result<ModpackManifest> {
+    if !data.starts_with(b"PK") {
+        return parse_manifest(data);
+    }
+
+    let mut archive = ZipArchive::new(Cursor::new(data))?;
+    let name = ["modrinth.index.json", "manifest.json"]
+        .into_iter()
+        .find(|name| archive.by_name(name).is_ok())
+        .context("The archive contains no modpack manifest")?;
+    let mut content = Vec::new();
+    archive.by_name(name)?.read_to_end(&mut content)?;
+
+    parse_manifest(&content)
+}
+
+fn parse_manifest(data: &[u8]) -> anyhow::Result<ModpackManifest> {
+    let value = serde_json::from_slice::<serde_json::Value>(data)?;
+
+    if value.get("formatVersion").is_some() && value.get("game").is_some() {
+        parse_modrinth_index(serde_json::from_value(value)?)
+    } else if value.get("manifestType").is_some() {
+        parse_curseforge_manifest(serde_json::from_value(value)?)
+    } else {
+        anyhow::bail!("Unknown modpack manifest")
+    }
+}
+
+fn parse_modrinth_index(index: ModrinthIndex) -> anyhow::Result<ModpackManifest> {
+    let game_version = index
+        .dependencies
+        .get("minecraft")
+        .context("The modpack does not depend on Minecraft")?
+        .clone();
+    let loader = index
+        .dependencies
+        .keys()
+        .find_map(|name| parse_loader(name.trim_end_matches("-loader")));
+
+    let mods = index
+        .files
+        .into_iter()
+        // Resource packs and shaders have no language files to translate.
+        .filter(|file| file.path.starts_with("mods/"))
+        .map(|file| ModpackEntry {
+            identifier: file.downloads.iter().find_map(|url| {
+                MODRINTH_CDN_URL
+                    .captures(url)
+                    .map(|captures| captures[1].to_string())
+            }),
+            file: file.path,
+        })
+        .collect();
+
+    Ok(ModpackManifest {
+        name: index.name,
+        version: index.version_id,
+        provider: ArchiveProvider::Modrinth,
+        game_version,
+        loader,
+        mods,
+    })
+}
+
+fn parse_curseforge_manifest(manifest: CurseForgeManifest) -> anyhow::Result<ModpackManifest> {
+    let loaders = &manifest.minecraft.mod_loaders;
+    let loader = loaders
+        .iter()
+        .find(|loader| loader.primary)
+        .or(loaders.first())
+        .and_then(|loader| parse_loader(loader.id.split('-').next().unwrap_or_default()));
+
+    let mods = manifest
+        .files
+        .into_iter()
+        .map(|file| ModpackEntry {
+            identifier: Some(file.project_id.to_string()),
+            file: format!("{}/{}", file.project_id, file.file_id),
+        })
+        .collect();
+
+    Ok(ModpackManifest {
+        name: manifest.name,
+        version: manifest.version,
+        provider: ArchiveProvider::CurseForge,
+        game_version: manifest.minecraft.version,
+        loader,
+        mods,
+    })
+}
+
+/// Creates the modpack, or updates the one with the same name and provider.
+pub async fn save_modpack(
+    db: &DatabaseConnection,
+    manifest: &ModpackManifest,
+) -> Result<modpack::Model, DbErr> {
+    let provider_type = manifest.provider.to_mod_provider_type();
+    let existing = modpack::Entity::find()
+        .filter(modpack::Column::ProviderType.eq(provider_type.clone()))
+        .filter(modpack::Column::Name.eq(&manifest.name))
+        .one(db)
+        .await?;
+
+    let mut model = match existing {
+        Some(existing) => existing.into(),
+        None => modpack::ActiveModel {
+            name: Set(manifest.name.clone()),
+            provider_type: Set(provider_type),
+            ..Default::default()
+        },
+    };
+    model.version = Set(manifest.version.clone());
+    model.game_version = Set(manifest.game_version.clone());
+    model.loader = Set(manifest.loader.clone());
+
+    model.save(db).await?.try_into_model()
+}
+
+/// Unlinks every mod of the modpack, e.g. before linking those of an updated manifest.
+pub async fn unlink_modpack_mods(db: &DatabaseConnection, modpack_id: i32) -> Result<(), DbErr> {
+    modpack_mod::Entity::delete_many()
+        .filter(modpack_mod::Column::ModpackId.eq(modpack_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Returns the ID of the mod archived from the provider, if any.
+pub async fn find_provider_mod_id(
+    db: &DatabaseConnection,
+    provider: &ArchiveProvider,
+    identifier: &str,
+) -> Result<Option<i32>, DbErr> {
+    let provider =
+        mod_provider::Entity::find_by_id((provider.to_mod_provider_type(), identifier.to_string()))
+            .one(db)
+            .await?;
+
+    Ok(provider.map(|provider| provider.mod_id))
+}
+
+pub async fn link_modpack_mod(
+    db: &DatabaseConnection,
+    modpack_id: i32,
+    mod_id: i32,
+) -> Result<(), DbErr> {
+    let model = modpack_mod::ActiveModel {
+        modpack_id: Set(modpack_id),
+        mod_id: Set(mod_id),
+    };
+    modpack_mod::Entity::insert(model)
+        .on_conflict(
+            OnConflict::columns([modpack_mod::Column::ModpackId, modpack_mod::Column::ModId])
+                .do_nothing()
+                .to_owned(),
+        )
+        .do_nothing()
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn list_modpacks(db: &DatabaseConnection) -> Result<Vec<modpack::Model>, DbErr> {
+    modpack::Entity::find()
+        .order_by_asc(modpack::Column::Name)
+        .all(db)
+        .await
+}
+
+pub async fn find_modpack(
+    db: &DatabaseConnection,
+    modpack_id: i32,
+) -> Result<Option<modpack::Model>, DbErr> {
+    modpack::Entity::find_by_id(modpack_id).one(db).await
+}
+
+pub async fn find_modpack_mods(
+    db: &DatabaseConnection,
+    modpack: &modpack::Model,
+) -> Result<Vec<minecraft_mod::Model>, DbErr> {
+    modpack
+        .find_related(minecraft_mod::Entity)
+        .order_by_asc(minecraft_mod::Column::Id)
+        .all(db)
+        .await
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModpackCoverage {
+    pub language: String,
+    pub total_entries: u64,
+    /// Entries with an approved translation in the language.
+    pub translated_entries: u64,
+}
+
+/// Counts the entries of the given mods and how many of them are translated.
+pub async fn compute_coverage(
+    db: &DatabaseConnection,
+    mod_ids: &[i32],
+    language: &str,
+) -> Result<ModpackCoverage, DbErr> {
+    let total_entries = text_entry::Entity::find()
+        .filter(text_entry::Column::ModId.is_in(mod_ids.to_vec()))
+        .count(db)
+        .await?;
+
+    let translated_keys: HashSet<String> = translation::Entity::find()
+        .select_only()
+        .column(translation::Column::Key)
+        .inner_join(text_entry::Entity)
+        .filter(text_entry::Column::ModId.is_in(mod_ids.to_vec()))
+        .filter(translation::Column::Language.eq(language))
+        .filter(translation::Column::Status.eq(TranslationStatus::Approved))
+        .into_tuple::<String>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+    Ok(ModpackCoverage {
+        language: language.to_string(),
+        total_entries,
+        translated_entries: translated_keys.len() as u64,
+    })
+}
+
+/// Aggregate of the archive tasks started for the mods of a modpack.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct ModpackTask {
+    pub id: String,
+    pub modpack_id: i32,
+    pub stage: ArchiveTaskStage,
+    pub progress: f32,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Username of the user who requested the task.
+    pub requester: Option<String>,
+    /// IDs of the archive tasks of the mods, see `/archives/tasks/{task_id}`.
+    pub task_ids: Vec<String>,
+    pub completed_tasks: usize,
+    pub failed_tasks: usize,
+}
+
+impl ModpackTask {
+    /// Creates a task that is preparing, its archive tasks are added by `start_modpack_task`.
+    pub fn new(modpack_id: i32, requester: Option<String>) -> Self {
+        Self {
+            id: format!("modpack-{}", modpack_id),
+            modpack_id,
+            stage: ArchiveTaskStage::Preparing,
+            progress: 0.0,
+            started_at: Utc::now(),
+            finished_at: None,
+            requester,
+            task_ids: Vec::new(),
+            completed_tasks: 0,
+            failed_tasks: 0,
+        }
+    }
+}
+
+/// Registers a new modpack task, returns `false` if a task for the same modpack is still running.
+pub fn insert_modpack_task(task: ModpackTask) -> bool {
+    let mut tasks = MODPACK_TASKS.lock().unwrap();
+    if tasks
+        .get(&task.id)
+        .is_some_and(|task| !task.stage.is_finished())
+    {
+        return false;
+    }
+
+    tasks.insert(task.id.clone(), task);
+    true
+}
+
+/// Starts tracking the archive tasks of the mods of a prepared modpack task.
+pub fn start_modpack_task(task_id: &str, task_ids: Vec<String>) {
+    if let Some(task) = MODPACK_TASKS.lock().unwrap().get_mut(task_id) {
+        task.stage = ArchiveTaskStage::Downloading;
+        task.task_ids = task_ids;
+    }
+}
+
+/// Removes a prepared modpack task that will not start any archive task.
+pub fn remove_modpack_task(task_id: &str) {
+    MODPACK_TASKS.lock().unwrap().remove(task_id);
+}
+
+/// Returns the modpack task, with its progress computed from the running archive tasks.
+pub fn get_modpack_task(task_id: &str) -> Option<ModpackTask> {
+    let mut task = MODPACK_TASKS.lock().unwrap().get(task_id)?.clone();
+
+    if !task.stage.is_finished() && !task.task_ids.is_empty() {
+        // Removed archive tasks have finished a while ago.
+        let progress: f32 = task
+            .task_ids
+            .iter()
+            .map(|id| get_task(id).map_or(1.0, |task| task.progress))
+            .sum();
+        task.progress = progress / task.task_ids.len() as f32;
+    }
+
+    Some(task)
+}
+
+/// Records that one archive task of the modpack finished, the modpack task is
+/// completed once all of them finished.
+pub fn finish_modpack_member(task_id: &str, succeeded: bool) {
+    let mut tasks = MODPACK_TASKS.lock().unwrap();
+    let Some(task) = tasks.get_mut(task_id) else {
+        return;
+    };
+
+    if succeeded {
+        task.completed_tasks += 1;
+    } else {
+        task.failed_tasks += 1;
+    }
+
+    if task.completed_tasks + task.failed_tasks >= task.task_ids.len() {
+        complete_modpack_task(task);
+        schedule_task_removal(task);
+    }
+}
+
+fn complete_modpack_task(task: &mut ModpackTask) {
+    // Only a modpack of which no mod could be archived counts as failed.
+    task.stage = if task.failed_tasks > 0 && task.completed_tasks == 0 {
+        ArchiveTaskStage::Failed
+    } else {
+        ArchiveTaskStage::Completed
+    };
+    task.progress = 1.0;
+    task.finished_at = Some(Utc::now());
+}
+
+fn schedule_task_removal(task: &ModpackTask) {
+    let task_id = task.id.clone();
+    let started_at = task.started_at;
+    tokio::spawn(async move {
+        tokio::time::sleep(FINISHED_TASK_RETENTION).await;
+
+        let mut tasks = MODPACK_TASKS.lock().unwrap();
+        // The task may have been replaced by a new run in the meantime.
+        if tasks
+            .get(&task_id)
+            .is_some_and(|task| task.started_at == started_at)
+        {
+            tasks.remove(&task_id);
+        }
+    });
+}
@@ -14,17 +14,22 @@ use ferinth::{
 };
 use sea_orm::{ActiveModelTrait, ActiveValue::NotSet, DatabaseConnection, EntityTrait, Set};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::minecraft::version;
 
 use super::task::get_archives_directory;
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ArchiveProvider {
     CurseForge,
     Modrinth,
+    /// Mods uploaded directly as jar files.
+    Custom,
+    /// The vanilla game, imported from local game assets.
+    Vanilla,
 }
 
 impl ArchiveProvider {
@@ -32,11 +37,22 @@ impl ArchiveProvider {
         match self {
             ArchiveProvider::CurseForge => ModProviderType::CurseForge,
             ArchiveProvider::Modrinth => ModProviderType::Modrinth,
+            ArchiveProvider::Custom => ModProviderType::Custom,
+            ArchiveProvider::Vanilla => ModProviderType::Vanilla,
+        }
+    }
+
+    pub fn from_mod_provider_type(provider_type: &ModProviderType) -> Self {
+        match provider_type {
+            ModProviderType::CurseForge => ArchiveProvider::CurseForge,
+            ModProviderType::Modrinth => ArchiveProvider::Modrinth,
+            ModProviderType::Custom => ArchiveProvider::Custom,
+            ModProviderType::Vanilla => ArchiveProvider::Vanilla,
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ArchiveResourceInfo {
     pub identifier: Option<String>,
     pub name: String,
@@ -118,6 +134,8 @@ pub async fn validate_resource_identifier(
 
             Ok(project.project_type == ProjectType::Mod)
         }
+        // Uploaded mods and game assets have no source to fetch from.
+        ArchiveProvider::Custom | ArchiveProvider::Vanilla => Ok(false),
     }
 }
 
@@ -128,6 +146,8 @@ pub async fn fetch_downloads(
     let result = match provider {
         ArchiveProvider::CurseForge => unimplemented!(),
         ArchiveProvider::Modrinth => fetch_modrinth_downloads(identifier).await,
+        ArchiveProvider::Custom => anyhow::bail!("Custom mods can only be uploaded"),
+        ArchiveProvider::Vanilla => anyhow::bail!("Vanilla assets are imported from local files"),
     };
 
     Ok(result?.into_iter().collect())
@@ -162,12 +182,9 @@ async fn fetch_modrinth_downloads(identifier: &str) -> anyhow::Result<HashSet<Mo
                 downloads.insert(ModDownloadInfo {
                     url: file.url.to_string(),
                     size: file.size,
-                    loader: match loader.as_str() {
-                        "fabric" => ModLoader::Fabric,
-                        "forge" => ModLoader::Forge,
-                        "neoforge" => ModLoader::Forge,
-                        "quilt" => ModLoader::Quilt,
-                        _ => continue,
+                    loader: match parse_loader(loader) {
+                        Some(loader) => loader,
+                        None => continue,
                     },
                     game_version: version::to_semver(game_ver)?,
                     path: dir.join(Uuid::new_v4().to_string()),
@@ -179,6 +196,17 @@ async fn fetch_modrinth_downloads(identifier: &str) -> anyhow::Result<HashSet<Mo
     Ok(downloads)
 }
 
+/// Parses a loader name as used by Modrinth (e.g. `fabric`), case-insensitively.
+/// NeoForge is treated as Forge.
+pub fn parse_loader(name: &str) -> Option<ModLoader> {
+    match name.to_lowercase().as_str() {
+        "fabric" => Some(ModLoader::Fabric),
+        "forge" | "neoforge" => Some(ModLoader::Forge),
+        "quilt" => Some(ModLoader::Quilt),
+        _ => None,
+    }
+}
+
 pub async fn create_mod_model(
     db: &DatabaseConnection,
     provider: &ArchiveProvider,
@@ -241,6 +269,12 @@ pub async fn create_provider_model(
                 ..Default::default()
             }
         }
+        ArchiveProvider::Custom => {
+            anyhow::bail!("Custom providers are created by create_custom_provider_model")
+        }
+        ArchiveProvider::Vanilla => {
+            anyhow::bail!("The vanilla provider is created by create_vanilla_provider_model")
+        }
     };
 
     let model = if is_existing {
@@ -1,39 +1,100 @@
 use std::{
+    cmp::Reverse,
     collections::{HashMap, HashSet},
     env::temp_dir,
     fs::{self, create_dir_all},
     io::BufReader,
     path::PathBuf,
-    sync::Mutex,
+    sync::{Mutex, OnceLock},
+    time::Duration,
 };
 
+use chrono::{DateTime, Utc};
 use entity::{
-    entry::text_entry,
-    minecraft::{mod_loader::{ModLoader, ModLoaderVec}, minecraft_mod},
+    entry::{text_entry, text_entry_revision, text_entry_variant, translation},
+    minecraft::{
+        minecraft_mod,
+        mod_loader::{ModLoader, ModLoaderVec},
+    },
     misc::StringVec,
 };
 use lazy_static::lazy_static;
-use sea_orm::{sea_query::OnConflict, DatabaseConnection, EntityTrait, Set};
-use serde::Serialize;
+use sea_orm::{
+    sea_query::OnConflict, ActiveValue::NotSet, ColumnTrait, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter, QuerySelect, QueryTrait, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
 use zip::ZipArchive;
 
-use super::resource::ModDownloadInfo;
-use crate::minecraft::file_metadata::{parse_language_file, parse_namespace};
+use super::resource::{
+    create_mod_model, create_provider_model, fetch_downloads, ArchiveProvider, ModDownloadInfo,
+};
+use crate::{
+    minecraft::file_metadata::{parse_language_file, parse_namespace},
+    translation::{memory::clear_memories, stats::invalidate_mod_stats},
+};
 
 lazy_static! {
-    pub static ref ARCHIVE_TASKS: Mutex<HashMap<String, ArchiveTask>> = Mutex::new(HashMap::new());
+    static ref ARCHIVE_TASKS: Mutex<HashMap<String, TaskEntry>> = Mutex::new(HashMap::new());
+}
+
+/// How long a finished task is kept around, so that every client polling or
+/// subscribing to it has a chance to see the final state.
+const FINISHED_TASK_RETENTION: Duration = Duration::from_secs(10 * 60);
+const TASK_EVENTS_CAPACITY: usize = 32;
+
+struct TaskEntry {
+    task: ArchiveTask,
+    events: broadcast::Sender<ArchiveTask>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, ToSchema)]
 pub struct ArchiveTask {
+    pub id: String,
+    pub provider: ArchiveProvider,
+    pub identifier: String,
     pub stage: ArchiveTaskStage,
     pub progress: f32,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Username of the user who requested the task.
+    pub requester: Option<String>,
 
     // This field is only set when the task is completed.
+    #[schema(value_type = Option<MinecraftMod>)]
     pub mc_mod: Option<minecraft_mod::Model>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+impl ArchiveTask {
+    pub fn new(provider: ArchiveProvider, identifier: String, requester: Option<String>) -> Self {
+        let id = format!(
+            "{}-{}",
+            serde_json::to_value(&provider).unwrap().as_str().unwrap(),
+            identifier
+        );
+
+        Self {
+            id,
+            provider,
+            identifier,
+            stage: ArchiveTaskStage::Preparing,
+            progress: 0.05,
+            started_at: Utc::now(),
+            finished_at: None,
+            requester,
+            mc_mod: None,
+        }
+    }
+
+    /// Time spent on the task so far, or in total if it is finished.
+    pub fn elapsed(&self) -> chrono::Duration {
+        self.finished_at.unwrap_or_else(Utc::now) - self.started_at
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ArchiveTaskStage {
     Preparing,
@@ -51,18 +112,124 @@ impl ArchiveTaskStage {
     }
 }
 
-pub fn get_archives_directory() -> PathBuf {
+#[derive(Debug, Default)]
+pub struct ArchiveTaskFilter {
+    pub stage: Option<ArchiveTaskStage>,
+    pub provider: Option<ArchiveProvider>,
+    pub started_after: Option<DateTime<Utc>>,
+    pub started_before: Option<DateTime<Utc>>,
+}
+
+impl ArchiveTaskFilter {
+    fn matches(&self, task: &ArchiveTask) -> bool {
+        self.stage.as_ref().is_none_or(|stage| &task.stage == stage)
+            && self
+                .provider
+                .as_ref()
+                .is_none_or(|provider| &task.provider == provider)
+            && self
+                .started_after
+                .is_none_or(|time| task.started_at >= time)
+            && self
+                .started_before
+                .is_none_or(|time| task.started_at <= time)
+    }
+}
+
+static ARCHIVES_DIRECTORY: OnceLock<PathBuf> = OnceLock::new();
+
+pub fn default_archives_directory() -> PathBuf {
     temp_dir().join("rtranslator-backend").join("archives")
 }
 
-pub fn update_task_progress(task_id: &str, stage: Option<ArchiveTaskStage>, progress: f32) {
-    let mut tasks = ARCHIVE_TASKS.lock().unwrap();
-    let task = tasks.get_mut(task_id).unwrap();
+/// Sets where the mod files are stored while they are archived, must be called before
+/// the first task runs. Returns `false` if the directory was already set.
+pub fn set_archives_directory(path: PathBuf) -> bool {
+    ARCHIVES_DIRECTORY.set(path).is_ok()
+}
+
+pub fn get_archives_directory() -> PathBuf {
+    ARCHIVES_DIRECTORY
+        .get_or_init(default_archives_directory)
+        .clone()
+}
+
+/// Limits applied while downloading the files of a mod.
+#[derive(Debug, Clone)]
+pub struct DownloadOptions {
+    pub max_simultaneous: usize,
+    /// Timeout of each download, from sending the request to the last byte.
+    pub timeout: Duration,
+}
 
-    if let Some(stage) = stage {
-        task.stage = stage;
+/// Registers a new task, returns `false` if a task with the same ID is still running.
+/// A finished task with the same ID is replaced.
+pub fn insert_task(task: ArchiveTask) -> bool {
+    let mut tasks = ARCHIVE_TASKS.lock().unwrap();
+    if let Some(entry) = tasks.get(&task.id) {
+        if !entry.task.stage.is_finished() {
+            return false;
+        }
     }
-    task.progress = progress;
+
+    let (events, _) = broadcast::channel(TASK_EVENTS_CAPACITY);
+    tasks.insert(task.id.clone(), TaskEntry { task, events });
+    true
+}
+
+pub fn get_task(task_id: &str) -> Option<ArchiveTask> {
+    let tasks = ARCHIVE_TASKS.lock().unwrap();
+    tasks.get(task_id).map(|entry| entry.task.clone())
+}
+
+/// Returns all known tasks matching the filter, the most recently started first.
+pub fn list_tasks(filter: &ArchiveTaskFilter) -> Vec<ArchiveTask> {
+    let tasks = ARCHIVE_TASKS.lock().unwrap();
+    let mut tasks: Vec<ArchiveTask> = tasks
+        .values()
+        .map(|entry| &entry.task)
+        .filter(|task| filter.matches(task))
+        .cloned()
+        .collect();
+
+    tasks.sort_by_key(|task| Reverse(task.started_at));
+    tasks
+}
+
+/// Returns the current state of the task and a receiver for all following changes.
+/// The channel is closed once the task is removed.
+pub fn subscribe_task(task_id: &str) -> Option<(ArchiveTask, broadcast::Receiver<ArchiveTask>)> {
+    let tasks = ARCHIVE_TASKS.lock().unwrap();
+    tasks
+        .get(task_id)
+        .map(|entry| (entry.task.clone(), entry.events.subscribe()))
+}
+
+pub fn update_task_progress(task_id: &str, stage: Option<ArchiveTaskStage>, progress: f32) {
+    modify_task(task_id, |task| {
+        if let Some(stage) = stage {
+            task.stage = stage;
+        }
+        task.progress = progress;
+    });
+}
+
+pub fn complete_task(task_id: &str, mc_mod: minecraft_mod::Model) {
+    modify_task(task_id, |task| {
+        task.stage = ArchiveTaskStage::Completed;
+        task.progress = 1.0;
+        task.finished_at = Some(Utc::now());
+        task.mc_mod = Some(mc_mod);
+    });
+    schedule_task_removal(task_id);
+}
+
+pub fn fail_task(task_id: &str) {
+    modify_task(task_id, |task| {
+        task.stage = ArchiveTaskStage::Failed;
+        task.finished_at = Some(Utc::now());
+    });
+    schedule_task_removal(task_id);
 }
 
 pub fn remove_task(task_id: &str) {
@@ -70,25 +237,64 @@ pub fn remove_task(task_id: &str) {
     tasks.remove(task_id);
 }
 
+fn modify_task(task_id: &str, modify: impl FnOnce(&mut ArchiveTask)) {
+    let mut tasks = ARCHIVE_TASKS.lock().unwrap();
+    // The task may have been removed once its retention period ended.
+    let Some(entry) = tasks.get_mut(task_id) else {
+        return;
+    };
+
+    modify(&mut entry.task);
+    // Sending only fails when nobody is subscribed, which is fine.
+    let _ = entry.events.send(entry.task.clone());
+}
+
+fn schedule_task_removal(task_id: &str) {
+    let events = {
+        let tasks = ARCHIVE_TASKS.lock().unwrap();
+        match tasks.get(task_id) {
+            Some(entry) => entry.events.clone(),
+            None => return,
+        }
+    };
+
+    let task_id = task_id.to_string();
+    tokio::spawn(async move {
+        tokio::time::sleep(FINISHED_TASK_RETENTION).await;
+
+        let mut tasks = ARCHIVE_TASKS.lock().unwrap();
+        // The task may have been replaced by a new run in the meantime.
+        if tasks
+            .get(&task_id)
+            .is_some_and(|entry| entry.events.same_channel(&events))
+        {
+            tasks.remove(&task_id);
+        }
+    });
+}
+
 pub async fn download_files(
     downloads: &[ModDownloadInfo],
-    max_simultaneous_downloads: usize,
+    options: &DownloadOptions,
     progress_changed: impl Fn(f32),
 ) -> anyhow::Result<()> {
     let total_size: usize = downloads.iter().map(|x| x.size).sum();
     let mut downloaded_size = 0;
 
     create_dir_all(get_archives_directory())?;
+    let client = reqwest::Client::builder()
+        .timeout(options.timeout)
+        .build()?;
 
-    for chuck in downloads.chunks(max_simultaneous_downloads) {
+    for chuck in downloads.chunks(options.max_simultaneous) {
         let mut handles = Vec::with_capacity(chuck.len());
 
         for (index, info) in chuck.iter().enumerate() {
-            let url = info.url.clone();
+            let request = client.get(&info.url);
             let path = info.path.clone();
 
             let handle = tokio::spawn(async move {
-                let bytes = reqwest::get(url).await?.bytes().await?;
+                let bytes = request.send().await?.bytes().await?;
                 tokio::fs::write(path, bytes).await?;
 
                 Ok::<_, anyhow::Error>(index)
@@ -108,13 +314,62 @@ pub async fn download_files(
     Ok(())
 }
 
+/// Fetches, parses and saves the entries of every release of a mod from its provider.
+/// The progress is reported with the stage it enters, if any.
+pub async fn archive_mod(
+    db: &DatabaseConnection,
+    provider: &ArchiveProvider,
+    identifier: String,
+    download_options: &DownloadOptions,
+    progress_changed: impl Fn(Option<ArchiveTaskStage>, f32),
+) -> anyhow::Result<minecraft_mod::Model> {
+    // Preparing download list.
+    let mut downloads = fetch_downloads(provider, &identifier).await?;
+    downloads.sort_by(|a, b| a.game_version.cmp(&b.game_version));
+
+    // Downloading mod files.
+    progress_changed(Some(ArchiveTaskStage::Downloading), 0.1);
+
+    download_files(&downloads, download_options, |progress| {
+        progress_changed(None, 0.1 + progress * 0.75)
+    })
+    .await?;
+
+    // Extracting and parsing language files.
+    progress_changed(Some(ArchiveTaskStage::Extracting), 0.55);
+    let text_entries = parse_language_files(&downloads, |progress| {
+        progress_changed(None, 0.85 + progress * 0.1)
+    })
+    .await?;
+
+    // Saving to database.
+    progress_changed(Some(ArchiveTaskStage::Saving), 0.95);
+    let mc_mod =
+        create_mod_model(db, provider, identifier.clone(), text_entries.is_empty()).await?;
+    create_provider_model(db, provider, identifier, mc_mod.id).await?;
+    save_text_entries(db, text_entries, mc_mod.id).await?;
+
+    Ok(mc_mod)
+}
+
 #[derive(Debug)]
 pub struct TextEntryData {
     pub key: String,
+    /// The value in the latest game version.
     pub value: String,
     pub namespaces: HashSet<String>,
     pub game_versions: HashSet<semver::Version>,
     pub loaders: HashSet<ModLoader>,
+    /// The distinct values across all game versions, e.g. when a string was
+    /// reworded between 1.16 and 1.20.
+    pub variants: Vec<TextEntryVariantData>,
+}
+
+#[derive(Debug)]
+pub struct TextEntryVariantData {
+    pub value: String,
+    pub game_versions: HashSet<semver::Version>,
+    pub loader: Option<ModLoader>,
 }
 
 pub async fn parse_language_files(
@@ -165,16 +420,39 @@ pub async fn parse_language_files(
             namespaces: HashSet::new(),
             game_versions: HashSet::new(),
             loaders: HashSet::new(),
+            variants: Vec::new(),
         };
 
-        for (index, _) in filtered_maps {
+        for (index, map) in filtered_maps {
             let namespace = namespaces.get(index).unwrap();
             let download_info = downloads.get(index).unwrap();
+            let value = map.get(key).unwrap();
 
             data.namespaces.insert(namespace.clone());
             data.game_versions
                 .insert(download_info.game_version.clone());
             data.loaders.insert(download_info.loader.clone());
+
+            // Each loader keeps its own game versions, a value may only exist for some of them.
+            let loader = Some(download_info.loader.clone());
+            let variant = match data
+                .variants
+                .iter_mut()
+                .find(|x| &x.value == value && x.loader == loader)
+            {
+                Some(variant) => variant,
+                None => {
+                    data.variants.push(TextEntryVariantData {
+                        value: value.to_string(),
+                        game_versions: HashSet::new(),
+                        loader,
+                    });
+                    data.variants.last_mut().unwrap()
+                }
+            };
+            variant
+                .game_versions
+                .insert(download_info.game_version.clone());
         }
 
         entries.push(data);
@@ -184,25 +462,61 @@ pub async fn parse_language_files(
     Ok(entries)
 }
 
+/// Returns the keys that belong to entries of other mods than the given one, sorted.
+/// Keys are unique across mods, so these cannot be saved for the mod. The mod is `None`
+/// if it is not saved yet.
+pub async fn find_keys_of_other_mods(
+    db: &DatabaseConnection,
+    mod_id: Option<i32>,
+    keys: &[String],
+) -> Result<Vec<String>, DbErr> {
+    let mut other_keys = Vec::new();
+    for chunk in keys.chunks(1000) {
+        let found: Vec<String> = text_entry::Entity::find()
+            .select_only()
+            .column(text_entry::Column::Key)
+            .filter(text_entry::Column::Key.is_in(chunk.iter().cloned()))
+            .apply_if(mod_id, |select, mod_id| {
+                select.filter(text_entry::Column::ModId.ne(mod_id))
+            })
+            .into_tuple()
+            .all(db)
+            .await?;
+        other_keys.extend(found);
+    }
+
+    other_keys.sort();
+    Ok(other_keys)
+}
+
+/// Saves the entries of the mod, replacing the values of existing entries.
+/// Replaced source values are kept as revisions and the translations of these
+/// entries are flagged as outdated.
 pub async fn save_text_entries(
     db: &DatabaseConnection,
     entries: Vec<TextEntryData>,
     mod_id: i32,
 ) -> Result<(), sea_orm::DbErr> {
     let mut models = Vec::with_capacity(entries.len());
+    let mut variants = Vec::new();
 
     for entry in entries {
+        for variant in entry.variants {
+            variants.push(text_entry_variant::ActiveModel {
+                id: NotSet,
+                key: Set(entry.key.clone()),
+                value: Set(variant.value),
+                game_versions: Set(to_string_vec(variant.game_versions)),
+                loader: Set(variant.loader),
+                mod_id: Set(mod_id),
+            });
+        }
+
         let model = text_entry::ActiveModel {
             key: Set(entry.key),
             value: Set(entry.value),
             namespaces: Set(StringVec(entry.namespaces.into_iter().collect())),
-            game_versions: Set(StringVec(
-                entry
-                    .game_versions
-                    .into_iter()
-                    .map(|x| x.to_string())
-                    .collect(),
-            )),
+            game_versions: Set(to_string_vec(entry.game_versions)),
             loaders: Set(ModLoaderVec(entry.loaders.into_iter().collect())),
             mod_id: Set(mod_id),
         };
@@ -210,7 +524,45 @@ pub async fn save_text_entries(
         models.push(model);
     }
 
+    let txn = db.begin().await?;
     for chuck in models.chunks(1000).map(|chunk| chunk.to_vec()) {
+        // Keep the previous source values of reworded entries and flag their translations.
+        let keys = chuck.iter().map(|model| model.key.clone().unwrap());
+        let previous: HashMap<String, String> = text_entry::Entity::find()
+            .filter(text_entry::Column::Key.is_in(keys))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|entry| (entry.key, entry.value))
+            .collect();
+        let changed: Vec<text_entry_revision::ActiveModel> = chuck
+            .iter()
+            .filter_map(|model| {
+                let key = model.key.as_ref();
+                let value = previous.get(key)?;
+                (value != model.value.as_ref()).then(|| text_entry_revision::ActiveModel {
+                    id: NotSet,
+                    key: Set(key.clone()),
+                    value: Set(value.clone()),
+                    // `insert_many` skips `before_save`.
+                    created_at: Set(Utc::now()),
+                    mod_id: Set(mod_id),
+                })
+            })
+            .collect();
+
+        if !changed.is_empty() {
+            let changed_keys = changed.iter().map(|model| model.key.clone().unwrap());
+            translation::Entity::update_many()
+                .col_expr(translation::Column::SourceChanged, true.into())
+                .filter(translation::Column::Key.is_in(changed_keys))
+                .exec(&txn)
+                .await?;
+            text_entry_revision::Entity::insert_many(changed)
+                .exec(&txn)
+                .await?;
+        }
+
         text_entry::Entity::insert_many(chuck)
             .on_conflict(
                 OnConflict::column(text_entry::Column::Key)
@@ -222,8 +574,37 @@ pub async fn save_text_entries(
                     ])
                     .to_owned(),
             )
-            .exec(db)
+            .exec(&txn)
             .await?;
     }
+
+    // The variants of the saved entries are replaced as a whole.
+    for chuck in variants.chunks(1000).map(|chunk| chunk.to_vec()) {
+        let keys = chuck
+            .iter()
+            .map(|model| model.key.clone().unwrap())
+            .collect::<HashSet<_>>();
+        text_entry_variant::Entity::delete_many()
+            .filter(text_entry_variant::Column::Key.is_in(keys))
+            .exec(&txn)
+            .await?;
+    }
+    for chuck in variants.chunks(1000).map(|chunk| chunk.to_vec()) {
+        text_entry_variant::Entity::insert_many(chuck)
+            .exec(&txn)
+            .await?;
+    }
+
+    txn.commit().await?;
+    invalidate_mod_stats(mod_id);
+    clear_memories();
     Ok(())
 }
+
+/// Sorted so that equal sets are stored equally.
+fn to_string_vec(game_versions: HashSet<semver::Version>) -> StringVec {
+    let mut game_versions: Vec<_> = game_versions.into_iter().collect();
+    game_versions.sort();
+
+    StringVec(game_versions.iter().map(|x| x.to_string()).collect())
+}
@@ -0,0 +1,412 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use entity::minecraft::{
+    minecraft_mod,
+    mod_provider::{self, ModProviderType},
+};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use uuid::Uuid;
+use zip::ZipArchive;
+
+use crate::minecraft::file_metadata::parse_namespace;
+
+use super::{
+    resource::{create_mod_model, ArchiveProvider, ModDownloadInfo},
+    task::{
+        find_keys_of_other_mods, get_archives_directory, parse_language_files, save_text_entries,
+        ArchiveTaskStage,
+    },
+};
+
+/// Details of an uploaded mod, stored as its `Custom` provider.
+#[derive(Debug, Clone)]
+pub struct CustomModInfo {
+    pub identifier: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub page_url: Option<String>,
+}
+
+/// Returns a new path in the archives directory to store an uploaded file at.
+pub fn create_upload_path() -> std::io::Result<PathBuf> {
+    let dir = get_archives_directory();
+    std::fs::create_dir_all(&dir)?;
+
+    Ok(dir.join(Uuid::new_v4().to_string()))
+}
+
+/// Checks that the uploaded file is a mod jar and returns its namespace.
+pub fn inspect_mod_archive(path: &Path) -> anyhow::Result<String> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(&file);
+    let mut archive = ZipArchive::new(reader)?;
+
+    parse_namespace(&mut archive)
+}
+
+pub async fn create_custom_provider_model(
+    db: &DatabaseConnection,
+    info: CustomModInfo,
+    mod_id: i32,
+) -> anyhow::Result<mod_provider::Model> {
+    let is_existing =
+        mod_provider::Entity::find_by_id((ModProviderType::Custom, info.identifier.clone()))
+            .one(db)
+            .await?
+            .is_some();
+
+    let model = mod_provider::ActiveModel {
+        identifier: Set(info.identifier),
+        provider_type: Set(ModProviderType::Custom),
+        display_name: Set(info.name),
+        description: Set(info.description.unwrap_or_default()),
+        image_url: Set(None),
+        page_url: Set(info.page_url.unwrap_or_default()),
+        mod_id: Set(mod_id),
+        ..Default::default()
+    };
+
+    let model = if is_existing {
+        model.update(db).await?
+    } else {
+        model.insert(db).await?
+    };
+    Ok(model)
+}
+
+/// Parses and saves the entries of uploaded mod jars, which are removed afterwards.
+/// The progress is reported with the stage it enters, if any.
+/// Keys of entries that belong to other mods are skipped and returned, sorted.
+pub async fn archive_uploaded_mod(
+    db: &DatabaseConnection,
+    info: CustomModInfo,
+    downloads: Vec<ModDownloadInfo>,
+    progress_changed: impl Fn(Option<ArchiveTaskStage>, f32),
+) -> anyhow::Result<(minecraft_mod::Model, Vec<String>)> {
+    // The files are already here, so there is nothing to download.
+    progress_changed(Some(ArchiveTaskStage::Extracting), 0.1);
+    let text_entries = parse_language_files(&downloads, |progress| {
+        progress_changed(None, 0.1 + progress * 0.8)
+    })
+    .await?;
+
+    // Saving to database.
+    progress_changed(Some(ArchiveTaskStage::Saving), 0.9);
+    let mod_id =
+        mod_provider::Entity::find_by_id((ModProviderType::Custom, info.identifier.clone()))
+            .one(db)
+            .await?
+            .map(|provider| provider.mod_id);
+    let keys: Vec<String> = text_entries.iter().map(|entry| entry.key.clone()).collect();
+    let skipped_keys = find_keys_of_other_mods(db, mod_id, &keys).await?;
+    let text_entries: Vec<_> = text_entries
+        .into_iter()
+        .filter(|entry| skipped_keys.binary_search(&entry.key).is_err())
+        .collect();
+
+    let mc_mod = create_mod_model(
+        db,
+        &ArchiveProvider::Custom,
+        info.identifier.clone(),
+        text_entries.is_empty(),
+    )
+    .await?;
+    create_custom_provider_model(db, info, mc_mod.id).await?;
+    save_text_entries(db, text_entries, mc_mod.id).await?;
+
+    Ok((mc_mod, skipped_keys))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::Future,
+        io::{Cursor, Write},
+        sync::Mutex,
+    };
+
+    use entity::{
+        database_initializer::initialize_database,
+        entry::{text_entry, text_entry_variant},
+        minecraft::{minecraft_mod::ModStatus, mod_loader::ModLoader},
+    };
+    use sea_orm::{ColumnTrait, Database, QueryFilter, QueryOrder};
+    use serde_json::json;
+    use zip::{write::FileOptions, ZipWriter};
+
+    use super::*;
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    /// Builds a Fabric mod jar with an English language file and stores it as an upload.
+    fn write_jar(namespace: &str, language_file: serde_json::Value) -> PathBuf {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = FileOptions::default();
+        let metadata = json!({ "schemaVersion": 1, "id": namespace, "version": "1.0.0" });
+        zip.start_file("fabric.mod.json", options).unwrap();
+        zip.write_all(metadata.to_string().as_bytes()).unwrap();
+        zip.start_file(format!("assets/{}/lang/en_us.json", namespace), options)
+            .unwrap();
+        zip.write_all(language_file.to_string().as_bytes()).unwrap();
+        let data = zip.finish().unwrap().into_inner();
+
+        let path = create_upload_path().unwrap();
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    fn download(path: PathBuf, game_version: &str) -> ModDownloadInfo {
+        ModDownloadInfo {
+            url: String::new(),
+            size: 0,
+            loader: ModLoader::Fabric,
+            game_version: semver::Version::parse(game_version).unwrap(),
+            path,
+        }
+    }
+
+    #[test]
+    fn inspect_returns_the_namespace() {
+        let path = write_jar("examplemod", json!({ "item.examplemod.gem": "Gem" }));
+
+        assert_eq!(inspect_mod_archive(&path).unwrap(), "examplemod");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn inspect_rejects_other_files() {
+        let not_zip = create_upload_path().unwrap();
+        std::fs::write(&not_zip, b"not a jar").unwrap();
+        assert!(inspect_mod_archive(&not_zip).is_err());
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("pack.mcmeta", FileOptions::default())
+            .unwrap();
+        zip.write_all(b"{}").unwrap();
+        let resource_pack = create_upload_path().unwrap();
+        std::fs::write(&resource_pack, zip.finish().unwrap().into_inner()).unwrap();
+        assert!(inspect_mod_archive(&resource_pack).is_err());
+
+        std::fs::remove_file(not_zip).unwrap();
+        std::fs::remove_file(resource_pack).unwrap();
+    }
+
+    #[test]
+    fn archive_uploaded_jars() {
+        block_on(async {
+            let db = Database::connect("sqlite::memory:").await.unwrap();
+            initialize_database(&db).await.unwrap();
+
+            let old = write_jar(
+                "examplemod",
+                json!({ "item.examplemod.gem": "Gem", "item.examplemod.ore": "Ore" }),
+            );
+            let new = write_jar(
+                "examplemod",
+                json!({ "item.examplemod.gem": "Shiny Gem", "item.examplemod.dust": "Dust" }),
+            );
+            let downloads = vec![
+                download(old.clone(), "1.19.2"),
+                download(new.clone(), "1.20.1"),
+            ];
+            let info = CustomModInfo {
+                identifier: "examplemod".to_string(),
+                name: "Example Mod".to_string(),
+                description: None,
+                page_url: None,
+            };
+            let stages = Mutex::new(Vec::new());
+
+            let (mc_mod, skipped_keys) = archive_uploaded_mod(&db, info, downloads, |stage, _| {
+                stages.lock().unwrap().extend(stage);
+            })
+            .await
+            .unwrap();
+
+            assert_eq!(mc_mod.status, ModStatus::Normal);
+            assert!(skipped_keys.is_empty());
+            assert_eq!(
+                stages.into_inner().unwrap(),
+                [ArchiveTaskStage::Extracting, ArchiveTaskStage::Saving]
+            );
+            // The uploaded files are removed once parsed.
+            assert!(!old.exists() && !new.exists());
+
+            let provider = mod_provider::Entity::find_by_id((
+                ModProviderType::Custom,
+                "examplemod".to_string(),
+            ))
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+            assert_eq!(provider.mod_id, mc_mod.id);
+            assert_eq!(provider.display_name, "Example Mod");
+
+            let entries: Vec<_> = text_entry::Entity::find()
+                .filter(text_entry::Column::ModId.eq(mc_mod.id))
+                .order_by_asc(text_entry::Column::Key)
+                .all(&db)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|entry| (entry.key, entry.value, entry.game_versions.0))
+                .collect();
+            let versions = |versions: &[&str]| versions.iter().map(|x| x.to_string()).collect();
+            assert_eq!(
+                entries,
+                [
+                    (
+                        "item.examplemod.dust".to_string(),
+                        "Dust".to_string(),
+                        versions(&["1.20.1"])
+                    ),
+                    (
+                        "item.examplemod.gem".to_string(),
+                        "Shiny Gem".to_string(),
+                        versions(&["1.19.2", "1.20.1"])
+                    ),
+                    (
+                        "item.examplemod.ore".to_string(),
+                        "Ore".to_string(),
+                        versions(&["1.19.2"])
+                    ),
+                ]
+            );
+
+            let variants: Vec<_> = text_entry_variant::Entity::find()
+                .filter(text_entry_variant::Column::Key.eq("item.examplemod.gem"))
+                .order_by_asc(text_entry_variant::Column::Value)
+                .all(&db)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|variant| (variant.value, variant.game_versions.0, variant.loader))
+                .collect();
+            assert_eq!(
+                variants,
+                [
+                    (
+                        "Gem".to_string(),
+                        versions(&["1.19.2"]),
+                        Some(ModLoader::Fabric)
+                    ),
+                    (
+                        "Shiny Gem".to_string(),
+                        versions(&["1.20.1"]),
+                        Some(ModLoader::Fabric)
+                    ),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn archive_without_entries_is_flagged() {
+        block_on(async {
+            let db = Database::connect("sqlite::memory:").await.unwrap();
+            initialize_database(&db).await.unwrap();
+
+            let empty = write_jar("emptymod", json!({}));
+            let info = CustomModInfo {
+                identifier: "emptymod".to_string(),
+                name: "Empty Mod".to_string(),
+                description: None,
+                page_url: None,
+            };
+
+            let (mc_mod, _) =
+                archive_uploaded_mod(&db, info, vec![download(empty, "1.20.1")], |_, _| {})
+                    .await
+                    .unwrap();
+            assert_eq!(mc_mod.status, ModStatus::MissingEntries);
+        });
+    }
+
+    #[test]
+    fn keys_of_other_mods_are_skipped() {
+        block_on(async {
+            let db = Database::connect("sqlite::memory:").await.unwrap();
+            initialize_database(&db).await.unwrap();
+            let info = |identifier: &str| CustomModInfo {
+                identifier: identifier.to_string(),
+                name: identifier.to_string(),
+                description: None,
+                page_url: None,
+            };
+
+            let original = write_jar("examplemod", json!({ "item.examplemod.gem": "Gem" }));
+            let (original_mod, _) = archive_uploaded_mod(
+                &db,
+                info("examplemod"),
+                vec![download(original, "1.20.1")],
+                |_, _| {},
+            )
+            .await
+            .unwrap();
+
+            let copy = write_jar(
+                "copymod",
+                json!({ "item.examplemod.gem": "Stolen Gem", "item.copymod.dust": "Dust" }),
+            );
+            let (copy_mod, skipped_keys) = archive_uploaded_mod(
+                &db,
+                info("copymod"),
+                vec![download(copy, "1.20.1")],
+                |_, _| {},
+            )
+            .await
+            .unwrap();
+            assert_eq!(skipped_keys, ["item.examplemod.gem"]);
+
+            let gem = text_entry::Entity::find_by_id("item.examplemod.gem")
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(gem.mod_id, original_mod.id);
+            assert_eq!(gem.value, "Gem");
+            let gem_variants: Vec<_> = text_entry_variant::Entity::find()
+                .filter(text_entry_variant::Column::Key.eq("item.examplemod.gem"))
+                .all(&db)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|variant| variant.value)
+                .collect();
+            assert_eq!(gem_variants, ["Gem"]);
+
+            let copy_keys: Vec<_> = text_entry::Entity::find()
+                .filter(text_entry::Column::ModId.eq(copy_mod.id))
+                .all(&db)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.key)
+                .collect();
+            assert_eq!(copy_keys, ["item.copymod.dust"]);
+
+            // Uploading the mod again keeps its own keys.
+            let update = write_jar("examplemod", json!({ "item.examplemod.gem": "Shiny Gem" }));
+            let (_, skipped_keys) = archive_uploaded_mod(
+                &db,
+                info("examplemod"),
+                vec![download(update, "1.20.1")],
+                |_, _| {},
+            )
+            .await
+            .unwrap();
+            assert!(skipped_keys.is_empty());
+        });
+    }
+}
@@ -0,0 +1,524 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use entity::{
+    entry::{
+        glossary_term::{self, PartOfSpeech},
+        text_entry_variant,
+        translation::{self, TranslationStatus},
+        translation_revision::{self, RevisionAction},
+    },
+    minecraft::{
+        minecraft_mod,
+        mod_provider::{self, ModProviderType},
+    },
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, Set, TransactionTrait,
+};
+use serde::Deserialize;
+use zip::ZipArchive;
+
+use super::{
+    resource::{create_mod_model, ArchiveProvider},
+    task::{find_keys_of_other_mods, save_text_entries, TextEntryData, TextEntryVariantData},
+};
+use crate::{
+    minecraft::file_metadata::parse_language_file,
+    translation::{
+        memory::invalidate_memory, review::find_approved_translations, stats::invalidate_mod_stats,
+    },
+};
+
+/// Identifier of the `Vanilla` provider of the mod holding the game's entries.
+pub const VANILLA_IDENTIFIER: &str = "minecraft";
+const SOURCE_LANGUAGE: &str = "en_us";
+const SOURCE_FILE: &str = "assets/minecraft/lang/en_us.json";
+const LANGUAGE_OBJECT_PREFIX: &str = "minecraft/lang/";
+
+/// Key prefixes of the names added to the glossaries, e.g. `block.minecraft.stone`.
+const GLOSSARY_CATEGORIES: [&str; 6] =
+    ["block", "item", "entity", "biome", "effect", "enchantment"];
+const MAX_GLOSSARY_TERM_WORDS: usize = 3;
+
+#[derive(Debug, Clone)]
+pub struct VanillaImportOptions {
+    /// The client jar of the game version, it contains the `en_us` source values.
+    pub client_jar: PathBuf,
+    /// The asset index of the game version (`assets/indexes/<id>.json`), the other
+    /// languages are read from the `assets/objects` directory next to it.
+    /// Only the source values are imported without it.
+    pub asset_index: Option<PathBuf>,
+    pub game_version: semver::Version,
+    /// Languages to import, all languages of the asset index if not set.
+    pub languages: Option<HashSet<String>>,
+    /// Adds the names of blocks, items, entities, biomes, effects and enchantments
+    /// to the global glossary of each language.
+    pub glossary: bool,
+}
+
+/// The source values of a game version and where its translations are stored.
+#[derive(Debug)]
+pub struct VanillaAssets {
+    pub source: HashMap<String, String>,
+    /// Paths of the language files by language code.
+    pub languages: BTreeMap<String, PathBuf>,
+}
+
+#[derive(Debug, Default)]
+pub struct VanillaImportReport {
+    pub game_version: String,
+    pub entries: usize,
+    /// Saved translations by language, translations equal to the live one are skipped.
+    pub translations: BTreeMap<String, usize>,
+    pub glossary_terms: usize,
+    /// Translations are only imported for the latest imported game version,
+    /// as entries always hold the latest source values.
+    pub latest: bool,
+    /// Keys of entries that belong to other mods, they are not imported.
+    pub skipped_keys: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetIndex {
+    objects: HashMap<String, AssetObject>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetObject {
+    hash: String,
+}
+
+/// Reads the source values from the client jar and locates the language files
+/// of the asset index, so that invalid paths are reported before importing.
+pub fn read_vanilla_assets(options: &VanillaImportOptions) -> anyhow::Result<VanillaAssets> {
+    let file = File::open(&options.client_jar).context("Failed to open the client jar")?;
+    let reader = BufReader::new(&file);
+    let mut archive = ZipArchive::new(reader).context("The client jar is not a zip archive")?;
+    let mut source_file = archive
+        .by_name(SOURCE_FILE)
+        .context("The client jar has no JSON language file")?;
+    let source = parse_language_file(&mut source_file)?;
+    if source.is_empty() {
+        anyhow::bail!("The client jar has no entries");
+    }
+
+    let languages = match &options.asset_index {
+        Some(path) => find_language_files(path, options.languages.as_ref())?,
+        None => BTreeMap::new(),
+    };
+
+    Ok(VanillaAssets { source, languages })
+}
+
+fn find_language_files(
+    asset_index: &Path,
+    filter: Option<&HashSet<String>>,
+) -> anyhow::Result<BTreeMap<String, PathBuf>> {
+    let file = File::open(asset_index).context("Failed to open the asset index")?;
+    let index: AssetIndex =
+        serde_json::from_reader(BufReader::new(file)).context("Invalid asset index")?;
+    // Indexes are stored in `assets/indexes`, next to `assets/objects`.
+    let objects = asset_index
+        .parent()
+        .and_then(Path::parent)
+        .context("The asset index is not in an assets directory")?
+        .join("objects");
+
+    let mut languages = BTreeMap::new();
+    for (name, object) in index.objects {
+        let Some(language) = name
+            .strip_prefix(LANGUAGE_OBJECT_PREFIX)
+            .and_then(|file| file.strip_suffix(".json"))
+        else {
+            continue;
+        };
+        if language == SOURCE_LANGUAGE || filter.is_some_and(|filter| !filter.contains(language)) {
+            continue;
+        }
+        if object.hash.len() < 2 {
+            anyhow::bail!("Invalid object hash of {}", name);
+        }
+
+        let path = objects.join(&object.hash[..2]).join(&object.hash);
+        if !path.is_file() {
+            anyhow::bail!("Missing object of {} at {}", name, path.display());
+        }
+        languages.insert(language.to_string(), path);
+    }
+
+    if let Some(filter) = filter {
+        if let Some(missing) = filter
+            .iter()
+            .find(|language| !languages.contains_key(*language))
+        {
+            anyhow::bail!("The asset index has no {} language file", missing);
+        }
+    }
+
+    Ok(languages)
+}
+
+/// Imports the game version into the vanilla mod: its source values are merged with the
+/// game versions imported before, then the approved translations and glossary terms of
+/// each language are saved on behalf of the user. Keys already used by other mods are
+/// skipped, as keys are unique across mods.
+pub async fn import_vanilla_assets(
+    db: &DatabaseConnection,
+    assets: VanillaAssets,
+    options: &VanillaImportOptions,
+    user_id: i32,
+    progress_changed: impl Fn(f32),
+) -> anyhow::Result<(minecraft_mod::Model, VanillaImportReport)> {
+    let mc_mod = create_mod_model(
+        db,
+        &ArchiveProvider::Vanilla,
+        VANILLA_IDENTIFIER.to_string(),
+        false,
+    )
+    .await?;
+    create_vanilla_provider_model(db, mc_mod.id).await?;
+
+    let keys: Vec<String> = assets.source.keys().cloned().collect();
+    let skipped_keys = find_keys_of_other_mods(db, Some(mc_mod.id), &keys).await?;
+    let mut source = assets.source;
+    for key in &skipped_keys {
+        source.remove(key);
+    }
+
+    let (entries, latest) =
+        merge_game_version(db, mc_mod.id, &options.game_version, &source).await?;
+    let mut report = VanillaImportReport {
+        game_version: options.game_version.to_string(),
+        entries: source.len(),
+        latest,
+        skipped_keys,
+        ..Default::default()
+    };
+    save_text_entries(db, entries, mc_mod.id).await?;
+    progress_changed(0.1);
+
+    if !latest {
+        return Ok((mc_mod, report));
+    }
+
+    let comment = format!("Imported from Minecraft {}", options.game_version);
+    let languages_len = assets.languages.len();
+    for (index, (language, path)) in assets.languages.iter().enumerate() {
+        let file = File::open(path)?;
+        let translations = parse_language_file(&mut BufReader::new(file))
+            .with_context(|| format!("Failed to parse the {} language file", language))?;
+
+        let saved =
+            save_vanilla_translations(db, &source, language, &translations, user_id, &comment)
+                .await?;
+        report.translations.insert(language.clone(), saved);
+
+        if options.glossary {
+            report.glossary_terms +=
+                save_vanilla_glossary(db, &source, language, &translations, user_id).await?;
+        }
+
+        progress_changed(0.1 + 0.9 * (index + 1) as f32 / languages_len as f32);
+    }
+    invalidate_mod_stats(mc_mod.id);
+
+    Ok((mc_mod, report))
+}
+
+async fn create_vanilla_provider_model(
+    db: &DatabaseConnection,
+    mod_id: i32,
+) -> anyhow::Result<mod_provider::Model> {
+    let is_existing = mod_provider::Entity::find_by_id((
+        ModProviderType::Vanilla,
+        VANILLA_IDENTIFIER.to_string(),
+    ))
+    .one(db)
+    .await?
+    .is_some();
+
+    let model = mod_provider::ActiveModel {
+        identifier: Set(VANILLA_IDENTIFIER.to_string()),
+        provider_type: Set(ModProviderType::Vanilla),
+        display_name: Set("Minecraft".to_string()),
+        description: Set("Translations of the vanilla game".to_string()),
+        image_url: Set(None),
+        page_url: Set("https://www.minecraft.net".to_string()),
+        mod_id: Set(mod_id),
+        ..Default::default()
+    };
+
+    let model = if is_existing {
+        model.update(db).await?
+    } else {
+        model.insert(db).await?
+    };
+    Ok(model)
+}
+
+/// Builds the entries of the vanilla mod with the game version replaced by the given
+/// source values, and whether the game version is the latest imported one.
+async fn merge_game_version(
+    db: &DatabaseConnection,
+    mod_id: i32,
+    game_version: &semver::Version,
+    source: &HashMap<String, String>,
+) -> Result<(Vec<TextEntryData>, bool), DbErr> {
+    let existing = text_entry_variant::Entity::find()
+        .filter(text_entry_variant::Column::ModId.eq(mod_id))
+        .all(db)
+        .await?;
+
+    let mut latest = true;
+    let mut variants: HashMap<String, Vec<TextEntryVariantData>> = HashMap::new();
+    for variant in existing {
+        let mut game_versions: HashSet<semver::Version> = variant
+            .game_versions
+            .0
+            .iter()
+            .filter_map(|version| semver::Version::parse(version).ok())
+            .collect();
+        game_versions.remove(game_version);
+        if game_versions.iter().any(|version| version > game_version) {
+            latest = false;
+        }
+
+        if !game_versions.is_empty() {
+            variants
+                .entry(variant.key)
+                .or_default()
+                .push(TextEntryVariantData {
+                    value: variant.value,
+                    game_versions,
+                    loader: None,
+                });
+        }
+    }
+
+    for (key, value) in source {
+        let key_variants = variants.entry(key.clone()).or_default();
+        let variant = match key_variants.iter_mut().find(|x| &x.value == value) {
+            Some(variant) => variant,
+            None => {
+                key_variants.push(TextEntryVariantData {
+                    value: value.clone(),
+                    game_versions: HashSet::new(),
+                    loader: None,
+                });
+                key_variants.last_mut().unwrap()
+            }
+        };
+        variant.game_versions.insert(game_version.clone());
+    }
+
+    let entries = variants
+        .into_iter()
+        .map(|(key, variants)| {
+            let value = variants
+                .iter()
+                .max_by_key(|variant| variant.game_versions.iter().max())
+                .map(|variant| variant.value.clone())
+                .unwrap_or_default();
+
+            TextEntryData {
+                key,
+                value,
+                namespaces: HashSet::from([VANILLA_IDENTIFIER.to_string()]),
+                game_versions: variants
+                    .iter()
+                    .flat_map(|variant| variant.game_versions.iter().cloned())
+                    .collect(),
+                loaders: HashSet::new(),
+                variants,
+            }
+        })
+        .collect();
+
+    Ok((entries, latest))
+}
+
+/// Saves the translations as approved, with their revisions, unless they are live already.
+/// Returns the number of saved translations.
+async fn save_vanilla_translations(
+    db: &DatabaseConnection,
+    source: &HashMap<String, String>,
+    language: &str,
+    translations: &HashMap<String, String>,
+    user_id: i32,
+    comment: &str,
+) -> Result<usize, DbErr> {
+    let keys: Vec<String> = translations
+        .iter()
+        .filter(|(key, value)| source.contains_key(*key) && !value.is_empty())
+        .map(|(key, _)| key.clone())
+        .collect();
+    // `insert_many` skips `before_save`.
+    let now = Utc::now();
+    let mut saved = 0;
+
+    let txn = db.begin().await?;
+    for chunk in keys.chunks(1000) {
+        let live = find_approved_translations(&txn, chunk.iter().cloned(), language).await?;
+        let models: Vec<translation::ActiveModel> = chunk
+            .iter()
+            .filter(|key| live.get(*key) != translations.get(*key))
+            .map(|key| translation::ActiveModel {
+                id: NotSet,
+                key: Set(key.clone()),
+                language: Set(language.to_string()),
+                value: Set(translations[key].clone()),
+                status: Set(TranslationStatus::Approved),
+                score: Set(0),
+                source_changed: Set(false),
+                review_comment: Set(Some(comment.to_string())),
+                reviewed_at: Set(Some(now)),
+                created_at: Set(now),
+                updated_at: Set(now),
+                translator_id: Set(user_id),
+                reviewer_id: Set(Some(user_id)),
+            })
+            .collect();
+        if models.is_empty() {
+            continue;
+        }
+        saved += models.len();
+        let saved_keys = models.iter().map(|model| model.key.clone().unwrap());
+        translation::Entity::insert_many(models.clone())
+            .exec(&txn)
+            .await?;
+
+        let inserted = find_inserted_translations(&txn, saved_keys, language, user_id, now).await?;
+        let revisions = inserted
+            .into_iter()
+            .map(|translation| translation_revision::ActiveModel {
+                id: NotSet,
+                key: Set(translation.key.clone()),
+                language: Set(translation.language),
+                action: Set(RevisionAction::Approved),
+                value: Set(translation.value),
+                previous_value: Set(live.get(&translation.key).cloned()),
+                status: Set(TranslationStatus::Approved),
+                comment: Set(Some(comment.to_string())),
+                created_at: Set(now),
+                translation_id: Set(translation.id),
+                user_id: Set(Some(user_id)),
+            })
+            .collect::<Vec<_>>();
+        if !revisions.is_empty() {
+            translation_revision::Entity::insert_many(revisions)
+                .exec(&txn)
+                .await?;
+        }
+    }
+    txn.commit().await?;
+
+    if saved > 0 {
+        invalidate_memory(language);
+    }
+    Ok(saved)
+}
+
+async fn find_inserted_translations<C: sea_orm::ConnectionTrait>(
+    db: &C,
+    keys: impl IntoIterator<Item = String>,
+    language: &str,
+    user_id: i32,
+    reviewed_at: DateTime<Utc>,
+) -> Result<Vec<translation::Model>, DbErr> {
+    translation::Entity::find()
+        .filter(translation::Column::Key.is_in(keys))
+        .filter(translation::Column::Language.eq(language))
+        .filter(translation::Column::TranslatorId.eq(user_id))
+        .filter(translation::Column::ReviewedAt.eq(reviewed_at))
+        .all(db)
+        .await
+}
+
+/// Adds the names of game objects to the global glossary of the language. Names already
+/// in the glossary, and names translated differently depending on the object, are skipped.
+/// Returns the number of added terms.
+async fn save_vanilla_glossary(
+    db: &DatabaseConnection,
+    source: &HashMap<String, String>,
+    language: &str,
+    translations: &HashMap<String, String>,
+    user_id: i32,
+) -> Result<usize, DbErr> {
+    let existing: HashSet<String> = glossary_term::Entity::find()
+        .filter(glossary_term::Column::Language.eq(language))
+        .filter(glossary_term::Column::ModId.is_null())
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|term| term.term.to_lowercase())
+        .collect();
+
+    // Lowercase term to the key, term and translation, or `None` if ambiguous.
+    let mut candidates: HashMap<String, Option<(&String, &String, &String)>> = HashMap::new();
+    for (key, term) in source {
+        let Some(translation) = translations.get(key) else {
+            continue;
+        };
+        if !is_glossary_name(key)
+            || term.split_whitespace().count() > MAX_GLOSSARY_TERM_WORDS
+            || term.contains('%')
+            || translation.contains('%')
+            || term.trim().is_empty()
+            || translation.trim().is_empty()
+        {
+            continue;
+        }
+
+        let text = term.to_lowercase();
+        if existing.contains(&text) {
+            continue;
+        }
+        candidates
+            .entry(text)
+            .and_modify(|candidate| {
+                if candidate.is_some_and(|(_, _, other)| other != translation) {
+                    *candidate = None;
+                }
+            })
+            .or_insert(Some((key, term, translation)));
+    }
+
+    let now = Utc::now();
+    let models: Vec<glossary_term::ActiveModel> = candidates
+        .into_values()
+        .flatten()
+        .map(|(key, term, translation)| glossary_term::ActiveModel {
+            id: NotSet,
+            term: Set(term.trim().to_string()),
+            language: Set(language.to_string()),
+            translation: Set(translation.trim().to_string()),
+            part_of_speech: Set(Some(PartOfSpeech::Noun)),
+            note: Set(Some(format!("Vanilla name ({})", key))),
+            created_at: Set(now),
+            updated_at: Set(now),
+            mod_id: Set(None),
+            creator_id: Set(user_id),
+        })
+        .collect();
+
+    let added = models.len();
+    for chunk in models.chunks(1000).map(|chunk| chunk.to_vec()) {
+        glossary_term::Entity::insert_many(chunk).exec(db).await?;
+    }
+    Ok(added)
+}
+
+/// Whether the key is the name of a game object, e.g. `item.minecraft.diamond_sword`
+/// but not `item.minecraft.potion.effect.water`.
+fn is_glossary_name(key: &str) -> bool {
+    let parts: Vec<&str> = key.split('.').collect();
+    parts.len() == 3 && GLOSSARY_CATEGORIES.contains(&parts[0]) && parts[1] == VANILLA_IDENTIFIER
+}
@@ -0,0 +1,61 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use super::ExchangeEntry;
+
+/// Multiple namespaces or game versions are separated by this in a cell.
+const LIST_SEPARATOR: &str = " ";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Record {
+    key: String,
+    source: String,
+    #[serde(default)]
+    target: String,
+    #[serde(default)]
+    namespaces: String,
+    #[serde(default)]
+    game_versions: String,
+}
+
+/// Writes the entries with a header row of `key,source,target,namespaces,game_versions`.
+pub fn write(entries: &[ExchangeEntry]) -> anyhow::Result<Vec<u8>> {
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+
+    for entry in entries {
+        writer.serialize(Record {
+            key: entry.key.clone(),
+            source: entry.source.clone(),
+            target: entry.target.clone().unwrap_or_default(),
+            namespaces: entry.namespaces.join(LIST_SEPARATOR),
+            game_versions: entry.game_versions.join(LIST_SEPARATOR),
+        })?;
+    }
+
+    Ok(writer.into_inner()?)
+}
+
+/// Reads the entries by the header row, only `key` and `source` are required.
+pub fn read(data: &[u8]) -> anyhow::Result<Vec<ExchangeEntry>> {
+    let mut reader = ::csv::Reader::from_reader(data);
+    let mut entries = Vec::new();
+
+    for (index, record) in reader.deserialize::<Record>().enumerate() {
+        let record = record.with_context(|| format!("Invalid CSV record {}", index + 1))?;
+        let split = |list: String| {
+            list.split_whitespace()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        entries.push(ExchangeEntry {
+            key: record.key,
+            source: record.source,
+            target: Some(record.target).filter(|target| !target.is_empty()),
+            namespaces: split(record.namespaces),
+            game_versions: split(record.game_versions),
+        });
+    }
+
+    Ok(entries)
+}
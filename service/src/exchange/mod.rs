@@ -0,0 +1,229 @@
+//! Import and export of entries and translations in the formats of CAT tools.
+
+pub mod csv;
+pub mod po;
+pub mod xliff;
+
+use std::collections::HashMap;
+
+use entity::{entry::text_entry, minecraft::minecraft_mod};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    qa::{check_translation, has_errors, QaIssue},
+    translation::{edit::save_translation, review::find_approved_translations},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExchangeFormat {
+    /// XLIFF 2.0
+    Xliff,
+    /// gettext PO
+    Po,
+    Csv,
+}
+
+impl ExchangeFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExchangeFormat::Xliff => "xlf",
+            ExchangeFormat::Po => "po",
+            ExchangeFormat::Csv => "csv",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExchangeFormat::Xliff => "application/xliff+xml",
+            ExchangeFormat::Po => "text/x-gettext-translation",
+            ExchangeFormat::Csv => "text/csv",
+        }
+    }
+}
+
+/// An entry with its translation as exchanged with CAT tools, the namespaces and
+/// game versions are only context for translators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExchangeEntry {
+    pub key: String,
+    pub source: String,
+    pub target: Option<String>,
+    pub namespaces: Vec<String>,
+    pub game_versions: Vec<String>,
+}
+
+/// Exports the entries of the mod with their approved translations in the language,
+/// untranslated entries are included without target. Returns `None` if the mod does
+/// not exist.
+pub async fn export_entries(
+    db: &DatabaseConnection,
+    mod_id: i32,
+    language: &str,
+    format: ExchangeFormat,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    if minecraft_mod::Entity::find_by_id(mod_id)
+        .one(db)
+        .await?
+        .is_none()
+    {
+        return Ok(None);
+    }
+
+    let entries = text_entry::Entity::find()
+        .filter(text_entry::Column::ModId.eq(mod_id))
+        .order_by_asc(text_entry::Column::Key)
+        .all(db)
+        .await?;
+    let mut translations =
+        find_approved_translations(db, entries.iter().map(|entry| entry.key.clone()), language)
+            .await?;
+
+    let entries: Vec<ExchangeEntry> = entries
+        .into_iter()
+        .map(|entry| ExchangeEntry {
+            target: translations.remove(&entry.key),
+            key: entry.key,
+            source: entry.value,
+            namespaces: entry.namespaces.0,
+            game_versions: entry.game_versions.0,
+        })
+        .collect();
+
+    let file_id = format!("mod-{}", mod_id);
+    let bytes = match format {
+        ExchangeFormat::Xliff => xliff::write(&file_id, language, &entries)?,
+        ExchangeFormat::Po => po::write(language, &entries),
+        ExchangeFormat::Csv => csv::write(&entries)?,
+    };
+    Ok(Some(bytes))
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ImportReport {
+    /// Keys whose translation was saved.
+    pub imported: Vec<String>,
+    /// Keys without target or with the same target as the live translation.
+    pub skipped: Vec<String>,
+    /// Keys that are not entries of the mod.
+    pub unknown_keys: Vec<String>,
+    /// Entries whose source value changed since the file was exported,
+    /// their translations are not saved as they may be outdated.
+    pub changed_sources: Vec<ChangedSource>,
+    /// Translations that failed the quality checks, only checked when submitting.
+    pub failed_checks: Vec<FailedCheck>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChangedSource {
+    pub key: String,
+    /// Source value in the imported file.
+    pub imported: String,
+    /// Current source value of the entry.
+    pub current: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FailedCheck {
+    pub key: String,
+    pub issues: Vec<QaIssue>,
+}
+
+/// Parses an imported file, the error describes what is invalid.
+pub fn parse_entries(format: ExchangeFormat, data: &[u8]) -> anyhow::Result<Vec<ExchangeEntry>> {
+    match format {
+        ExchangeFormat::Xliff => xliff::read(data),
+        ExchangeFormat::Po => po::read(data),
+        ExchangeFormat::Csv => csv::read(data),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ImportOptions {
+    /// Submit the imported translations for review instead of saving drafts.
+    pub submit: bool,
+    /// Only validate the file, nothing is saved.
+    pub dry_run: bool,
+}
+
+/// Imports translations of the mod's entries in the language, saved as translations of
+/// the translator. Unknown keys and entries whose source changed are only reported.
+///
+/// Returns `None` if the mod does not exist.
+pub async fn import_entries(
+    db: &DatabaseConnection,
+    mod_id: i32,
+    language: &str,
+    translator_id: i32,
+    imported: Vec<ExchangeEntry>,
+    options: ImportOptions,
+) -> Result<Option<ImportReport>, DbErr> {
+    if minecraft_mod::Entity::find_by_id(mod_id)
+        .one(db)
+        .await?
+        .is_none()
+    {
+        return Ok(None);
+    }
+
+    let current: HashMap<String, String> = text_entry::Entity::find()
+        .filter(text_entry::Column::ModId.eq(mod_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|entry| (entry.key, entry.value))
+        .collect();
+    let live =
+        find_approved_translations(db, imported.iter().map(|x| x.key.clone()), language).await?;
+
+    let mut report = ImportReport::default();
+    for entry in imported {
+        let Some(current_source) = current.get(&entry.key) else {
+            report.unknown_keys.push(entry.key);
+            continue;
+        };
+        if current_source != &entry.source {
+            report.changed_sources.push(ChangedSource {
+                key: entry.key,
+                imported: entry.source,
+                current: current_source.clone(),
+            });
+            continue;
+        }
+
+        let target = match entry.target {
+            Some(target) if !target.is_empty() && live.get(&entry.key) != Some(&target) => target,
+            _ => {
+                report.skipped.push(entry.key);
+                continue;
+            }
+        };
+        if options.submit {
+            let issues = check_translation(current_source, &target);
+            if has_errors(&issues) {
+                report.failed_checks.push(FailedCheck {
+                    key: entry.key,
+                    issues,
+                });
+                continue;
+            }
+        }
+
+        if !options.dry_run {
+            save_translation(
+                db,
+                translator_id,
+                entry.key.clone(),
+                language.to_string(),
+                target,
+                options.submit,
+            )
+            .await?;
+        }
+        report.imported.push(entry.key);
+    }
+
+    Ok(Some(report))
+}
@@ -0,0 +1,307 @@
+use anyhow::{anyhow, bail};
+
+use super::ExchangeEntry;
+
+/// Writes a gettext PO file, the key of each entry is its `msgctxt` and the
+/// namespaces and game versions are extracted comments.
+pub fn write(language: &str, entries: &[ExchangeEntry]) -> Vec<u8> {
+    let mut output = String::new();
+
+    output.push_str("msgid \"\"\nmsgstr \"\"\n");
+    output.push_str(&format!(
+        "\"Language: {}\\n\"\n",
+        gettext_language(language)
+    ));
+    output.push_str("\"MIME-Version: 1.0\\n\"\n");
+    output.push_str("\"Content-Type: text/plain; charset=UTF-8\\n\"\n");
+    output.push_str("\"Content-Transfer-Encoding: 8bit\\n\"\n");
+
+    for entry in entries {
+        output.push('\n');
+        output.push_str(&format!("#. namespaces: {}\n", entry.namespaces.join(" ")));
+        output.push_str(&format!(
+            "#. game_versions: {}\n",
+            entry.game_versions.join(" ")
+        ));
+        output.push_str(&format!("msgctxt \"{}\"\n", escape(&entry.key)));
+        output.push_str(&format!("msgid \"{}\"\n", escape(&entry.source)));
+        output.push_str(&format!(
+            "msgstr \"{}\"\n",
+            escape(entry.target.as_deref().unwrap_or_default())
+        ));
+    }
+
+    output.into_bytes()
+}
+
+#[derive(Default)]
+struct PendingEntry {
+    fuzzy: bool,
+    namespaces: Vec<String>,
+    game_versions: Vec<String>,
+    context: Option<String>,
+    id: Option<String>,
+    translation: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+    Context,
+    Id,
+    Translation,
+}
+
+/// Reads a gettext PO file, entries without `msgctxt` (such as the header) are ignored
+/// and the translations of fuzzy entries are not used.
+pub fn read(data: &[u8]) -> anyhow::Result<Vec<ExchangeEntry>> {
+    let text = std::str::from_utf8(data).map_err(|_| anyhow!("PO file must be UTF-8"))?;
+    let mut entries = Vec::new();
+    let mut pending = PendingEntry::default();
+    let mut field = None;
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        let line_number = index + 1;
+
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            // A comment starts the next entry.
+            if pending.translation.is_some() {
+                finish_entry(&mut entries, std::mem::take(&mut pending));
+            }
+            if let Some(flags) = comment.strip_prefix(',') {
+                pending.fuzzy |= flags.split(',').any(|flag| flag.trim() == "fuzzy");
+            } else if let Some(extracted) = comment.strip_prefix('.') {
+                let extracted = extracted.trim();
+                let split = |list: &str| list.split_whitespace().map(|x| x.to_string()).collect();
+                if let Some(list) = extracted.strip_prefix("namespaces:") {
+                    pending.namespaces = split(list);
+                } else if let Some(list) = extracted.strip_prefix("game_versions:") {
+                    pending.game_versions = split(list);
+                }
+            }
+            continue;
+        }
+
+        let (keyword, rest) = match line.split_once(char::is_whitespace) {
+            Some((keyword, rest)) if !line.starts_with('"') => (Some(keyword), rest.trim()),
+            _ => (None, line),
+        };
+        let value =
+            unquote(rest).ok_or_else(|| anyhow!("Invalid string on line {}", line_number))?;
+
+        match keyword {
+            None => {
+                let target = match field {
+                    Some(Field::Context) => &mut pending.context,
+                    Some(Field::Id) => &mut pending.id,
+                    Some(Field::Translation) => &mut pending.translation,
+                    None => bail!("Unexpected string on line {}", line_number),
+                };
+                target.get_or_insert_with(String::new).push_str(&value);
+            }
+            Some("msgctxt") | Some("msgid") if pending.translation.is_some() => {
+                finish_entry(&mut entries, std::mem::take(&mut pending));
+                field = Some(start_field(&mut pending, keyword.unwrap(), value));
+            }
+            Some(keyword @ ("msgctxt" | "msgid" | "msgstr")) => {
+                field = Some(start_field(&mut pending, keyword, value));
+            }
+            Some("msgid_plural") | Some("msgstr[0]") => {
+                bail!("Plural forms are not supported (line {})", line_number)
+            }
+            Some(keyword) => bail!("Unknown keyword {} on line {}", keyword, line_number),
+        }
+    }
+    if pending.translation.is_some() {
+        finish_entry(&mut entries, pending);
+    }
+
+    Ok(entries)
+}
+
+fn start_field(pending: &mut PendingEntry, keyword: &str, value: String) -> Field {
+    let field = match keyword {
+        "msgctxt" => Field::Context,
+        "msgid" => Field::Id,
+        _ => Field::Translation,
+    };
+    let target = match field {
+        Field::Context => &mut pending.context,
+        Field::Id => &mut pending.id,
+        Field::Translation => &mut pending.translation,
+    };
+    *target = Some(value);
+
+    field
+}
+
+fn finish_entry(entries: &mut Vec<ExchangeEntry>, pending: PendingEntry) {
+    let (Some(key), Some(source)) = (pending.context, pending.id) else {
+        return;
+    };
+
+    entries.push(ExchangeEntry {
+        key,
+        source,
+        target: pending
+            .translation
+            .filter(|translation| !pending.fuzzy && !translation.is_empty()),
+        namespaces: pending.namespaces,
+        game_versions: pending.game_versions,
+    });
+}
+
+/// gettext uses `zh_TW` where Minecraft uses `zh_tw`.
+fn gettext_language(language: &str) -> String {
+    match language.split_once('_') {
+        Some((language, region)) => format!("{}_{}", language, region.to_uppercase()),
+        None => language.to_string(),
+    }
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Parses a quoted PO string, returns `None` if it is not properly quoted.
+fn unquote(value: &str) -> Option<String> {
+    let inner = value.strip_prefix('"')?.strip_suffix('"')?;
+    let mut unescaped = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                'n' => unescaped.push('\n'),
+                'r' => unescaped.push('\r'),
+                't' => unescaped.push('\t'),
+                c => unescaped.push(c),
+            },
+            '"' => return None,
+            c => unescaped.push(c),
+        }
+    }
+    Some(unescaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str, source: &str, target: Option<&str>) -> ExchangeEntry {
+        ExchangeEntry {
+            key: key.to_string(),
+            source: source.to_string(),
+            target: target.map(|x| x.to_string()),
+            namespaces: vec!["examplemod".to_string()],
+            game_versions: vec!["1.19.2".to_string(), "1.20.1".to_string()],
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let entries = vec![
+            entry("item.examplemod.gem", "Gem", Some("寶石")),
+            entry("block.examplemod.ore", "Gem Ore", None),
+            entry(
+                "tooltip.examplemod.quote",
+                "Say \"hi\"\\n\tthen\nleave\r",
+                Some("說「嗨」\\n\t然後\n離開\r"),
+            ),
+        ];
+
+        let data = write("zh_tw", &entries);
+        assert_eq!(read(&data).unwrap(), entries);
+    }
+
+    #[test]
+    fn header_uses_the_gettext_language() {
+        let data = String::from_utf8(write("zh_tw", &[])).unwrap();
+        assert!(data.contains("\"Language: zh_TW\\n\"\n"));
+        assert!(read(data.as_bytes()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn multiline_strings_are_joined() {
+        let data = r#"
+msgctxt ""
+"tooltip.examplemod.long"
+msgid ""
+"First line\n"
+"Second line"
+msgstr "Erste Zeile\n"
+"Zweite Zeile"
+"#;
+
+        let entries = read(data.as_bytes()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].key, "tooltip.examplemod.long");
+        assert_eq!(entries[0].source, "First line\nSecond line");
+        assert_eq!(
+            entries[0].target.as_deref(),
+            Some("Erste Zeile\nZweite Zeile")
+        );
+    }
+
+    #[test]
+    fn entries_follow_each_other_without_blank_lines() {
+        let data = r#"msgctxt "a"
+msgid "A"
+msgstr "Ä"
+msgctxt "b"
+msgid "B"
+msgstr ""
+#, fuzzy
+msgctxt "c"
+msgid "C"
+msgstr "Ç"
+msgid "No context"
+msgstr "Kein Kontext"
+"#;
+
+        let entries = read(data.as_bytes()).unwrap();
+        let targets: Vec<_> = entries
+            .iter()
+            .map(|entry| (entry.key.as_str(), entry.target.as_deref()))
+            .collect();
+        // Empty and fuzzy translations are not used, entries without context are skipped.
+        assert_eq!(targets, [("a", Some("Ä")), ("b", None), ("c", None)]);
+    }
+
+    #[test]
+    fn malformed_input() {
+        let cases = [
+            "msgctxt \"a\"\nmsgid \"A\nmsgstr \"\"\n",
+            "msgctxt \"a\"\nmsgid \"A\"\"\nmsgstr \"\"\n",
+            "msgctxt a\nmsgid \"A\"\nmsgstr \"\"\n",
+            "\"Dangling string\"\n",
+            "msgctxt \"a\"\nmsgid \"A\"\nmsgid_plural \"As\"\nmsgstr[0] \"\"\n",
+            "msgctxt \"a\"\nmsgfoo \"A\"\n",
+        ];
+        for case in cases {
+            assert!(read(case.as_bytes()).is_err(), "accepted {:?}", case);
+        }
+
+        assert!(read(b"msgctxt \"\xff\"\n").is_err());
+    }
+
+    #[test]
+    fn malformed_input_reports_the_line() {
+        let err = read(b"msgctxt \"a\"\nmsgid \"A\nmsgstr \"\"\n").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid string on line 2");
+    }
+}
@@ -0,0 +1,291 @@
+use std::io::Cursor;
+
+use anyhow::{anyhow, bail};
+use quick_xml::{
+    events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event},
+    Reader, Writer,
+};
+
+use super::ExchangeEntry;
+
+const XLIFF_NAMESPACE: &str = "urn:oasis:names:tc:xliff:document:2.0";
+const SOURCE_LANGUAGE: &str = "en-US";
+
+/// Writes an XLIFF 2.0 document with one unit per entry, the key is the `name` of the unit
+/// (keys are not always valid IDs) and the namespaces and game versions are notes.
+pub fn write(file_id: &str, language: &str, entries: &[ExchangeEntry]) -> anyhow::Result<Vec<u8>> {
+    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+    let target_language = bcp47_language(language);
+
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+    writer.write_event(Event::Start(BytesStart::new("xliff").with_attributes([
+        ("xmlns", XLIFF_NAMESPACE),
+        ("version", "2.0"),
+        ("srcLang", SOURCE_LANGUAGE),
+        ("trgLang", target_language.as_str()),
+    ])))?;
+    writer.write_event(Event::Start(
+        BytesStart::new("file").with_attributes([("id", file_id)]),
+    ))?;
+
+    for (index, entry) in entries.iter().enumerate() {
+        let id = format!("u{}", index + 1);
+        writer.write_event(Event::Start(
+            BytesStart::new("unit").with_attributes([("id", id.as_str()), ("name", &entry.key)]),
+        ))?;
+
+        writer.write_event(Event::Start(BytesStart::new("notes")))?;
+        write_element(
+            &mut writer,
+            BytesStart::new("note").with_attributes([("category", "namespaces")]),
+            &entry.namespaces.join(" "),
+        )?;
+        write_element(
+            &mut writer,
+            BytesStart::new("note").with_attributes([("category", "game_versions")]),
+            &entry.game_versions.join(" "),
+        )?;
+        writer.write_event(Event::End(BytesEnd::new("notes")))?;
+
+        writer.write_event(Event::Start(BytesStart::new("segment")))?;
+        write_element(&mut writer, BytesStart::new("source"), &entry.source)?;
+        if let Some(target) = &entry.target {
+            write_element(&mut writer, BytesStart::new("target"), target)?;
+        }
+        writer.write_event(Event::End(BytesEnd::new("segment")))?;
+
+        writer.write_event(Event::End(BytesEnd::new("unit")))?;
+    }
+
+    writer.write_event(Event::End(BytesEnd::new("file")))?;
+    writer.write_event(Event::End(BytesEnd::new("xliff")))?;
+
+    Ok(writer.into_inner().into_inner())
+}
+
+fn write_element(
+    writer: &mut Writer<Cursor<Vec<u8>>>,
+    start: BytesStart,
+    text: &str,
+) -> anyhow::Result<()> {
+    let end = start.to_end().into_owned();
+    writer.write_event(Event::Start(start))?;
+    writer.write_event(Event::Text(BytesText::new(text)))?;
+    writer.write_event(Event::End(end))?;
+    Ok(())
+}
+
+#[derive(Default)]
+struct PendingUnit {
+    key: Option<String>,
+    source: Option<String>,
+    target: Option<String>,
+    namespaces: Vec<String>,
+    game_versions: Vec<String>,
+}
+
+enum Text {
+    Source,
+    Target,
+    Note(String),
+}
+
+/// Reads the units of an XLIFF 2.0 document, the key is the `name` of the unit or its
+/// `id` if it has no name. Inline markup in source and target is dropped.
+pub fn read(data: &[u8]) -> anyhow::Result<Vec<ExchangeEntry>> {
+    let mut reader = Reader::from_reader(data);
+    let mut buf = Vec::new();
+    let mut entries = Vec::new();
+    let mut unit: Option<PendingUnit> = None;
+    let mut text: Option<(Text, String)> = None;
+    let mut is_xliff = false;
+
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|err| anyhow!("Invalid XML at {}: {}", reader.buffer_position(), err))?;
+
+        match event {
+            Event::Start(element) => match element.local_name().as_ref() {
+                b"xliff" => {
+                    let version = attribute(&element, "version")?;
+                    if version.as_deref() != Some("2.0") {
+                        bail!("Only XLIFF 2.0 is supported");
+                    }
+                    is_xliff = true;
+                }
+                b"unit" => {
+                    let key = match attribute(&element, "name")? {
+                        Some(name) => Some(name),
+                        None => attribute(&element, "id")?,
+                    };
+                    unit = Some(PendingUnit {
+                        key,
+                        ..Default::default()
+                    });
+                }
+                b"source" => text = Some((Text::Source, String::new())),
+                b"target" => text = Some((Text::Target, String::new())),
+                b"note" => {
+                    let category = attribute(&element, "category")?.unwrap_or_default();
+                    text = Some((Text::Note(category), String::new()));
+                }
+                _ => {}
+            },
+            Event::Text(content) => {
+                if let Some((_, value)) = &mut text {
+                    value.push_str(&content.unescape()?);
+                }
+            }
+            Event::CData(content) => {
+                if let Some((_, value)) = &mut text {
+                    value.push_str(&String::from_utf8_lossy(&content));
+                }
+            }
+            Event::End(element) => match element.local_name().as_ref() {
+                b"source" | b"target" | b"note" => {
+                    if let (Some((kind, value)), Some(unit)) = (text.take(), &mut unit) {
+                        let split = || value.split_whitespace().map(|x| x.to_string()).collect();
+                        match kind {
+                            // A unit may have several segments, they are joined.
+                            Text::Source => {
+                                unit.source.get_or_insert_with(String::new).push_str(&value)
+                            }
+                            Text::Target => {
+                                unit.target.get_or_insert_with(String::new).push_str(&value)
+                            }
+                            Text::Note(category) if category == "namespaces" => {
+                                unit.namespaces = split()
+                            }
+                            Text::Note(category) if category == "game_versions" => {
+                                unit.game_versions = split()
+                            }
+                            Text::Note(_) => {}
+                        }
+                    }
+                }
+                b"unit" => {
+                    let unit = unit.take().unwrap_or_default();
+                    let key = unit.key.ok_or_else(|| anyhow!("Unit without name or id"))?;
+                    let source = unit
+                        .source
+                        .ok_or_else(|| anyhow!("Unit {} has no source", key))?;
+
+                    entries.push(ExchangeEntry {
+                        key,
+                        source,
+                        target: unit.target.filter(|target| !target.is_empty()),
+                        namespaces: unit.namespaces,
+                        game_versions: unit.game_versions,
+                    });
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if !is_xliff {
+        bail!("Not an XLIFF document");
+    }
+    Ok(entries)
+}
+
+fn attribute(element: &BytesStart, name: &str) -> anyhow::Result<Option<String>> {
+    match element.try_get_attribute(name)? {
+        Some(attribute) => Ok(Some(attribute.unescape_value()?.into_owned())),
+        None => Ok(None),
+    }
+}
+
+/// XLIFF uses BCP 47 tags like `zh-TW` where Minecraft uses `zh_tw`.
+fn bcp47_language(language: &str) -> String {
+    match language.split_once('_') {
+        Some((language, region)) => format!("{}-{}", language, region.to_uppercase()),
+        None => language.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: &str, source: &str, target: Option<&str>) -> ExchangeEntry {
+        ExchangeEntry {
+            key: key.to_string(),
+            source: source.to_string(),
+            target: target.map(|x| x.to_string()),
+            namespaces: vec!["examplemod".to_string(), "examplemod_compat".to_string()],
+            game_versions: vec!["1.20.1".to_string()],
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let entries = vec![
+            entry("item.examplemod.gem", "Gem", Some("寶石")),
+            entry("block.examplemod.ore", "Gem Ore", None),
+            entry(
+                "tooltip.examplemod.<markup> & \"quotes\"",
+                "<b>Tom & \"Jerry\"</b>\nsecond line",
+                Some("<b>湯姆 & 「傑利」</b>\n第二行"),
+            ),
+        ];
+
+        let data = write("mod-1", "zh_tw", &entries).unwrap();
+        assert_eq!(read(&data).unwrap(), entries);
+    }
+
+    #[test]
+    fn languages_are_bcp47_tags() {
+        let data = String::from_utf8(write("mod-1", "zh_tw", &[]).unwrap()).unwrap();
+        assert!(data.contains("srcLang=\"en-US\""));
+        assert!(data.contains("trgLang=\"zh-TW\""));
+    }
+
+    #[test]
+    fn units_without_name_use_their_id() {
+        let data = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xliff xmlns="urn:oasis:names:tc:xliff:document:2.0" version="2.0" srcLang="en-US" trgLang="de-DE">
+  <file id="f1">
+    <unit id="item.examplemod.gem">
+      <segment><source>Gem</source><target>Edel</target></segment>
+      <segment><source><![CDATA[ & Stone]]></source><target><![CDATA[stein]]></target></segment>
+    </unit>
+  </file>
+</xliff>"#;
+
+        let entries = read(data.as_bytes()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].key, "item.examplemod.gem");
+        // Segments are joined.
+        assert_eq!(entries[0].source, "Gem & Stone");
+        assert_eq!(entries[0].target.as_deref(), Some("Edelstein"));
+        assert!(entries[0].namespaces.is_empty());
+    }
+
+    #[test]
+    fn malformed_input() {
+        let cases = [
+            // Not XML.
+            "msgid \"Gem\"",
+            // Mismatched tags.
+            r#"<xliff version="2.0"><file id="f"><unit name="a"><segment><source>A</target></segment></unit></file></xliff>"#,
+            // Not XLIFF.
+            r#"<resources><string name="a">A</string></resources>"#,
+            // XLIFF 1.2.
+            r#"<xliff version="1.2"><file original="f"><body><trans-unit id="a"><source>A</source></trans-unit></body></file></xliff>"#,
+            // A unit without source.
+            r#"<xliff version="2.0"><file id="f"><unit name="a"><segment><target>A</target></segment></unit></file></xliff>"#,
+            // A unit without name or id.
+            r#"<xliff version="2.0"><file id="f"><unit><segment><source>A</source></segment></unit></file></xliff>"#,
+            // An unknown entity.
+            r#"<xliff version="2.0"><file id="f"><unit name="a"><segment><source>&nbsp;</source></segment></unit></file></xliff>"#,
+        ];
+        for case in cases {
+            assert!(read(case.as_bytes()).is_err(), "accepted {}", case);
+        }
+    }
+}
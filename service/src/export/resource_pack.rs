@@ -0,0 +1,289 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    io::{Cursor, Write},
+};
+
+use entity::{
+    entry::{text_entry, text_entry_variant},
+    minecraft::minecraft_mod,
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use semver::Version;
+use serde_json::json;
+use zip::{write::FileOptions, ZipWriter};
+
+use crate::{
+    minecraft::variant::closest_game_version, translation::review::find_approved_translations,
+};
+
+/// `pack_format` of resource packs by the first game version using it, the newest last.
+const PACK_FORMATS: &[((u64, u64, u64), u32)] = &[
+    ((1, 6, 1), 1),
+    ((1, 9, 0), 2),
+    ((1, 11, 0), 3),
+    ((1, 13, 0), 4),
+    ((1, 15, 0), 5),
+    ((1, 16, 2), 6),
+    ((1, 17, 0), 7),
+    ((1, 18, 0), 8),
+    ((1, 19, 0), 9),
+    ((1, 19, 3), 12),
+    ((1, 19, 4), 13),
+    ((1, 20, 0), 15),
+    ((1, 20, 2), 18),
+    ((1, 20, 3), 22),
+    ((1, 20, 5), 32),
+    ((1, 21, 0), 34),
+    ((1, 21, 2), 42),
+    ((1, 21, 4), 46),
+    ((1, 21, 5), 55),
+];
+
+/// Returns the resource pack format of the game version, versions newer than the
+/// known ones use the latest known format.
+pub fn pack_format(game_version: &Version) -> u32 {
+    PACK_FORMATS
+        .iter()
+        .rev()
+        .find(|((major, minor, patch), _)| *game_version >= Version::new(*major, *minor, *patch))
+        .map(|(_, format)| *format)
+        .unwrap_or(1)
+}
+
+/// Builds a resource pack zip with the approved translations of the mods in the language.
+///
+/// The translations are written to the language file of every namespace the entries were
+/// found in, as JSON since 1.13 and in the legacy `.lang` format before. Untranslated
+/// entries are left out so the game falls back to English, as are translations of source
+/// values the releases of the mod for the game version do not contain.
+///
+/// Returns `None` if any of the mods does not exist.
+pub async fn build_resource_pack(
+    db: &DatabaseConnection,
+    mod_ids: &[i32],
+    language: &str,
+    game_version: &Version,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    let mods = minecraft_mod::Entity::find()
+        .filter(minecraft_mod::Column::Id.is_in(mod_ids.iter().copied()))
+        .all(db)
+        .await?;
+    if mods.len() != mod_ids.len() {
+        return Ok(None);
+    }
+
+    let entries = text_entry::Entity::find()
+        .filter(text_entry::Column::ModId.is_in(mod_ids.iter().copied()))
+        .order_by_asc(text_entry::Column::Key)
+        .all(db)
+        .await?;
+    let translations =
+        find_approved_translations(db, entries.iter().map(|entry| entry.key.clone()), language)
+            .await?;
+    let variants = text_entry_variant::Entity::find()
+        .filter(text_entry_variant::Column::ModId.is_in(mod_ids.iter().copied()))
+        .all(db)
+        .await?;
+    let sources = find_sources_for_version(&variants, game_version);
+
+    // Sorted by namespace and key so that the same translations produce the same pack.
+    let mut namespaces: BTreeMap<&str, BTreeMap<&str, &str>> = BTreeMap::new();
+    for entry in &entries {
+        // Entries archived before variants were kept have none and are exported as they are.
+        let in_version = sources
+            .get(&entry.mod_id)
+            .is_none_or(|sources| sources.contains(&(entry.key.as_str(), entry.value.as_str())));
+        if !in_version {
+            continue;
+        }
+
+        if let Some(value) = translations.get(&entry.key) {
+            for namespace in &entry.namespaces.0 {
+                namespaces
+                    .entry(namespace)
+                    .or_default()
+                    .insert(&entry.key, value);
+            }
+        }
+    }
+
+    let names: Vec<_> = mods.iter().filter_map(|x| x.name.as_deref()).collect();
+    let description = format!("{} translations of {}", language, names.join(", "));
+    let mcmeta = json!({
+        "pack": {
+            "pack_format": pack_format(game_version),
+            "description": description,
+        }
+    });
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default();
+
+    zip.start_file("pack.mcmeta", options)?;
+    zip.write_all(serde_json::to_string_pretty(&mcmeta)?.as_bytes())?;
+
+    let legacy = *game_version < Version::new(1, 13, 0);
+    let file_name = language_file_name(language, game_version);
+    for (namespace, entries) in namespaces {
+        zip.start_file(format!("assets/{}/lang/{}", namespace, file_name), options)?;
+
+        if legacy {
+            for (key, value) in entries {
+                // The legacy format has one entry per line.
+                writeln!(zip, "{}={}", key, value.replace('\n', "\\n"))?;
+            }
+        } else {
+            zip.write_all(serde_json::to_string_pretty(&entries)?.as_bytes())?;
+        }
+    }
+
+    Ok(Some(zip.finish()?.into_inner()))
+}
+
+/// Returns the keys and source values of each mod in its releases for the game version,
+/// or for the closest archived game version if the mod was not archived for it.
+fn find_sources_for_version<'a>(
+    variants: &'a [text_entry_variant::Model],
+    game_version: &Version,
+) -> HashMap<i32, HashSet<(&'a str, &'a str)>> {
+    let mut versions: HashMap<i32, Vec<(Version, &text_entry_variant::Model)>> = HashMap::new();
+    for variant in variants {
+        for version in &variant.game_versions.0 {
+            if let Ok(version) = Version::parse(version) {
+                versions
+                    .entry(variant.mod_id)
+                    .or_default()
+                    .push((version, variant));
+            }
+        }
+    }
+
+    versions
+        .into_iter()
+        .map(|(mod_id, versions)| {
+            let closest =
+                closest_game_version(versions.iter().map(|(version, _)| version), game_version);
+            let sources = versions
+                .iter()
+                .filter(|(version, _)| Some(version) == closest)
+                .map(|(_, variant)| (variant.key.as_str(), variant.value.as_str()))
+                .collect();
+            (mod_id, sources)
+        })
+        .collect()
+}
+
+/// Language files are `zh_tw.json` since 1.13, `zh_tw.lang` in 1.11 and 1.12,
+/// and `zh_TW.lang` before.
+fn language_file_name(language: &str, game_version: &Version) -> String {
+    if *game_version >= Version::new(1, 13, 0) {
+        return format!("{}.json", language);
+    }
+    if *game_version >= Version::new(1, 11, 0) {
+        return format!("{}.lang", language);
+    }
+
+    match language.split_once('_') {
+        Some((language, region)) => format!("{}_{}.lang", language, region.to_uppercase()),
+        None => format!("{}.lang", language),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use entity::{minecraft::mod_loader::ModLoader, misc::StringVec};
+
+    use super::*;
+
+    fn version(version: &str) -> Version {
+        Version::parse(version).unwrap()
+    }
+
+    #[test]
+    fn pack_format_uses_the_first_version_of_each_format() {
+        assert_eq!(pack_format(&version("1.6.1")), 1);
+        assert_eq!(pack_format(&version("1.12.2")), 3);
+        assert_eq!(pack_format(&version("1.16.1")), 5);
+        assert_eq!(pack_format(&version("1.16.2")), 6);
+        assert_eq!(pack_format(&version("1.20.1")), 15);
+        assert_eq!(pack_format(&version("1.21.5")), 55);
+    }
+
+    #[test]
+    fn pack_format_of_unknown_versions() {
+        assert_eq!(pack_format(&version("1.5.2")), 1);
+        assert_eq!(pack_format(&version("1.99.0")), 55);
+    }
+
+    #[test]
+    fn pack_formats_are_ordered() {
+        for pair in PACK_FORMATS.windows(2) {
+            assert!(pair[0].0 < pair[1].0 && pair[0].1 < pair[1].1);
+        }
+    }
+
+    #[test]
+    fn language_file_names() {
+        assert_eq!(
+            language_file_name("zh_tw", &version("1.20.1")),
+            "zh_tw.json"
+        );
+        assert_eq!(
+            language_file_name("zh_tw", &version("1.13.0")),
+            "zh_tw.json"
+        );
+        assert_eq!(
+            language_file_name("zh_tw", &version("1.12.2")),
+            "zh_tw.lang"
+        );
+        assert_eq!(
+            language_file_name("zh_tw", &version("1.11.0")),
+            "zh_tw.lang"
+        );
+        assert_eq!(
+            language_file_name("zh_tw", &version("1.10.2")),
+            "zh_TW.lang"
+        );
+        assert_eq!(
+            language_file_name("lol_us", &version("1.7.10")),
+            "lol_US.lang"
+        );
+        assert_eq!(language_file_name("eo", &version("1.7.10")), "eo.lang");
+    }
+
+    fn variant(key: &str, value: &str, game_versions: &[&str]) -> text_entry_variant::Model {
+        text_entry_variant::Model {
+            id: 0,
+            key: key.to_string(),
+            value: value.to_string(),
+            game_versions: StringVec(game_versions.iter().map(|x| x.to_string()).collect()),
+            loader: Some(ModLoader::Fabric),
+            mod_id: 1,
+        }
+    }
+
+    #[test]
+    fn sources_of_the_closest_archived_version() {
+        let variants = [
+            variant("item.a", "Old", &["1.16.5"]),
+            variant("item.a", "New", &["1.20.1"]),
+            variant("item.b", "Removed", &["1.16.5"]),
+        ];
+
+        let sources = find_sources_for_version(&variants, &version("1.18.2"));
+        assert_eq!(
+            sources[&1],
+            HashSet::from([("item.a", "Old"), ("item.b", "Removed")])
+        );
+
+        let sources = find_sources_for_version(&variants, &version("1.21.0"));
+        assert_eq!(sources[&1], HashSet::from([("item.a", "New")]));
+
+        // Older than every archived version, the oldest one is used.
+        let sources = find_sources_for_version(&variants, &version("1.12.2"));
+        assert_eq!(
+            sources[&1],
+            HashSet::from([("item.a", "Old"), ("item.b", "Removed")])
+        );
+    }
+}
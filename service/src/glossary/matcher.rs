@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use entity::entry::glossary_term::{self, PartOfSpeech};
+use log::warn;
+use regex::Regex;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// A glossary term found in a source value.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GlossaryMatch {
+    pub term_id: i32,
+    /// The term as written in the glossary.
+    pub term: String,
+    pub translation: String,
+    pub part_of_speech: Option<PartOfSpeech>,
+    pub note: Option<String>,
+    /// Offset of the first character of the term in the value, in characters.
+    pub start: usize,
+    /// Offset after the last character of the term in the value, in characters.
+    pub end: usize,
+}
+
+/// The terms applying to the entries of a mod in a language, compiled to find them in values.
+pub struct Glossary {
+    /// Terms by their lowercase text.
+    terms: HashMap<String, glossary_term::Model>,
+    pattern: Option<Regex>,
+}
+
+impl Glossary {
+    /// Terms scoped to a mod take precedence over global terms with the same text.
+    pub fn new(terms: Vec<glossary_term::Model>) -> Self {
+        let mut by_text: HashMap<String, glossary_term::Model> = HashMap::new();
+        for term in terms {
+            let text = term.term.to_lowercase();
+            if text.trim().is_empty() {
+                continue;
+            }
+            let replaces = match by_text.get(&text) {
+                Some(existing) => existing.mod_id.is_none() && term.mod_id.is_some(),
+                None => true,
+            };
+            if replaces {
+                by_text.insert(text, term);
+            }
+        }
+
+        // Alternatives are tried in order, longer terms first so that
+        // "Nether Star" is found rather than "Nether".
+        let mut texts: Vec<&String> = by_text.keys().collect();
+        texts.sort_by(|a, b| b.chars().count().cmp(&a.chars().count()).then(a.cmp(b)));
+        let alternatives: Vec<String> = texts.into_iter().map(|text| word_pattern(text)).collect();
+        let pattern = (!alternatives.is_empty())
+            .then(
+                || match Regex::new(&format!("(?i){}", alternatives.join("|"))) {
+                    Ok(pattern) => Some(pattern),
+                    Err(err) => {
+                        warn!(
+                            "Glossary of {} terms is not matched, its pattern failed to build: {}",
+                            alternatives.len(),
+                            err
+                        );
+                        None
+                    }
+                },
+            )
+            .flatten();
+
+        Self {
+            terms: by_text,
+            pattern,
+        }
+    }
+
+    /// Finds the terms in the value, in order and without overlaps.
+    pub fn find_terms(&self, value: &str) -> Vec<GlossaryMatch> {
+        let Some(pattern) = &self.pattern else {
+            return Vec::new();
+        };
+
+        pattern
+            .find_iter(value)
+            .filter_map(|found| {
+                let term = self.terms.get(&found.as_str().to_lowercase())?;
+                let start = value[..found.start()].chars().count();
+                Some(GlossaryMatch {
+                    term_id: term.id,
+                    term: term.term.clone(),
+                    translation: term.translation.clone(),
+                    part_of_speech: term.part_of_speech.clone(),
+                    note: term.note.clone(),
+                    start,
+                    end: start + found.as_str().chars().count(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Matches the text as whole words, e.g. "Nether" is not found in "Netherite".
+fn word_pattern(text: &str) -> String {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let mut pattern = regex::escape(text);
+    if is_word(text.chars().next()) {
+        pattern.insert_str(0, r"\b");
+    }
+    if is_word(text.chars().last()) {
+        pattern.push_str(r"\b");
+    }
+
+    pattern
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn term(id: i32, text: &str, translation: &str, mod_id: Option<i32>) -> glossary_term::Model {
+        glossary_term::Model {
+            id,
+            term: text.to_string(),
+            language: "zh_tw".to_string(),
+            translation: translation.to_string(),
+            part_of_speech: None,
+            note: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            mod_id,
+            creator_id: 1,
+        }
+    }
+
+    fn found(glossary: &Glossary, value: &str) -> Vec<(String, usize, usize)> {
+        glossary
+            .find_terms(value)
+            .into_iter()
+            .map(|x| (x.translation, x.start, x.end))
+            .collect()
+    }
+
+    #[test]
+    fn longest_term_is_found_first() {
+        let glossary = Glossary::new(vec![
+            term(1, "Nether", "地獄", None),
+            term(2, "Nether Star", "地獄之星", None),
+        ]);
+
+        assert_eq!(
+            found(&glossary, "Nether Star from the nether"),
+            [
+                ("地獄之星".to_string(), 0, 11),
+                ("地獄".to_string(), 21, 27)
+            ]
+        );
+    }
+
+    #[test]
+    fn terms_are_whole_words() {
+        let glossary = Glossary::new(vec![term(1, "Nether", "地獄", None)]);
+
+        assert!(found(&glossary, "Netherite Ingot").is_empty());
+        assert!(found(&glossary, "Undernether").is_empty());
+        assert_eq!(found(&glossary, "(Nether)"), [("地獄".to_string(), 1, 7)]);
+    }
+
+    #[test]
+    fn mod_terms_override_global_terms() {
+        let glossary = Glossary::new(vec![
+            term(1, "Mana", "魔力", None),
+            term(2, "mana", "法力", Some(7)),
+            term(3, "Mana", "瑪那", None),
+        ]);
+
+        let matches = glossary.find_terms("Mana Pool");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].term_id, 2);
+        assert_eq!(matches[0].translation, "法力");
+    }
+
+    #[test]
+    fn offsets_are_in_characters() {
+        let glossary = Glossary::new(vec![
+            term(1, "Ender Pearl", "終界珍珠", None),
+            term(2, "終界", "The End", None),
+        ]);
+
+        assert_eq!(
+            found(&glossary, "Über Ender Pearl"),
+            [("終界珍珠".to_string(), 5, 16)]
+        );
+        assert_eq!(
+            found(&glossary, "前往 終界 吧"),
+            [("The End".to_string(), 3, 5)]
+        );
+    }
+
+    #[test]
+    fn empty_glossary_finds_nothing() {
+        let glossary = Glossary::new(vec![term(1, "  ", "空白", None)]);
+
+        assert!(found(&glossary, "Anything at all").is_empty());
+    }
+}
@@ -0,0 +1,166 @@
+pub mod matcher;
+
+use entity::{
+    entry::glossary_term::{self, PartOfSpeech},
+    minecraft::minecraft_mod,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, Condition, DatabaseConnection, DbErr,
+    EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, QueryTrait, Set,
+};
+
+#[derive(Debug, Default)]
+pub struct GlossaryFilter {
+    pub language: Option<String>,
+    /// Only the terms scoped to the mod, global terms are listed without a mod.
+    pub mod_id: Option<i32>,
+    /// Part of the term or its translation.
+    pub query: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GlossaryTermInput {
+    pub term: String,
+    pub language: String,
+    pub translation: String,
+    pub part_of_speech: Option<PartOfSpeech>,
+    pub note: Option<String>,
+    pub mod_id: Option<i32>,
+}
+
+pub async fn find_glossary_term(
+    db: &DatabaseConnection,
+    term_id: i32,
+) -> Result<Option<glossary_term::Model>, DbErr> {
+    glossary_term::Entity::find_by_id(term_id).one(db).await
+}
+
+/// Lists the terms matching the filter ordered by term.
+pub async fn list_glossary_terms(
+    db: &DatabaseConnection,
+    filter: GlossaryFilter,
+    page: u64,
+) -> Result<(u64, Vec<glossary_term::Model>), DbErr> {
+    let pages = glossary_term::Entity::find()
+        .apply_if(filter.language, |select, language| {
+            select.filter(glossary_term::Column::Language.eq(language))
+        })
+        .apply_if(filter.mod_id, |select, mod_id| {
+            select.filter(glossary_term::Column::ModId.eq(mod_id))
+        })
+        .apply_if(filter.query, |select, query| {
+            select.filter(
+                Condition::any()
+                    .add(glossary_term::Column::Term.contains(&query))
+                    .add(glossary_term::Column::Translation.contains(&query)),
+            )
+        })
+        .order_by_asc(glossary_term::Column::Term)
+        .order_by_asc(glossary_term::Column::Id)
+        .paginate(db, 50);
+    let total_pages = pages.num_pages().await?;
+
+    Ok((total_pages, pages.fetch_page(page).await?))
+}
+
+/// Returns the terms of the language that apply to the mod, the global ones and
+/// those scoped to the mod.
+pub async fn find_applicable_terms(
+    db: &DatabaseConnection,
+    language: &str,
+    mod_id: i32,
+) -> Result<Vec<glossary_term::Model>, DbErr> {
+    glossary_term::Entity::find()
+        .filter(glossary_term::Column::Language.eq(language))
+        .filter(
+            Condition::any()
+                .add(glossary_term::Column::ModId.is_null())
+                .add(glossary_term::Column::ModId.eq(mod_id)),
+        )
+        .order_by_asc(glossary_term::Column::Id)
+        .all(db)
+        .await
+}
+
+/// Returns the term with the same text, language and scope, terms are unique regardless of case.
+pub async fn find_duplicate_term(
+    db: &DatabaseConnection,
+    input: &GlossaryTermInput,
+    exclude_id: Option<i32>,
+) -> Result<Option<glossary_term::Model>, DbErr> {
+    let scope = match input.mod_id {
+        Some(mod_id) => glossary_term::Column::ModId.eq(mod_id),
+        None => glossary_term::Column::ModId.is_null(),
+    };
+    let term = input.term.to_lowercase();
+
+    Ok(glossary_term::Entity::find()
+        .filter(glossary_term::Column::Language.eq(&input.language))
+        .filter(scope)
+        .all(db)
+        .await?
+        .into_iter()
+        .find(|existing| Some(existing.id) != exclude_id && existing.term.to_lowercase() == term))
+}
+
+async fn scope_exists(db: &DatabaseConnection, mod_id: Option<i32>) -> Result<bool, DbErr> {
+    match mod_id {
+        Some(mod_id) => Ok(minecraft_mod::Entity::find_by_id(mod_id)
+            .one(db)
+            .await?
+            .is_some()),
+        None => Ok(true),
+    }
+}
+
+/// Returns `None` if the mod the term is scoped to does not exist.
+pub async fn create_glossary_term(
+    db: &DatabaseConnection,
+    input: GlossaryTermInput,
+    creator_id: i32,
+) -> Result<Option<glossary_term::Model>, DbErr> {
+    if !scope_exists(db, input.mod_id).await? {
+        return Ok(None);
+    }
+
+    let model = glossary_term::ActiveModel {
+        id: NotSet,
+        term: Set(input.term),
+        language: Set(input.language),
+        translation: Set(input.translation),
+        part_of_speech: Set(input.part_of_speech),
+        note: Set(input.note),
+        mod_id: Set(input.mod_id),
+        creator_id: Set(creator_id),
+        ..Default::default()
+    };
+    model.insert(db).await.map(Some)
+}
+
+/// Returns `None` if the mod the term is scoped to does not exist.
+pub async fn update_glossary_term(
+    db: &DatabaseConnection,
+    term: glossary_term::Model,
+    input: GlossaryTermInput,
+) -> Result<Option<glossary_term::Model>, DbErr> {
+    if !scope_exists(db, input.mod_id).await? {
+        return Ok(None);
+    }
+
+    let mut model = term.into_active_model();
+    model.term = Set(input.term);
+    model.language = Set(input.language);
+    model.translation = Set(input.translation);
+    model.part_of_speech = Set(input.part_of_speech);
+    model.note = Set(input.note);
+    model.mod_id = Set(input.mod_id);
+    model.update(db).await.map(Some)
+}
+
+/// Deletes the term, returns `false` if there is no such term.
+pub async fn delete_glossary_term(db: &DatabaseConnection, term_id: i32) -> Result<bool, DbErr> {
+    let result = glossary_term::Entity::delete_by_id(term_id)
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
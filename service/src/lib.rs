@@ -1,5 +1,12 @@
+pub mod account;
 pub mod archive;
+pub mod exchange;
+pub mod export;
+pub mod glossary;
 pub mod minecraft;
+pub mod qa;
+pub mod translation;
 
+pub use chrono;
 pub use entity::database_initializer::initialize_database;
 pub use sea_orm;
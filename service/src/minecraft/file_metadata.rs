@@ -5,7 +5,7 @@ use std::{
 };
 
 use anyhow::Context;
-use zip::{read::ZipFile, ZipArchive};
+use zip::ZipArchive;
 
 pub fn parse_namespace(archive: &mut ZipArchive<BufReader<&File>>) -> anyhow::Result<String> {
     let fabric_file = archive.by_name("fabric.mod.json");
@@ -28,14 +28,17 @@ pub fn parse_namespace(archive: &mut ZipArchive<BufReader<&File>>) -> anyhow::Re
     Err(anyhow::anyhow!("Failed to parse namespace"))
 }
 
-pub fn parse_language_file(file: &mut ZipFile) -> anyhow::Result<HashMap<String, String>> {
+pub fn parse_language_file(file: &mut impl Read) -> anyhow::Result<HashMap<String, String>> {
     let mut content = String::new();
     file.read_to_string(&mut content)?;
     let json_value = serde_json::from_str::<serde_json::Value>(&content)?;
 
     let mut map: HashMap<String, String> = HashMap::new();
 
-    for (key, value) in json_value.as_object().unwrap() {
+    let object = json_value
+        .as_object()
+        .context("Language file is not a JSON object")?;
+    for (key, value) in object {
         if let Some(value) = value.as_str() {
             map.insert(key.to_string(), value.to_string());
         }
@@ -4,10 +4,15 @@ use entity::minecraft::{
     minecraft_mod::{self, ModStatus},
     mod_provider::{self, ModProviderType},
 };
-use sea_orm::{DatabaseConnection, DbErr, EntityTrait, ModelTrait, QueryOrder};
+use sea_orm::{
+    ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, ModelTrait, QueryOrder, Set,
+};
 use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::translation::stats::{find_mod_stats, TranslationStats};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ModMetadata {
     pub id: i32,
     pub status: ModStatus,
@@ -15,20 +20,28 @@ pub struct ModMetadata {
     pub description: String,
     pub image_url: Option<String>,
     pub page_url: HashMap<ModProviderType, String>,
+    /// Translation progress for each language the mod has translations in.
+    pub stats: Vec<TranslationStats>,
 }
 
 impl ModMetadata {
-    pub async fn from_model(model: minecraft_mod::Model, db: &DatabaseConnection) -> Option<Self> {
+    /// Returns `None` if the mod has no provider yet.
+    pub async fn from_model(
+        model: minecraft_mod::Model,
+        db: &DatabaseConnection,
+    ) -> Result<Option<Self>, DbErr> {
         let providers = model
             .find_related(mod_provider::Entity)
             .order_by_desc(mod_provider::Column::UpdatedAt)
             .all(db)
-            .await
-            .ok()?;
+            .await?;
         // Prefer the latest updated mod provider information.
-        let preferred_provider = providers.first()?;
+        let Some(preferred_provider) = providers.first() else {
+            return Ok(None);
+        };
+        let stats = find_mod_stats(db, model.id).await?;
 
-        Some(ModMetadata {
+        Ok(Some(ModMetadata {
             id: model.id,
             status: model.status,
             name: model
@@ -36,11 +49,14 @@ impl ModMetadata {
                 .unwrap_or(preferred_provider.display_name.clone()),
             description: preferred_provider.description.clone(),
             image_url: preferred_provider.image_url.clone(),
+            // Uploaded mods may have no page.
             page_url: providers
                 .into_iter()
+                .filter(|provider| !provider.page_url.is_empty())
                 .map(|provider| (provider.provider_type, provider.page_url))
                 .collect(),
-        })
+            stats,
+        }))
     }
 }
 
@@ -51,7 +67,26 @@ pub async fn lookup_mod_metadata(
     let model = minecraft_mod::Entity::find_by_id(mod_id).one(db).await?;
 
     if let Some(model) = model {
-        Ok(ModMetadata::from_model(model, db).await)
+        ModMetadata::from_model(model, db).await
+    } else {
+        Ok(None)
+    }
+}
+
+/// Changes the status of the mod, returns `None` if the mod does not exist.
+pub async fn update_mod_status(
+    db: &DatabaseConnection,
+    mod_id: i32,
+    status: ModStatus,
+) -> Result<Option<ModMetadata>, DbErr> {
+    let model = minecraft_mod::Entity::find_by_id(mod_id).one(db).await?;
+
+    if let Some(model) = model {
+        let mut model: minecraft_mod::ActiveModel = model.into();
+        model.status = Set(status);
+        let model = model.update(db).await?;
+
+        ModMetadata::from_model(model, db).await
     } else {
         Ok(None)
     }
@@ -1,4 +1,5 @@
 pub mod file_metadata;
-pub mod version;
+pub mod metadata;
 pub mod search;
-pub mod metadata;
\ No newline at end of file
+pub mod variant;
+pub mod version;
@@ -7,14 +7,38 @@ use sea_orm::{
     QueryTrait,
 };
 use serde::Serialize;
+use utoipa::ToSchema;
 
 use super::metadata::ModMetadata;
+use crate::{
+    glossary::{
+        find_applicable_terms,
+        matcher::{Glossary, GlossaryMatch},
+    },
+    translation::{
+        machine::TranslationOrigin,
+        memory::{find_memory_matches, MemoryMatch},
+        review::find_approved_translations,
+    },
+};
+
+/// Suggestions from the translation memory given for each entry.
+const MEMORY_SUGGESTIONS: usize = 3;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct TextEntry {
     pub key: String,
     pub value: String,
+    /// The approved translation in the requested language, if any.
     pub preview_translation: Option<String>,
+    /// Where the preview translation comes from, machine previews are only given on request.
+    pub preview_origin: Option<TranslationOrigin>,
+    /// Approved translations of similar strings in the requested language, the most similar first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub memory_matches: Vec<MemoryMatch>,
+    /// Glossary terms of the requested language found in the value, in order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub glossary_terms: Vec<GlossaryMatch>,
 }
 
 pub async fn search_mods_in_database(
@@ -35,7 +59,7 @@ pub async fn search_mods_in_database(
     let mut mods = Vec::with_capacity(raw_mods.len());
 
     for raw_mod in raw_mods {
-        if let Some(result) = ModMetadata::from_model(raw_mod, db).await {
+        if let Some(result) = ModMetadata::from_model(raw_mod, db).await? {
             mods.push(result);
         }
     }
@@ -47,6 +71,7 @@ pub async fn search_mod_entries_in_database(
     db: &DatabaseConnection,
     mod_id: i32,
     query: Option<String>,
+    language: Option<String>,
     page: u64,
 ) -> Result<(u64, Vec<TextEntry>), DbErr> {
     let pages = text_entry::Entity::find()
@@ -65,14 +90,43 @@ pub async fn search_mod_entries_in_database(
         .into_iter()
         .map(|(entry, _)| entry)
         .collect();
-    let mut entries = Vec::with_capacity(raw_entries.len());
+    let mut translations = match &language {
+        Some(language) => {
+            let keys = raw_entries.iter().map(|entry| entry.key.clone());
+            find_approved_translations(db, keys, language).await?
+        }
+        None => Default::default(),
+    };
+    let glossary = match &language {
+        Some(language) => Glossary::new(find_applicable_terms(db, language, mod_id).await?),
+        None => Glossary::new(Vec::new()),
+    };
 
-    for entry in &raw_entries {
+    let mut entries = Vec::with_capacity(raw_entries.len());
+    for entry in raw_entries {
+        let memory_matches = match &language {
+            Some(language) => {
+                find_memory_matches(
+                    db,
+                    &entry.value,
+                    language,
+                    Some(&entry.key),
+                    MEMORY_SUGGESTIONS,
+                )
+                .await?
+            }
+            None => Vec::new(),
+        };
+        let preview_translation = translations.remove(&entry.key);
         entries.push(TextEntry {
-            key: entry.key.clone(),
-            value: entry.value.clone(),
-            // TODO: fetch preview translation from database.
-            preview_translation: None,
+            preview_origin: preview_translation
+                .as_ref()
+                .map(|_| TranslationOrigin::Human),
+            preview_translation,
+            memory_matches,
+            glossary_terms: glossary.find_terms(&entry.value),
+            key: entry.key,
+            value: entry.value,
         });
     }
 
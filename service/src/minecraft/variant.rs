@@ -0,0 +1,95 @@
+use entity::{entry::text_entry_variant, minecraft::mod_loader::ModLoader};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TextEntryVariant {
+    pub value: String,
+    pub game_versions: Vec<String>,
+    /// Not set for the vanilla game, whose values apply to every loader.
+    pub loader: Option<ModLoader>,
+}
+
+impl From<text_entry_variant::Model> for TextEntryVariant {
+    fn from(model: text_entry_variant::Model) -> Self {
+        Self {
+            value: model.value,
+            game_versions: model.game_versions.0,
+            loader: model.loader,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VersionedValue {
+    #[serde(flatten)]
+    pub variant: TextEntryVariant,
+    /// Whether the value was archived for the requested game version, otherwise it is the
+    /// value of the closest archived game version (preferring older ones).
+    pub exact_match: bool,
+}
+
+/// Lists the distinct values of the entry across the archived releases of the mod,
+/// once per loader the value was archived for.
+pub async fn find_entry_variants(
+    db: &DatabaseConnection,
+    mod_id: i32,
+    key: &str,
+) -> Result<Vec<TextEntryVariant>, DbErr> {
+    let variants = text_entry_variant::Entity::find()
+        .filter(text_entry_variant::Column::ModId.eq(mod_id))
+        .filter(text_entry_variant::Column::Key.eq(key))
+        .order_by_asc(text_entry_variant::Column::Id)
+        .all(db)
+        .await?;
+
+    Ok(variants.into_iter().map(TextEntryVariant::from).collect())
+}
+
+/// Finds the value of the entry for the game version, optionally of a loader.
+///
+/// If the entry was not archived for the game version, the value of the closest older
+/// archived version is used, or the closest newer one if there is no older version.
+pub async fn find_entry_value_for_version(
+    db: &DatabaseConnection,
+    mod_id: i32,
+    key: &str,
+    game_version: &semver::Version,
+    loader: Option<&ModLoader>,
+) -> Result<Option<VersionedValue>, DbErr> {
+    let variants = find_entry_variants(db, mod_id, key).await?;
+
+    let mut candidates = Vec::new();
+    for variant in &variants {
+        if loader.is_some_and(|loader| variant.loader.as_ref().is_some_and(|x| x != loader)) {
+            continue;
+        }
+        for version in &variant.game_versions {
+            if let Ok(version) = semver::Version::parse(version) {
+                candidates.push((version, variant));
+            }
+        }
+    }
+
+    let closest = closest_game_version(candidates.iter().map(|(version, _)| version), game_version)
+        .and_then(|closest| candidates.iter().find(|(version, _)| version == closest))
+        .map(|(version, variant)| VersionedValue {
+            variant: (*variant).clone(),
+            exact_match: version == game_version,
+        });
+    Ok(closest)
+}
+
+/// Returns the game version itself if it is archived, otherwise the closest older
+/// archived version, or the closest newer one if there is no older version.
+pub fn closest_game_version<'a>(
+    versions: impl IntoIterator<Item = &'a semver::Version>,
+    game_version: &semver::Version,
+) -> Option<&'a semver::Version> {
+    let (older, newer): (Vec<_>, Vec<_>) = versions
+        .into_iter()
+        .partition(|version| *version <= game_version);
+
+    older.into_iter().max().or(newer.into_iter().min())
+}
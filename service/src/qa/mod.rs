@@ -0,0 +1,402 @@
+//! Quality checks comparing a translation with its source string, so that a
+//! translation cannot break the placeholders and formatting the game relies on.
+
+pub mod report;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::glossary::matcher::Glossary;
+
+lazy_static! {
+    /// The placeholders supported by the game's translatable components.
+    static ref FORMAT_PLACEHOLDER: Regex = Regex::new(r"%(?:(\d+)\$)?([A-Za-z%]|$)").unwrap();
+    /// Placeholders formatted by mods themselves, e.g. `{}` or `{0}`.
+    static ref BRACE_PLACEHOLDER: Regex = Regex::new(r"\{[^{}\s]*\}").unwrap();
+    static ref FORMATTING_CODE: Regex = Regex::new(r"§[0-9a-fk-orA-FK-OR]").unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QaSeverity {
+    /// The translation breaks the string in game, it cannot be submitted.
+    Error,
+    /// Likely a mistake, but the string still works.
+    Warning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QaIssueKind {
+    MissingPlaceholder,
+    ExtraPlaceholder,
+    ReorderedPlaceholders,
+    FormattingCodes,
+    LeadingWhitespace,
+    TrailingWhitespace,
+    Newlines,
+    GlossaryTerm,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct QaIssue {
+    pub kind: QaIssueKind,
+    pub severity: QaSeverity,
+    pub message: String,
+    /// The placeholders or formatting codes concerned.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+}
+
+impl QaIssue {
+    fn new(kind: QaIssueKind, severity: QaSeverity, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            severity,
+            message: message.into(),
+            details: Vec::new(),
+        }
+    }
+
+    fn with_details(mut self, details: Vec<String>) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+/// Returns `true` if any of the issues prevents the translation from being submitted.
+pub fn has_errors(issues: &[QaIssue]) -> bool {
+    issues
+        .iter()
+        .any(|issue| issue.severity == QaSeverity::Error)
+}
+
+/// A format placeholder with the argument it refers to.
+#[derive(Debug, Clone)]
+struct Placeholder {
+    text: String,
+    /// Zero-based index of the argument.
+    index: usize,
+    conversion: String,
+}
+
+impl PartialEq for Placeholder {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.conversion == other.conversion
+    }
+}
+
+/// Parses the format placeholders like the game does, implicit placeholders
+/// (`%s`) take the arguments in order, independently of explicit ones (`%2$s`).
+fn parse_placeholders(value: &str) -> Vec<Placeholder> {
+    let mut implicit_index = 0;
+    let mut placeholders = Vec::new();
+
+    for captures in FORMAT_PLACEHOLDER.captures_iter(value) {
+        let conversion = captures[2].to_string();
+        // `%%` is an escaped percent sign.
+        if conversion == "%" {
+            continue;
+        }
+
+        let explicit_index = captures
+            .get(1)
+            .and_then(|index| index.as_str().parse::<usize>().ok());
+        let index = match explicit_index {
+            Some(index) => index.saturating_sub(1),
+            None => {
+                implicit_index += 1;
+                implicit_index - 1
+            }
+        };
+        placeholders.push(Placeholder {
+            text: captures[0].to_string(),
+            index,
+            conversion,
+        });
+    }
+
+    placeholders
+}
+
+/// Returns the items of `a` that have no counterpart in `b`, each item of `b` matches once.
+fn difference<T: PartialEq + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    let mut remaining = b.to_vec();
+    let mut difference = Vec::new();
+    for item in a {
+        match remaining.iter().position(|other| other == item) {
+            Some(position) => {
+                remaining.remove(position);
+            }
+            None => difference.push(item.clone()),
+        }
+    }
+
+    difference
+}
+
+fn matches(regex: &Regex, value: &str) -> Vec<String> {
+    regex
+        .find_iter(value)
+        .map(|found| found.as_str().to_string())
+        .collect()
+}
+
+/// Compares the translation with its source string.
+pub fn check_translation(source: &str, target: &str) -> Vec<QaIssue> {
+    let mut issues = Vec::new();
+    check_placeholders(source, target, &mut issues);
+
+    let source_braces = matches(&BRACE_PLACEHOLDER, source);
+    let target_braces = matches(&BRACE_PLACEHOLDER, target);
+    let missing = difference(&source_braces, &target_braces);
+    if !missing.is_empty() {
+        issues.push(
+            QaIssue::new(
+                QaIssueKind::MissingPlaceholder,
+                QaSeverity::Error,
+                "Placeholders of the source are missing",
+            )
+            .with_details(missing),
+        );
+    }
+    let extra = difference(&target_braces, &source_braces);
+    if !extra.is_empty() {
+        issues.push(
+            QaIssue::new(
+                QaIssueKind::ExtraPlaceholder,
+                QaSeverity::Error,
+                "Placeholders are not in the source",
+            )
+            .with_details(extra),
+        );
+    }
+
+    // Codes may move with the words they format, only their use has to match.
+    let mut source_codes = matches(&FORMATTING_CODE, &source.to_lowercase());
+    let mut target_codes = matches(&FORMATTING_CODE, &target.to_lowercase());
+    source_codes.sort();
+    target_codes.sort();
+    if source_codes != target_codes {
+        let mut details = difference(&source_codes, &target_codes);
+        details.extend(difference(&target_codes, &source_codes));
+        issues.push(
+            QaIssue::new(
+                QaIssueKind::FormattingCodes,
+                QaSeverity::Warning,
+                "Formatting codes differ from the source",
+            )
+            .with_details(details),
+        );
+    }
+
+    if source.starts_with(char::is_whitespace) != target.starts_with(char::is_whitespace) {
+        issues.push(QaIssue::new(
+            QaIssueKind::LeadingWhitespace,
+            QaSeverity::Warning,
+            "Leading whitespace differs from the source",
+        ));
+    }
+    if source.ends_with(char::is_whitespace) != target.ends_with(char::is_whitespace) {
+        issues.push(QaIssue::new(
+            QaIssueKind::TrailingWhitespace,
+            QaSeverity::Warning,
+            "Trailing whitespace differs from the source",
+        ));
+    }
+    if source.matches('\n').count() != target.matches('\n').count() {
+        issues.push(QaIssue::new(
+            QaIssueKind::Newlines,
+            QaSeverity::Warning,
+            "Number of line breaks differs from the source",
+        ));
+    }
+
+    issues
+}
+
+/// Checks that the glossary terms found in the source are rendered as the glossary says.
+pub fn check_glossary(source: &str, target: &str, glossary: &Glossary) -> Option<QaIssue> {
+    let target = target.to_lowercase();
+    let mut details: Vec<String> = Vec::new();
+    for found in glossary.find_terms(source) {
+        let detail = format!("{} → {}", found.term, found.translation);
+        if !target.contains(&found.translation.to_lowercase()) && !details.contains(&detail) {
+            details.push(detail);
+        }
+    }
+
+    (!details.is_empty()).then(|| {
+        QaIssue::new(
+            QaIssueKind::GlossaryTerm,
+            QaSeverity::Warning,
+            "Glossary terms are not translated as in the glossary",
+        )
+        .with_details(details)
+    })
+}
+
+fn check_placeholders(source: &str, target: &str, issues: &mut Vec<QaIssue>) {
+    let source_placeholders = parse_placeholders(source);
+    let target_placeholders = parse_placeholders(target);
+    let missing = difference(&source_placeholders, &target_placeholders);
+    let extra = difference(&target_placeholders, &source_placeholders);
+    let texts = |placeholders: &[Placeholder]| -> Vec<String> {
+        placeholders
+            .iter()
+            .map(|placeholder| placeholder.text.clone())
+            .collect()
+    };
+
+    // The same placeholders taking different arguments, e.g. `%s %d` translated as
+    // `%d %s`, which needs explicit indexes (`%2$d %1$s`) instead.
+    let mut source_conversions: Vec<_> = missing.iter().map(|x| &x.conversion).collect();
+    let mut target_conversions: Vec<_> = extra.iter().map(|x| &x.conversion).collect();
+    source_conversions.sort();
+    target_conversions.sort();
+    if !missing.is_empty() && source_conversions == target_conversions {
+        issues.push(
+            QaIssue::new(
+                QaIssueKind::ReorderedPlaceholders,
+                QaSeverity::Error,
+                "Placeholders are reordered, use explicit indexes like %1$s to change their order",
+            )
+            .with_details(texts(&target_placeholders)),
+        );
+        return;
+    }
+
+    if !missing.is_empty() {
+        issues.push(
+            QaIssue::new(
+                QaIssueKind::MissingPlaceholder,
+                QaSeverity::Error,
+                "Placeholders of the source are missing",
+            )
+            .with_details(texts(&missing)),
+        );
+    }
+    if !extra.is_empty() {
+        issues.push(
+            QaIssue::new(
+                QaIssueKind::ExtraPlaceholder,
+                QaSeverity::Error,
+                "Placeholders are not in the source",
+            )
+            .with_details(texts(&extra)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholder_issues(source: &str, target: &str) -> Vec<(QaIssueKind, Vec<String>)> {
+        let mut issues = Vec::new();
+        check_placeholders(source, target, &mut issues);
+        issues
+            .into_iter()
+            .map(|issue| (issue.kind, issue.details))
+            .collect()
+    }
+
+    fn kinds(source: &str, target: &str) -> Vec<QaIssueKind> {
+        check_translation(source, target)
+            .into_iter()
+            .map(|issue| issue.kind)
+            .collect()
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn matching_placeholders() {
+        assert!(placeholder_issues("%s has %d items", "%s hat %d Gegenstände").is_empty());
+        assert!(placeholder_issues("%s", "%1$s").is_empty());
+        assert!(placeholder_issues("%1$s and %2$s", "%2$s und %1$s").is_empty());
+    }
+
+    #[test]
+    fn reordered_placeholders() {
+        assert_eq!(
+            placeholder_issues("%s has %d items", "%d Gegenstände hat %s"),
+            [(QaIssueKind::ReorderedPlaceholders, strings(&["%d", "%s"]))]
+        );
+        // Explicit indexes keep the arguments in place.
+        assert!(placeholder_issues("%s has %d items", "%2$d Gegenstände hat %1$s").is_empty());
+    }
+
+    #[test]
+    fn missing_and_extra_placeholders() {
+        assert_eq!(
+            placeholder_issues("%s killed %s", "%s wurde getötet"),
+            [(QaIssueKind::MissingPlaceholder, strings(&["%s"]))]
+        );
+        assert_eq!(
+            placeholder_issues("Killed", "%s getötet"),
+            [(QaIssueKind::ExtraPlaceholder, strings(&["%s"]))]
+        );
+        assert_eq!(
+            placeholder_issues("%s items", "%d Gegenstände"),
+            [
+                (QaIssueKind::MissingPlaceholder, strings(&["%s"])),
+                (QaIssueKind::ExtraPlaceholder, strings(&["%d"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn escaped_percent_signs() {
+        assert!(placeholder_issues("100%% done", "100 %% fertig").is_empty());
+        assert!(placeholder_issues("100%% done", "fertig").is_empty());
+        assert!(placeholder_issues("Done", "100%% fertig").is_empty());
+        // A percent sign followed by a space is not a placeholder.
+        assert!(placeholder_issues("50% off", "50 Prozent Rabatt").is_empty());
+    }
+
+    #[test]
+    fn trailing_percent_signs() {
+        // The game reads a trailing percent sign as a placeholder.
+        assert!(placeholder_issues("Progress: 100%", "Fortschritt: 100 %").is_empty());
+        assert_eq!(
+            placeholder_issues("Progress: 100%", "Fortschritt: 100 Prozent"),
+            [(QaIssueKind::MissingPlaceholder, strings(&["%"]))]
+        );
+        assert_eq!(
+            placeholder_issues("Progress", "Fortschritt %"),
+            [(QaIssueKind::ExtraPlaceholder, strings(&["%"]))]
+        );
+    }
+
+    #[test]
+    fn brace_placeholders() {
+        assert!(kinds("{0} joined {1}", "{1} beigetreten von {0}").is_empty());
+        assert!(kinds("Hello {}", "Hallo {}").is_empty());
+        assert_eq!(
+            kinds("{0} joined {1}", "{0} ist beigetreten"),
+            [QaIssueKind::MissingPlaceholder]
+        );
+        assert_eq!(
+            kinds("Joined", "{player} beigetreten"),
+            [QaIssueKind::ExtraPlaceholder]
+        );
+        // Braces around text with whitespace are not placeholders.
+        assert!(kinds("{not a placeholder}", "{kein Platzhalter}").is_empty());
+        // Each placeholder of the source has to be used as often.
+        let issues = check_translation("{} + {} = {}", "{} + {}");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].details, strings(&["{}"]));
+    }
+
+    #[test]
+    fn placeholder_issues_are_errors() {
+        assert!(has_errors(&check_translation("%s", "")));
+        assert!(has_errors(&check_translation("{0}", "")));
+        assert!(!has_errors(&check_translation("§aGreen", "Grün ")));
+    }
+}
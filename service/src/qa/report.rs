@@ -0,0 +1,96 @@
+use entity::entry::{
+    text_entry,
+    translation::{self, TranslationStatus},
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::{check_glossary, check_translation, QaIssue};
+use crate::{
+    glossary::{find_applicable_terms, matcher::Glossary},
+    translation::review::pick_live_translations,
+};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QaReportEntry {
+    pub key: String,
+    pub source: String,
+    #[schema(value_type = Translation)]
+    pub translation: translation::Model,
+    pub issues: Vec<QaIssue>,
+}
+
+/// Checks the translation of an entry, returns `None` if the entry does not exist.
+/// The glossary of the language is only checked if a language is given.
+pub async fn check_entry_translation(
+    db: &DatabaseConnection,
+    key: &str,
+    value: &str,
+    language: Option<&str>,
+) -> Result<Option<Vec<QaIssue>>, DbErr> {
+    let Some(entry) = text_entry::Entity::find_by_id(key.to_string())
+        .one(db)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let mut issues = check_translation(&entry.value, value);
+    if let Some(language) = language {
+        let glossary = Glossary::new(find_applicable_terms(db, language, entry.mod_id).await?);
+        issues.extend(check_glossary(&entry.value, value, &glossary));
+    }
+
+    Ok(Some(issues))
+}
+
+/// Checks the live and submitted translations of the mod in the language,
+/// returns those with issues ordered by key.
+pub async fn build_qa_report(
+    db: &DatabaseConnection,
+    mod_id: i32,
+    language: &str,
+) -> Result<Vec<QaReportEntry>, DbErr> {
+    let translations = translation::Entity::find()
+        .find_also_related(text_entry::Entity)
+        .filter(text_entry::Column::ModId.eq(mod_id))
+        .filter(translation::Column::Language.eq(language))
+        .filter(
+            translation::Column::Status
+                .is_in([TranslationStatus::Submitted, TranslationStatus::Approved]),
+        )
+        .all(db)
+        .await?;
+
+    let glossary = Glossary::new(find_applicable_terms(db, language, mod_id).await?);
+
+    let (approved, submitted): (Vec<_>, Vec<_>) = translations
+        .into_iter()
+        .filter_map(|(translation, entry)| Some((translation, entry?.value)))
+        .partition(|(translation, _)| translation.status == TranslationStatus::Approved);
+
+    let live = pick_live_translations(approved, |(translation, _)| translation);
+
+    let mut report: Vec<QaReportEntry> = live
+        .into_values()
+        .chain(submitted)
+        .filter_map(|(translation, source)| {
+            let mut issues = check_translation(&source, &translation.value);
+            issues.extend(check_glossary(&source, &translation.value, &glossary));
+            (!issues.is_empty()).then(|| QaReportEntry {
+                key: translation.key.clone(),
+                source,
+                translation,
+                issues,
+            })
+        })
+        .collect();
+    report.sort_by(|a, b| {
+        a.key
+            .cmp(&b.key)
+            .then(a.translation.id.cmp(&b.translation.id))
+    });
+
+    Ok(report)
+}
@@ -0,0 +1,189 @@
+use entity::entry::{
+    text_entry,
+    translation::{self, TranslationStatus},
+    translation_revision::RevisionAction,
+    translation_vote,
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, QueryTrait, Set, TransactionTrait,
+};
+
+use super::{history::record_revision, stats::invalidate_entry_stats};
+
+#[derive(Debug, Default)]
+pub struct TranslationFilter {
+    pub key: Option<String>,
+    pub language: Option<String>,
+    pub status: Option<TranslationStatus>,
+    pub translator_id: Option<i32>,
+}
+
+pub async fn find_translation(
+    db: &DatabaseConnection,
+    translation_id: i32,
+) -> Result<Option<translation::Model>, DbErr> {
+    translation::Entity::find_by_id(translation_id)
+        .one(db)
+        .await
+}
+
+pub async fn find_translations(
+    db: &DatabaseConnection,
+    translation_ids: &[i32],
+) -> Result<Vec<translation::Model>, DbErr> {
+    translation::Entity::find()
+        .filter(translation::Column::Id.is_in(translation_ids.iter().copied()))
+        .order_by_asc(translation::Column::Id)
+        .all(db)
+        .await
+}
+
+/// Lists the translations matching the filter, the most recently updated first.
+pub async fn list_translations(
+    db: &DatabaseConnection,
+    filter: TranslationFilter,
+    page: u64,
+) -> Result<(u64, Vec<translation::Model>), DbErr> {
+    let pages = translation::Entity::find()
+        .apply_if(filter.key, |select, key| {
+            select.filter(translation::Column::Key.eq(key))
+        })
+        .apply_if(filter.language, |select, language| {
+            select.filter(translation::Column::Language.eq(language))
+        })
+        .apply_if(filter.status, |select, status| {
+            select.filter(translation::Column::Status.eq(status))
+        })
+        .apply_if(filter.translator_id, |select, translator_id| {
+            select.filter(translation::Column::TranslatorId.eq(translator_id))
+        })
+        .order_by_desc(translation::Column::UpdatedAt)
+        .order_by_desc(translation::Column::Id)
+        .paginate(db, 50);
+    let total_pages = pages.num_pages().await?;
+
+    Ok((total_pages, pages.fetch_page(page).await?))
+}
+
+/// Saves the translation of the translator for the entry, as a draft or directly submitted
+/// for review. Returns `None` if the entry does not exist.
+///
+/// A pending (not yet approved) translation of the translator is updated in place, so each
+/// translator has at most one pending translation per entry and language, changing its
+/// value clears its votes. Approved translations are never modified, a new translation is
+/// created instead so the approved one stays live until the new one is approved.
+pub async fn save_translation(
+    db: &DatabaseConnection,
+    translator_id: i32,
+    key: String,
+    language: String,
+    value: String,
+    submit: bool,
+) -> Result<Option<translation::Model>, DbErr> {
+    if text_entry::Entity::find_by_id(key.clone())
+        .one(db)
+        .await?
+        .is_none()
+    {
+        return Ok(None);
+    }
+
+    let status = if submit {
+        TranslationStatus::Submitted
+    } else {
+        TranslationStatus::Draft
+    };
+    let txn = db.begin().await?;
+    let pending = translation::Entity::find()
+        .filter(translation::Column::Key.eq(key.clone()))
+        .filter(translation::Column::Language.eq(language.clone()))
+        .filter(translation::Column::TranslatorId.eq(translator_id))
+        .filter(translation::Column::Status.ne(TranslationStatus::Approved))
+        .one(&txn)
+        .await?;
+
+    let (model, action, previous_value) = match pending {
+        Some(pending) => {
+            // Votes were cast on the previous value.
+            let value_changed = pending.value != value;
+            if value_changed {
+                translation_vote::Entity::delete_many()
+                    .filter(translation_vote::Column::TranslationId.eq(pending.id))
+                    .exec(&txn)
+                    .await?;
+            }
+            let previous_value = pending.value.clone();
+
+            let mut model = pending.into_active_model();
+            if value_changed {
+                model.score = Set(0);
+            }
+            model.value = Set(value);
+            model.status = Set(status);
+            // The value was saved against the current source.
+            model.source_changed = Set(false);
+            model.review_comment = Set(None);
+            model.reviewed_at = Set(None);
+            model.reviewer_id = Set(None);
+            let model = model.update(&txn).await?;
+            (model, RevisionAction::Edited, Some(previous_value))
+        }
+        None => {
+            let model = translation::ActiveModel {
+                id: NotSet,
+                key: Set(key),
+                language: Set(language),
+                value: Set(value),
+                status: Set(status),
+                score: Set(0),
+                source_changed: Set(false),
+                review_comment: Set(None),
+                reviewed_at: Set(None),
+                translator_id: Set(translator_id),
+                reviewer_id: Set(None),
+                ..Default::default()
+            };
+            (model.insert(&txn).await?, RevisionAction::Created, None)
+        }
+    };
+    record_revision(
+        &txn,
+        &model,
+        action,
+        previous_value,
+        Some(translator_id),
+        None,
+    )
+    .await?;
+
+    txn.commit().await?;
+    invalidate_entry_stats(db, [model.key.clone()]).await;
+    Ok(Some(model))
+}
+
+/// Submits a draft or rejected translation for review.
+pub async fn submit_translation(
+    db: &DatabaseConnection,
+    translation: translation::Model,
+) -> Result<translation::Model, DbErr> {
+    let txn = db.begin().await?;
+
+    let translator_id = translation.translator_id;
+    let mut model = translation.into_active_model();
+    model.status = Set(TranslationStatus::Submitted);
+    let translation = model.update(&txn).await?;
+    record_revision(
+        &txn,
+        &translation,
+        RevisionAction::Submitted,
+        None,
+        Some(translator_id),
+        None,
+    )
+    .await?;
+
+    txn.commit().await?;
+    invalidate_entry_stats(db, [translation.key.clone()]).await;
+    Ok(translation)
+}
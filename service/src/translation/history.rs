@@ -0,0 +1,135 @@
+use chrono::Utc;
+use entity::entry::{
+    text_entry, text_entry_revision,
+    translation::{self, TranslationStatus},
+    translation_revision::{self, RevisionAction},
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+
+use super::{
+    memory::invalidate_memory, review::find_approved_translations, stats::invalidate_entry_stats,
+};
+
+/// Records a change to the translation, `translation` is the state after the change.
+pub(crate) async fn record_revision<C: ConnectionTrait>(
+    db: &C,
+    translation: &translation::Model,
+    action: RevisionAction,
+    previous_value: Option<String>,
+    user_id: Option<i32>,
+    comment: Option<String>,
+) -> Result<translation_revision::Model, DbErr> {
+    let model = translation_revision::ActiveModel {
+        id: NotSet,
+        key: Set(translation.key.clone()),
+        language: Set(translation.language.clone()),
+        action: Set(action),
+        value: Set(translation.value.clone()),
+        previous_value: Set(previous_value),
+        status: Set(translation.status.clone()),
+        comment: Set(comment),
+        translation_id: Set(translation.id),
+        user_id: Set(user_id),
+        ..Default::default()
+    };
+    model.insert(db).await
+}
+
+pub async fn find_revision(
+    db: &DatabaseConnection,
+    revision_id: i32,
+) -> Result<Option<translation_revision::Model>, DbErr> {
+    translation_revision::Entity::find_by_id(revision_id)
+        .one(db)
+        .await
+}
+
+/// Lists the changes to all translations of the entry in the language, the newest first.
+pub async fn list_history(
+    db: &DatabaseConnection,
+    key: &str,
+    language: &str,
+) -> Result<Vec<translation_revision::Model>, DbErr> {
+    translation_revision::Entity::find()
+        .filter(translation_revision::Column::Key.eq(key))
+        .filter(translation_revision::Column::Language.eq(language))
+        .order_by_desc(translation_revision::Column::Id)
+        .all(db)
+        .await
+}
+
+/// Makes the value of the revision live again by approving it as a new translation,
+/// credited to the translator of the revision. The replaced live value is kept in the
+/// history like any other change. The reverted translation is flagged as outdated if
+/// the source value changed since the revision was made.
+pub async fn revert_to_revision(
+    db: &DatabaseConnection,
+    revision: translation_revision::Model,
+    user_id: i32,
+) -> Result<translation::Model, DbErr> {
+    let txn = db.begin().await?;
+
+    let translator_id = translation::Entity::find_by_id(revision.translation_id)
+        .one(&txn)
+        .await?
+        .map(|translation| translation.translator_id)
+        .ok_or(DbErr::RecordNotFound("Translation not found".to_string()))?;
+    let live_value = find_approved_translations(&txn, [revision.key.clone()], &revision.language)
+        .await?
+        .remove(&revision.key);
+    let source_changed = source_changed_since(&txn, &revision).await?;
+
+    let comment = format!("Reverted to revision {}", revision.id);
+    let model = translation::ActiveModel {
+        id: NotSet,
+        key: Set(revision.key),
+        language: Set(revision.language),
+        value: Set(revision.value),
+        status: Set(TranslationStatus::Approved),
+        score: Set(0),
+        source_changed: Set(source_changed),
+        review_comment: Set(Some(comment.clone())),
+        reviewed_at: Set(Some(Utc::now())),
+        translator_id: Set(translator_id),
+        reviewer_id: Set(Some(user_id)),
+        ..Default::default()
+    };
+    let translation = model.insert(&txn).await?;
+    record_revision(
+        &txn,
+        &translation,
+        RevisionAction::Reverted,
+        live_value,
+        Some(user_id),
+        Some(comment),
+    )
+    .await?;
+
+    txn.commit().await?;
+    invalidate_memory(&translation.language);
+    invalidate_entry_stats(db, [translation.key.clone()]).await;
+    Ok(translation)
+}
+
+/// Compares the current source value of the entry with the one the revision was made
+/// against, which is kept by the first source change after the revision.
+async fn source_changed_since<C: ConnectionTrait>(
+    db: &C,
+    revision: &translation_revision::Model,
+) -> Result<bool, DbErr> {
+    let entry = text_entry::Entity::find_by_id(revision.key.clone())
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotFound("Text entry not found".to_string()))?;
+    let source_at_revision = text_entry_revision::Entity::find()
+        .filter(text_entry_revision::Column::Key.eq(&revision.key))
+        .filter(text_entry_revision::Column::CreatedAt.gt(revision.created_at))
+        .order_by_asc(text_entry_revision::Column::Id)
+        .one(db)
+        .await?;
+
+    Ok(source_at_revision.is_some_and(|source| source.value != entry.value))
+}
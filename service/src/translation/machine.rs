@@ -0,0 +1,534 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use entity::entry::text_entry;
+use futures_util::future::join_all;
+use lazy_static::lazy_static;
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+
+use crate::minecraft::search::TextEntry;
+
+lazy_static! {
+    static ref MACHINE_CACHE: Mutex<HashMap<CacheKey, (Instant, String)>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Engine, target language and source text.
+type CacheKey = (String, String, String);
+
+/// Engines are billed per character, their output for the same text rarely changes.
+const MACHINE_CACHE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const MAX_CACHED_TRANSLATIONS: usize = 50_000;
+/// Texts sent to an engine in one request.
+const MAX_BATCH_SIZE: usize = 50;
+/// Language of the source values of text entries.
+pub const SOURCE_LANGUAGE: &str = "en_us";
+
+/// Where a translation comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslationOrigin {
+    /// Made by a translator and approved.
+    Human,
+    /// Made by a machine translation engine, nobody reviewed it.
+    Machine,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MachineSuggestion {
+    /// Name of the engine, e.g. `deepl`.
+    pub engine: String,
+    pub language: String,
+    pub value: String,
+    /// Always `machine`, machine suggestions must be reviewed like any other.
+    pub origin: TranslationOrigin,
+    /// Whether the suggestion was served from the cache instead of the engine.
+    pub cached: bool,
+}
+
+/// An engine translating texts between languages, given as Minecraft language codes.
+#[async_trait]
+pub trait MachineTranslator: Send + Sync {
+    /// Name used to select the engine, e.g. `deepl`.
+    fn name(&self) -> &'static str;
+
+    /// Translates the texts, returning the translations in the same order.
+    async fn translate(
+        &self,
+        texts: &[String],
+        source_language: &str,
+        target_language: &str,
+    ) -> anyhow::Result<Vec<String>>;
+}
+
+#[derive(Debug, Clone)]
+pub struct MachineTranslatorConfig {
+    pub api_key: Option<String>,
+    /// Base URL of the engine API, configurable to run against a local stand-in.
+    pub base_url: String,
+    /// Model to use, only for engines serving several models.
+    pub model: Option<String>,
+    /// Timeout of each request to the engine.
+    pub timeout: Duration,
+}
+
+impl MachineTranslatorConfig {
+    fn api_key(&self) -> &str {
+        self.api_key.as_deref().unwrap_or_default()
+    }
+
+    fn client(&self) -> reqwest::Client {
+        reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .expect("Failed to build HTTP client")
+    }
+}
+
+/// Splits a Minecraft language code into the language and the region, e.g. `zh_tw`.
+fn split_language(code: &str) -> (&str, Option<&str>) {
+    match code.split_once('_') {
+        Some((language, region)) => (language, Some(region)),
+        None => (code, None),
+    }
+}
+
+/// Whether the Chinese variant of the region uses traditional characters.
+fn is_traditional_chinese(region: Option<&str>) -> bool {
+    matches!(region, Some("tw" | "hk" | "mo"))
+}
+
+fn ensure_count(translations: &[String], texts: &[String]) -> anyhow::Result<()> {
+    if translations.len() != texts.len() {
+        bail!(
+            "Expected {} translations, got {}",
+            texts.len(),
+            translations.len()
+        );
+    }
+    Ok(())
+}
+
+pub struct DeepL {
+    config: MachineTranslatorConfig,
+    client: reqwest::Client,
+}
+
+impl DeepL {
+    pub fn new(config: MachineTranslatorConfig) -> Self {
+        Self {
+            client: config.client(),
+            config,
+        }
+    }
+
+    fn target_language(code: &str) -> String {
+        match split_language(code) {
+            ("en", Some("gb" | "au" | "nz")) => "EN-GB".to_string(),
+            ("en", _) => "EN-US".to_string(),
+            ("pt", Some("br")) => "PT-BR".to_string(),
+            ("pt", _) => "PT-PT".to_string(),
+            ("zh", region) if is_traditional_chinese(region) => "ZH-HANT".to_string(),
+            ("zh", _) => "ZH-HANS".to_string(),
+            (language, _) => language.to_uppercase(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepLResponse {
+    translations: Vec<DeepLTranslation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepLTranslation {
+    text: String,
+}
+
+#[async_trait]
+impl MachineTranslator for DeepL {
+    fn name(&self) -> &'static str {
+        "deepl"
+    }
+
+    async fn translate(
+        &self,
+        texts: &[String],
+        source_language: &str,
+        target_language: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let response = self
+            .client
+            .post(format!("{}/v2/translate", self.config.base_url))
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("DeepL-Auth-Key {}", self.config.api_key()),
+            )
+            .json(&json!({
+                "text": texts,
+                "source_lang": split_language(source_language).0.to_uppercase(),
+                "target_lang": Self::target_language(target_language),
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<DeepLResponse>()
+            .await
+            .context("Failed to parse DeepL response")?;
+
+        let translations: Vec<String> = response
+            .translations
+            .into_iter()
+            .map(|translation| translation.text)
+            .collect();
+        ensure_count(&translations, texts)?;
+        Ok(translations)
+    }
+}
+
+/// Google Cloud Translation (basic edition).
+pub struct GoogleTranslate {
+    config: MachineTranslatorConfig,
+    client: reqwest::Client,
+}
+
+impl GoogleTranslate {
+    pub fn new(config: MachineTranslatorConfig) -> Self {
+        Self {
+            client: config.client(),
+            config,
+        }
+    }
+
+    fn language(code: &str) -> String {
+        match split_language(code) {
+            ("zh", region) if is_traditional_chinese(region) => "zh-TW".to_string(),
+            ("zh", _) => "zh-CN".to_string(),
+            ("pt", Some("pt")) => "pt-PT".to_string(),
+            (language, _) => language.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleResponse {
+    data: GoogleData,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleData {
+    translations: Vec<GoogleTranslation>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleTranslation {
+    translated_text: String,
+}
+
+#[async_trait]
+impl MachineTranslator for GoogleTranslate {
+    fn name(&self) -> &'static str {
+        "google"
+    }
+
+    async fn translate(
+        &self,
+        texts: &[String],
+        source_language: &str,
+        target_language: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let response = self
+            .client
+            .post(format!("{}/language/translate/v2", self.config.base_url))
+            .query(&[("key", self.config.api_key())])
+            .json(&json!({
+                "q": texts,
+                "source": Self::language(source_language),
+                "target": Self::language(target_language),
+                "format": "text",
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<GoogleResponse>()
+            .await
+            .context("Failed to parse Google Translate response")?;
+
+        let translations: Vec<String> = response
+            .data
+            .translations
+            .into_iter()
+            .map(|translation| translation.translated_text)
+            .collect();
+        ensure_count(&translations, texts)?;
+        Ok(translations)
+    }
+}
+
+/// A chat completions endpoint compatible with the OpenAI API, e.g. a local LLM server.
+pub struct OpenAiCompatible {
+    config: MachineTranslatorConfig,
+    client: reqwest::Client,
+}
+
+impl OpenAiCompatible {
+    pub fn new(config: MachineTranslatorConfig) -> Self {
+        Self {
+            client: config.client(),
+            config,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+#[async_trait]
+impl MachineTranslator for OpenAiCompatible {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    async fn translate(
+        &self,
+        texts: &[String],
+        source_language: &str,
+        target_language: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let instructions = format!(
+            "You translate strings of Minecraft mods from the Minecraft language `{}` to the \
+             Minecraft language `{}`. Keep placeholders such as %s, %1$d or {{0}} and formatting \
+             codes such as §a unchanged. The user gives a JSON array of strings, reply with only \
+             a JSON array of their translations in the same order.",
+            source_language, target_language
+        );
+        let mut request = self
+            .client
+            .post(format!("{}/chat/completions", self.config.base_url))
+            .json(&json!({
+                "model": self.config.model.as_deref().unwrap_or("gpt-4o-mini"),
+                "temperature": 0,
+                "messages": [
+                    { "role": "system", "content": instructions },
+                    { "role": "user", "content": serde_json::to_string(texts)? },
+                ],
+            }));
+        // Local servers usually do not need a key.
+        if let Some(api_key) = &self.config.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<ChatResponse>()
+            .await
+            .context("Failed to parse chat completion response")?;
+        let content = response
+            .choices
+            .into_iter()
+            .next()
+            .context("Chat completion has no choices")?
+            .message
+            .content;
+        // Models like to wrap JSON in a code block.
+        let content = content
+            .trim()
+            .trim_start_matches("```json")
+            .trim_start_matches("```")
+            .trim_end_matches("```");
+        let translations: Vec<String> =
+            serde_json::from_str(content.trim()).context("Chat completion is not a JSON array")?;
+        ensure_count(&translations, texts)?;
+        Ok(translations)
+    }
+}
+
+pub struct LibreTranslate {
+    config: MachineTranslatorConfig,
+    client: reqwest::Client,
+}
+
+impl LibreTranslate {
+    pub fn new(config: MachineTranslatorConfig) -> Self {
+        Self {
+            client: config.client(),
+            config,
+        }
+    }
+
+    fn language(code: &str) -> &str {
+        match split_language(code) {
+            ("zh", region) if is_traditional_chinese(region) => "zt",
+            (language, _) => language,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LibreTranslateResponse {
+    translated_text: Vec<String>,
+}
+
+#[async_trait]
+impl MachineTranslator for LibreTranslate {
+    fn name(&self) -> &'static str {
+        "libretranslate"
+    }
+
+    async fn translate(
+        &self,
+        texts: &[String],
+        source_language: &str,
+        target_language: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let response = self
+            .client
+            .post(format!("{}/translate", self.config.base_url))
+            .json(&json!({
+                "q": texts,
+                "source": Self::language(source_language),
+                "target": Self::language(target_language),
+                "format": "text",
+                "api_key": self.config.api_key,
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<LibreTranslateResponse>()
+            .await
+            .context("Failed to parse LibreTranslate response")?;
+
+        ensure_count(&response.translated_text, texts)?;
+        Ok(response.translated_text)
+    }
+}
+
+/// Translates the texts from [`SOURCE_LANGUAGE`], the translations are cached so that
+/// the engine is only asked for texts it has not translated recently.
+pub async fn machine_translate(
+    translator: &dyn MachineTranslator,
+    texts: &[String],
+    language: &str,
+) -> anyhow::Result<Vec<MachineSuggestion>> {
+    let cache_key = |text: &String| {
+        (
+            translator.name().to_string(),
+            language.to_string(),
+            text.clone(),
+        )
+    };
+    let mut values: Vec<Option<(String, bool)>> = {
+        let cache = MACHINE_CACHE.lock().unwrap();
+        texts
+            .iter()
+            .map(|text| match cache.get(&cache_key(text)) {
+                Some((cached_at, value)) if cached_at.elapsed() < MACHINE_CACHE_TTL => {
+                    Some((value.clone(), true))
+                }
+                _ => None,
+            })
+            .collect()
+    };
+
+    let missing: Vec<usize> = (0..texts.len()).filter(|i| values[*i].is_none()).collect();
+    for chunk in missing.chunks(MAX_BATCH_SIZE) {
+        let batch: Vec<String> = chunk.iter().map(|i| texts[*i].clone()).collect();
+        let translations = translator
+            .translate(&batch, SOURCE_LANGUAGE, language)
+            .await?;
+
+        let mut cache = MACHINE_CACHE.lock().unwrap();
+        if cache.len() + translations.len() > MAX_CACHED_TRANSLATIONS {
+            cache.retain(|_, (cached_at, _)| cached_at.elapsed() < MACHINE_CACHE_TTL);
+            if cache.len() + translations.len() > MAX_CACHED_TRANSLATIONS {
+                cache.clear();
+            }
+        }
+        for (i, translation) in chunk.iter().zip(translations) {
+            cache.insert(cache_key(&texts[*i]), (Instant::now(), translation.clone()));
+            values[*i] = Some((translation, false));
+        }
+    }
+
+    Ok(values
+        .into_iter()
+        .flatten()
+        .map(|(value, cached)| MachineSuggestion {
+            engine: translator.name().to_string(),
+            language: language.to_string(),
+            value,
+            origin: TranslationOrigin::Machine,
+            cached,
+        })
+        .collect())
+}
+
+/// Machine translates the source value of the entry with each engine at once, returns
+/// the result of each engine in the same order, or `None` if the entry does not exist.
+pub async fn machine_translate_entry(
+    db: &DatabaseConnection,
+    translators: &[&dyn MachineTranslator],
+    key: &str,
+    language: &str,
+) -> Result<Option<Vec<anyhow::Result<MachineSuggestion>>>, DbErr> {
+    let Some(entry) = text_entry::Entity::find_by_id(key.to_string())
+        .one(db)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let texts = [entry.value];
+    let results = join_all(translators.iter().map(|translator| async {
+        machine_translate(*translator, &texts, language)
+            .await?
+            .pop()
+            .context("Machine translation returned nothing")
+    }))
+    .await;
+    Ok(Some(results))
+}
+
+/// Fills the previews of the entries without an approved translation with machine translations.
+pub async fn prefill_machine_previews(
+    translator: &dyn MachineTranslator,
+    entries: &mut [TextEntry],
+    language: &str,
+) -> anyhow::Result<()> {
+    let mut missing: Vec<&mut TextEntry> = entries
+        .iter_mut()
+        .filter(|entry| entry.preview_translation.is_none())
+        .collect();
+    let texts: Vec<String> = missing.iter().map(|entry| entry.value.clone()).collect();
+    let suggestions = machine_translate(translator, &texts, language).await?;
+
+    for (entry, suggestion) in missing.iter_mut().zip(suggestions) {
+        entry.preview_translation = Some(suggestion.value);
+        entry.preview_origin = Some(suggestion.origin);
+    }
+    Ok(())
+}
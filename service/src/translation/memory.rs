@@ -0,0 +1,338 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use entity::entry::{
+    text_entry,
+    translation::{self, TranslationStatus},
+};
+use lazy_static::lazy_static;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::review::pick_live_translations;
+
+lazy_static! {
+    static ref MEMORIES: Mutex<HashMap<String, Arc<LanguageMemory>>> = Mutex::new(HashMap::new());
+}
+
+/// Memories are rebuilt after approvals made through this crate, expiring them as
+/// well bounds how stale they get after changes made elsewhere.
+const MEMORY_TTL: Duration = Duration::from_secs(30 * 60);
+/// Matches less similar than this are not worth suggesting.
+const MIN_SIMILARITY: f32 = 0.6;
+/// Units sharing the most trigrams with the source are scored precisely.
+const MAX_CANDIDATES: usize = 50;
+/// Longer sources are not worth comparing, nobody reuses whole paragraphs.
+pub const MAX_SOURCE_LENGTH: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MemoryMatch {
+    /// Key of the entry the translation was made for.
+    pub key: String,
+    pub source: String,
+    pub translation: String,
+    /// From 0 to 1, 1 being an identical source.
+    pub similarity: f32,
+}
+
+/// The live translations of a language with a trigram index of their sources.
+struct LanguageMemory {
+    built_at: Instant,
+    units: Vec<MemoryUnit>,
+    index: HashMap<String, Vec<usize>>,
+}
+
+struct MemoryUnit {
+    key: String,
+    source: String,
+    translation: String,
+    trigram_count: usize,
+}
+
+impl LanguageMemory {
+    fn new(pairs: Vec<(String, String, String)>) -> Self {
+        let mut units = Vec::with_capacity(pairs.len());
+        let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+        // Many mods share the same strings, they are suggested once.
+        let mut seen = HashSet::new();
+
+        for (key, source, translation) in pairs {
+            if !seen.insert((source.clone(), translation.clone())) {
+                continue;
+            }
+
+            let trigrams = trigrams(&source);
+            for trigram in &trigrams {
+                index.entry(trigram.clone()).or_default().push(units.len());
+            }
+            units.push(MemoryUnit {
+                key,
+                source,
+                translation,
+                trigram_count: trigrams.len(),
+            });
+        }
+
+        Self {
+            built_at: Instant::now(),
+            units,
+            index,
+        }
+    }
+
+    fn find_matches(
+        &self,
+        source: &str,
+        exclude_key: Option<&str>,
+        limit: usize,
+    ) -> Vec<MemoryMatch> {
+        let trigrams = trigrams(source);
+        let mut shared: HashMap<usize, usize> = HashMap::new();
+        for trigram in &trigrams {
+            for unit in self.index.get(trigram).into_iter().flatten() {
+                *shared.entry(*unit).or_default() += 1;
+            }
+        }
+
+        // Dice coefficient of the trigram sets, cheap enough to rank every unit.
+        let mut candidates: Vec<(usize, f32)> = shared
+            .into_iter()
+            .filter(|(unit, _)| exclude_key != Some(self.units[*unit].key.as_str()))
+            .map(|(unit, count)| {
+                let total = trigrams.len() + self.units[unit].trigram_count;
+                (unit, 2.0 * count as f32 / total as f32)
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        candidates.truncate(MAX_CANDIDATES);
+
+        let mut matches: Vec<MemoryMatch> = candidates
+            .into_iter()
+            .filter_map(|(unit, _)| {
+                let unit = &self.units[unit];
+                let similarity = similarity(source, &unit.source)?;
+                Some(MemoryMatch {
+                    key: unit.key.clone(),
+                    source: unit.source.clone(),
+                    translation: unit.translation.clone(),
+                    similarity,
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            b.similarity
+                .partial_cmp(&a.similarity)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.key.cmp(&b.key))
+        });
+        matches.truncate(limit);
+
+        matches
+    }
+}
+
+fn trigrams(value: &str) -> HashSet<String> {
+    let chars: Vec<char> = format!("  {} ", value.to_lowercase()).chars().collect();
+    chars
+        .windows(3)
+        .map(|window| window.iter().collect())
+        .collect()
+}
+
+/// Levenshtein similarity of the strings, ignoring case. Returns `None` if it is
+/// below [`MIN_SIMILARITY`], which the lengths alone often tell.
+fn similarity(a: &str, b: &str) -> Option<f32> {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return Some(1.0);
+    }
+    let to_similarity = |distance: usize| 1.0 - distance as f32 / longest as f32;
+    if to_similarity(a.len().abs_diff(b.len())) < MIN_SIMILARITY {
+        return None;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_char) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    let similarity = to_similarity(previous[b.len()]);
+    (similarity >= MIN_SIMILARITY).then_some(similarity)
+}
+
+async fn load_memory(
+    db: &DatabaseConnection,
+    language: &str,
+) -> Result<Arc<LanguageMemory>, DbErr> {
+    if let Some(memory) = MEMORIES.lock().unwrap().get(language) {
+        if memory.built_at.elapsed() < MEMORY_TTL {
+            return Ok(memory.clone());
+        }
+    }
+
+    let translations = translation::Entity::find()
+        .find_also_related(text_entry::Entity)
+        .filter(translation::Column::Language.eq(language))
+        .filter(translation::Column::Status.eq(TranslationStatus::Approved))
+        .all(db)
+        .await?;
+    let translations = translations
+        .into_iter()
+        .filter_map(|(translation, entry)| Some((translation, entry?)));
+    let mut pairs: Vec<_> = pick_live_translations(translations, |(translation, _)| translation)
+        .into_iter()
+        .map(|(key, (translation, entry))| (key, entry.value, translation.value))
+        .collect();
+    pairs.sort();
+
+    let memory = Arc::new(LanguageMemory::new(pairs));
+    // Languages without translations are not kept, any code can be asked for.
+    if !memory.units.is_empty() {
+        MEMORIES
+            .lock()
+            .unwrap()
+            .insert(language.to_string(), memory.clone());
+    }
+    Ok(memory)
+}
+
+/// Finds the approved translations in the language whose source is most similar to
+/// the given one, the best first. The translation of `exclude_key` is left out, so the
+/// entry being translated does not match itself. Sources longer than
+/// [`MAX_SOURCE_LENGTH`] have no matches.
+pub async fn find_memory_matches(
+    db: &DatabaseConnection,
+    source: &str,
+    language: &str,
+    exclude_key: Option<&str>,
+    limit: usize,
+) -> Result<Vec<MemoryMatch>, DbErr> {
+    if source.chars().count() > MAX_SOURCE_LENGTH {
+        return Ok(Vec::new());
+    }
+
+    let memory = load_memory(db, language).await?;
+    Ok(memory.find_matches(source, exclude_key, limit))
+}
+
+/// Drops the memory of the language, e.g. after translations were approved.
+pub fn invalidate_memory(language: &str) {
+    MEMORIES.lock().unwrap().remove(language);
+}
+
+/// Drops the memories of all languages, e.g. after source values changed.
+pub fn clear_memories() {
+    MEMORIES.lock().unwrap().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(key: &str, source: &str, translation: &str) -> (String, String, String) {
+        (key.to_string(), source.to_string(), translation.to_string())
+    }
+
+    fn keys(matches: &[MemoryMatch]) -> Vec<&str> {
+        matches.iter().map(|x| x.key.as_str()).collect()
+    }
+
+    #[test]
+    fn trigrams_are_padded_and_lowercase() {
+        let expected: HashSet<String> = ["  a", " ab", "ab "]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(trigrams("AB"), expected);
+        assert_eq!(trigrams("ab"), trigrams("Ab"));
+    }
+
+    #[test]
+    fn similarity_ignores_case() {
+        assert_eq!(similarity("Iron Ingot", "iron ingot"), Some(1.0));
+        assert_eq!(similarity("", ""), Some(1.0));
+        assert_eq!(
+            similarity("Iron Ingot", "Iron Ingots"),
+            Some(1.0 - 1.0 / 11.0)
+        );
+    }
+
+    #[test]
+    fn similarity_below_the_minimum_is_none() {
+        assert_eq!(similarity("Iron", "Iron Ingot Block"), None);
+        assert_eq!(similarity("Iron Ingot", "Gold Nugget"), None);
+    }
+
+    #[test]
+    fn matches_exclude_the_own_key() {
+        let memory = LanguageMemory::new(vec![
+            pair("item.a.iron_ingot", "Iron Ingot", "鐵錠"),
+            pair("item.b.iron_ingots", "Iron Ingots", "鐵錠們"),
+        ]);
+
+        let matches = memory.find_matches("Iron Ingot", Some("item.a.iron_ingot"), 10);
+        assert_eq!(keys(&matches), ["item.b.iron_ingots"]);
+    }
+
+    #[test]
+    fn matches_below_the_minimum_are_dropped() {
+        let memory = LanguageMemory::new(vec![
+            pair("item.a.iron_ingot", "Iron Ingot", "鐵錠"),
+            pair("item.a.iron_block", "Block of Iron", "鐵方塊"),
+        ]);
+
+        let matches = memory.find_matches("Iron Ingot", None, 10);
+        assert_eq!(keys(&matches), ["item.a.iron_ingot"]);
+        assert!(matches.iter().all(|x| x.similarity >= MIN_SIMILARITY));
+    }
+
+    #[test]
+    fn shared_strings_are_suggested_once() {
+        let memory = LanguageMemory::new(vec![
+            pair("item.a.copper", "Copper Ingot", "銅錠"),
+            pair("item.b.copper", "Copper Ingot", "銅錠"),
+            pair("item.c.copper", "Copper Ingot", "紅銅錠"),
+        ]);
+
+        let matches = memory.find_matches("Copper Ingot", None, 10);
+        assert_eq!(keys(&matches), ["item.a.copper", "item.c.copper"]);
+    }
+
+    #[test]
+    fn matches_are_ordered_by_similarity_then_key() {
+        let memory = LanguageMemory::new(vec![
+            pair("item.a.ingots", "Gold Ingots", "金錠們"),
+            pair("item.b.ingot", "Gold Ingot", "金錠"),
+            pair("item.c.ingot", "gold ingot", "金錠"),
+            pair("item.d.bar", "Gold Ingot Bar", "金錠條"),
+        ]);
+
+        let matches = memory.find_matches("Gold Ingot", None, 10);
+        assert_eq!(
+            keys(&matches),
+            [
+                "item.b.ingot",
+                "item.c.ingot",
+                "item.a.ingots",
+                "item.d.bar"
+            ]
+        );
+        assert_eq!(matches[0].similarity, 1.0);
+
+        let matches = memory.find_matches("Gold Ingot", None, 2);
+        assert_eq!(keys(&matches), ["item.b.ingot", "item.c.ingot"]);
+    }
+}
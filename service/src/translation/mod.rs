@@ -0,0 +1,8 @@
+pub mod edit;
+pub mod history;
+pub mod machine;
+pub mod memory;
+pub mod outdated;
+pub mod review;
+pub mod stats;
+pub mod vote;
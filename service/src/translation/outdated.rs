@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use entity::entry::{
+    text_entry, text_entry_revision,
+    translation::{self, TranslationStatus},
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::review::pick_live_translations;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OutdatedTranslation {
+    #[schema(value_type = Translation)]
+    pub translation: translation::Model,
+    /// The current source value of the entry.
+    pub source_value: String,
+    /// The source value before the latest change.
+    pub previous_source_value: Option<String>,
+}
+
+/// Lists the live translations of the mod in the language whose source value changed
+/// after they were made, ordered by key.
+pub async fn find_outdated_translations(
+    db: &DatabaseConnection,
+    mod_id: i32,
+    language: &str,
+) -> Result<Vec<OutdatedTranslation>, DbErr> {
+    let approved = translation::Entity::find()
+        .find_also_related(text_entry::Entity)
+        .filter(text_entry::Column::ModId.eq(mod_id))
+        .filter(translation::Column::Language.eq(language))
+        .filter(translation::Column::Status.eq(TranslationStatus::Approved))
+        .all(db)
+        .await?;
+    let approved = approved
+        .into_iter()
+        .filter_map(|(translation, entry)| Some((translation, entry?.value)));
+
+    let mut outdated: Vec<_> = pick_live_translations(approved, |(translation, _)| translation)
+        .into_values()
+        .filter(|(translation, _)| translation.source_changed)
+        .collect();
+    outdated.sort_by(|(a, _), (b, _)| a.key.cmp(&b.key));
+
+    let keys = outdated
+        .iter()
+        .map(|(translation, _)| translation.key.clone());
+    let previous_values: HashMap<String, String> = text_entry_revision::Entity::find()
+        .filter(text_entry_revision::Column::Key.is_in(keys))
+        .order_by_asc(text_entry_revision::Column::Id)
+        .all(db)
+        .await?
+        .into_iter()
+        // Later revisions overwrite earlier ones.
+        .map(|revision| (revision.key, revision.value))
+        .collect();
+
+    Ok(outdated
+        .into_iter()
+        .map(|(translation, source_value)| OutdatedTranslation {
+            previous_source_value: previous_values.get(&translation.key).cloned(),
+            translation,
+            source_value,
+        })
+        .collect())
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use entity::entry::{
+    translation::{self, TranslationStatus},
+    translation_revision::RevisionAction,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    IntoActiveModel, QueryFilter, Set, TransactionTrait,
+};
+
+use super::{history::record_revision, memory::invalidate_memory, stats::invalidate_entry_stats};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewDecision {
+    Approve,
+    Reject,
+}
+
+/// Approves or rejects the translations with the same comment, either all of them are
+/// reviewed or none. Callers are responsible for checking that they are submitted.
+///
+/// There is no reviewer if a suggestion is approved automatically by votes.
+pub async fn review_translations(
+    db: &DatabaseConnection,
+    translations: Vec<translation::Model>,
+    reviewer_id: Option<i32>,
+    decision: ReviewDecision,
+    comment: Option<String>,
+) -> Result<Vec<translation::Model>, DbErr> {
+    let (status, action) = match decision {
+        ReviewDecision::Approve => (TranslationStatus::Approved, RevisionAction::Approved),
+        ReviewDecision::Reject => (TranslationStatus::Rejected, RevisionAction::Rejected),
+    };
+    let now = Utc::now();
+
+    let txn = db.begin().await?;
+    let mut reviewed = Vec::with_capacity(translations.len());
+    for translation in translations {
+        let previous_value = match decision {
+            ReviewDecision::Approve => {
+                let key = translation.key.clone();
+                find_approved_translations(&txn, [key.clone()], &translation.language)
+                    .await?
+                    .remove(&key)
+            }
+            ReviewDecision::Reject => None,
+        };
+
+        let mut model = translation.into_active_model();
+        model.status = Set(status.clone());
+        model.review_comment = Set(comment.clone());
+        model.reviewed_at = Set(Some(now));
+        model.reviewer_id = Set(reviewer_id);
+        let translation = model.update(&txn).await?;
+        record_revision(
+            &txn,
+            &translation,
+            action.clone(),
+            previous_value,
+            reviewer_id,
+            comment.clone(),
+        )
+        .await?;
+        reviewed.push(translation);
+    }
+    txn.commit().await?;
+    if decision == ReviewDecision::Approve {
+        for translation in &reviewed {
+            invalidate_memory(&translation.language);
+        }
+    }
+    invalidate_entry_stats(
+        db,
+        reviewed.iter().map(|translation| translation.key.clone()),
+    )
+    .await;
+
+    Ok(reviewed)
+}
+
+/// Returns the live translation of each key in the language, used for previews and exports.
+pub async fn find_approved_translations<C: ConnectionTrait>(
+    db: &C,
+    keys: impl IntoIterator<Item = String>,
+    language: &str,
+) -> Result<HashMap<String, String>, DbErr> {
+    let translations = translation::Entity::find()
+        .filter(translation::Column::Key.is_in(keys))
+        .filter(translation::Column::Language.eq(language))
+        .filter(translation::Column::Status.eq(TranslationStatus::Approved))
+        .all(db)
+        .await?;
+
+    Ok(
+        pick_live_translations(translations, |translation| translation)
+            .into_iter()
+            .map(|(key, translation)| (key, translation.value))
+            .collect(),
+    )
+}
+
+/// Picks the live translation of each key among translations of a single language.
+/// When several translations of a key were approved, the most recently approved one is
+/// live, translations that are not approved are skipped.
+pub(crate) fn pick_live_translations<T>(
+    items: impl IntoIterator<Item = T>,
+    translation: impl Fn(&T) -> &translation::Model,
+) -> HashMap<String, T> {
+    let mut live: HashMap<String, T> = HashMap::new();
+    for item in items {
+        let model = translation(&item);
+        if model.status != TranslationStatus::Approved {
+            continue;
+        }
+
+        let key = model.key.clone();
+        let is_newer = live.get(&key).is_none_or(|current| {
+            let current = translation(current);
+            (current.reviewed_at, current.id) < (model.reviewed_at, model.id)
+        });
+        if is_newer {
+            live.insert(key, item);
+        }
+    }
+    live
+}
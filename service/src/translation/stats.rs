@@ -0,0 +1,254 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use entity::{
+    account::user,
+    entry::{
+        text_entry,
+        translation::{self, TranslationStatus},
+    },
+};
+use lazy_static::lazy_static;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::review::pick_live_translations;
+
+lazy_static! {
+    static ref STATS_CACHE: Mutex<StatsCache> = Mutex::new(StatsCache::default());
+}
+
+/// Cached mod stats are invalidated on every change made through this crate,
+/// expiring them as well bounds how stale they get after changes made elsewhere.
+const MOD_STATS_TTL: Duration = Duration::from_secs(10 * 60);
+/// The global stats cover every entry, so they are only recomputed after they expire.
+const GLOBAL_STATS_TTL: Duration = Duration::from_secs(5 * 60);
+const TOP_TRANSLATORS: u64 = 10;
+
+#[derive(Default)]
+struct StatsCache {
+    mods: HashMap<i32, (Instant, Vec<TranslationStats>)>,
+    global: Option<(Instant, GlobalStats)>,
+}
+
+/// Translation progress of a set of entries in a language.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TranslationStats {
+    pub language: String,
+    pub total_entries: u64,
+    /// Entries with a submitted or approved translation.
+    pub translated_entries: u64,
+    /// Entries with a live translation.
+    pub approved_entries: u64,
+    /// Live translations whose source value changed after they were made.
+    pub outdated_entries: u64,
+    /// Words of the source values without an up-to-date live translation.
+    pub words_to_go: u64,
+    /// Characters of the source values without an up-to-date live translation.
+    pub characters_to_go: u64,
+    /// Share of the entries with an up-to-date live translation, from 0 to 1.
+    pub progress: f32,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TranslatorStats {
+    pub user_id: i32,
+    pub username: String,
+    pub approved_translations: u64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GlobalStats {
+    pub total_entries: u64,
+    /// Languages with the most approved entries first.
+    pub languages: Vec<TranslationStats>,
+    /// Translators with the most approved translations first.
+    pub top_translators: Vec<TranslatorStats>,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// Returns the stats of the mod for each language it has translations in, ordered by language.
+pub async fn find_mod_stats(
+    db: &DatabaseConnection,
+    mod_id: i32,
+) -> Result<Vec<TranslationStats>, DbErr> {
+    if let Some((computed_at, stats)) = STATS_CACHE.lock().unwrap().mods.get(&mod_id) {
+        if computed_at.elapsed() < MOD_STATS_TTL {
+            return Ok(stats.clone());
+        }
+    }
+
+    let entries = text_entry::Entity::find()
+        .filter(text_entry::Column::ModId.eq(mod_id))
+        .all(db)
+        .await?;
+    let translations = translation::Entity::find()
+        .inner_join(text_entry::Entity)
+        .filter(text_entry::Column::ModId.eq(mod_id))
+        .filter(
+            translation::Column::Status
+                .is_in([TranslationStatus::Submitted, TranslationStatus::Approved]),
+        )
+        .all(db)
+        .await?;
+    let stats = tally(&entries, translations);
+
+    STATS_CACHE
+        .lock()
+        .unwrap()
+        .mods
+        .insert(mod_id, (Instant::now(), stats.clone()));
+    Ok(stats)
+}
+
+/// Returns the stats across all mods, with the languages and translators ranked.
+pub async fn find_global_stats(db: &DatabaseConnection) -> Result<GlobalStats, DbErr> {
+    if let Some((computed_at, stats)) = &STATS_CACHE.lock().unwrap().global {
+        if computed_at.elapsed() < GLOBAL_STATS_TTL {
+            return Ok(stats.clone());
+        }
+    }
+
+    let entries = text_entry::Entity::find().all(db).await?;
+    let translations = translation::Entity::find()
+        .filter(
+            translation::Column::Status
+                .is_in([TranslationStatus::Submitted, TranslationStatus::Approved]),
+        )
+        .all(db)
+        .await?;
+    let mut languages = tally(&entries, translations);
+    languages.sort_by_key(|stats| Reverse(stats.approved_entries));
+
+    let counts: Vec<(i32, i64)> = translation::Entity::find()
+        .select_only()
+        .column(translation::Column::TranslatorId)
+        .column_as(translation::Column::Id.count(), "count")
+        .filter(translation::Column::Status.eq(TranslationStatus::Approved))
+        .group_by(translation::Column::TranslatorId)
+        .order_by_desc(translation::Column::Id.count())
+        .limit(TOP_TRANSLATORS)
+        .into_tuple()
+        .all(db)
+        .await?;
+    let usernames: HashMap<i32, String> = user::Entity::find()
+        .filter(user::Column::Id.is_in(counts.iter().map(|(user_id, _)| *user_id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.username))
+        .collect();
+    let top_translators = counts
+        .into_iter()
+        .filter_map(|(user_id, count)| {
+            Some(TranslatorStats {
+                user_id,
+                username: usernames.get(&user_id)?.clone(),
+                approved_translations: count as u64,
+            })
+        })
+        .collect();
+
+    let stats = GlobalStats {
+        total_entries: entries.len() as u64,
+        languages,
+        top_translators,
+        computed_at: Utc::now(),
+    };
+    STATS_CACHE.lock().unwrap().global = Some((Instant::now(), stats.clone()));
+    Ok(stats)
+}
+
+/// Drops the cached stats of the mod, e.g. after its entries changed.
+pub fn invalidate_mod_stats(mod_id: i32) {
+    STATS_CACHE.lock().unwrap().mods.remove(&mod_id);
+}
+
+/// Drops the cached stats of the mods the entries belong to, e.g. after they were translated.
+pub async fn invalidate_entry_stats(
+    db: &DatabaseConnection,
+    keys: impl IntoIterator<Item = String>,
+) {
+    let mod_ids = text_entry::Entity::find()
+        .select_only()
+        .column(text_entry::Column::ModId)
+        .distinct()
+        .filter(text_entry::Column::Key.is_in(keys))
+        .into_tuple::<i32>()
+        .all(db)
+        .await;
+
+    let mut cache = STATS_CACHE.lock().unwrap();
+    match mod_ids {
+        Ok(mod_ids) => {
+            for mod_id in mod_ids {
+                cache.mods.remove(&mod_id);
+            }
+        }
+        // Not knowing which mods changed, none of them can be trusted.
+        Err(_) => cache.mods.clear(),
+    }
+}
+
+/// Counts the progress of the entries per language, the translations must be
+/// submitted or approved.
+fn tally(
+    entries: &[text_entry::Model],
+    translations: Vec<translation::Model>,
+) -> Vec<TranslationStats> {
+    let mut languages: BTreeMap<String, Vec<translation::Model>> = BTreeMap::new();
+    for translation in translations {
+        languages
+            .entry(translation.language.clone())
+            .or_default()
+            .push(translation);
+    }
+
+    languages
+        .into_iter()
+        .map(|(language, translations)| {
+            // The keys with a suggestion and whether the live translation of each key is outdated.
+            let translated: HashSet<&str> = translations
+                .iter()
+                .map(|translation| translation.key.as_str())
+                .collect();
+            let live: HashMap<String, bool> =
+                pick_live_translations(&translations, |translation| *translation)
+                    .into_iter()
+                    .map(|(key, translation)| (key, translation.source_changed))
+                    .collect();
+
+            let mut stats = TranslationStats {
+                language,
+                total_entries: entries.len() as u64,
+                translated_entries: translated.len() as u64,
+                approved_entries: live.len() as u64,
+                outdated_entries: live.values().filter(|outdated| **outdated).count() as u64,
+                words_to_go: 0,
+                characters_to_go: 0,
+                progress: 0.0,
+            };
+
+            for entry in entries {
+                if live.get(&entry.key) != Some(&false) {
+                    stats.words_to_go += entry.value.split_whitespace().count() as u64;
+                    stats.characters_to_go += entry.value.chars().count() as u64;
+                }
+            }
+            if stats.total_entries > 0 {
+                let up_to_date = stats.approved_entries - stats.outdated_entries;
+                stats.progress = up_to_date as f32 / stats.total_entries as f32;
+            }
+
+            stats
+        })
+        .collect()
+}
@@ -0,0 +1,89 @@
+use entity::entry::{
+    translation::{self, TranslationStatus},
+    translation_vote,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, IntoActiveModel,
+    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+
+/// Lists the suggestions for the entry in the language, the best first.
+/// Suggestions are submitted or approved translations, ranked by score,
+/// ties are won by the earlier suggestion.
+pub async fn list_suggestions(
+    db: &DatabaseConnection,
+    key: &str,
+    language: &str,
+) -> Result<Vec<translation::Model>, DbErr> {
+    translation::Entity::find()
+        .filter(translation::Column::Key.eq(key))
+        .filter(translation::Column::Language.eq(language))
+        .filter(
+            translation::Column::Status
+                .is_in([TranslationStatus::Submitted, TranslationStatus::Approved]),
+        )
+        .order_by_desc(translation::Column::Score)
+        .order_by_asc(translation::Column::CreatedAt)
+        .order_by_asc(translation::Column::Id)
+        .all(db)
+        .await
+}
+
+/// Returns the best submitted suggestion for the entry in the language, if any.
+pub async fn find_top_suggestion(
+    db: &DatabaseConnection,
+    key: &str,
+    language: &str,
+) -> Result<Option<translation::Model>, DbErr> {
+    translation::Entity::find()
+        .filter(translation::Column::Key.eq(key))
+        .filter(translation::Column::Language.eq(language))
+        .filter(translation::Column::Status.eq(TranslationStatus::Submitted))
+        .order_by_desc(translation::Column::Score)
+        .order_by_asc(translation::Column::CreatedAt)
+        .order_by_asc(translation::Column::Id)
+        .limit(1)
+        .one(db)
+        .await
+}
+
+/// Sets the vote of the user on the translation, replacing the previous vote of the user.
+/// A positive value is an upvote, a negative value a downvote and zero removes the vote.
+/// Returns the translation with its recomputed score.
+pub async fn cast_vote(
+    db: &DatabaseConnection,
+    translation: translation::Model,
+    user_id: i32,
+    value: i32,
+) -> Result<translation::Model, DbErr> {
+    let txn = db.begin().await?;
+
+    translation_vote::Entity::delete_many()
+        .filter(translation_vote::Column::TranslationId.eq(translation.id))
+        .filter(translation_vote::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+    if value != 0 {
+        let vote = translation_vote::ActiveModel {
+            translation_id: Set(translation.id),
+            user_id: Set(user_id),
+            value: Set(value.signum()),
+            ..Default::default()
+        };
+        vote.insert(&txn).await?;
+    }
+
+    let score: i32 = translation_vote::Entity::find()
+        .filter(translation_vote::Column::TranslationId.eq(translation.id))
+        .all(&txn)
+        .await?
+        .iter()
+        .map(|vote| vote.value)
+        .sum();
+    let mut model = translation.into_active_model();
+    model.score = Set(score);
+    let translation = model.update(&txn).await?;
+
+    txn.commit().await?;
+    Ok(translation)
+}
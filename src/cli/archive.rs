@@ -0,0 +1,238 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use api::config::ServerConfig;
+use entity::minecraft::{minecraft_mod, mod_loader::ModLoader};
+use service::{
+    archive::{
+        failure::{
+            clear_archive_failure, find_archive_failure, list_archive_failures,
+            record_archive_failure,
+        },
+        resource::{parse_loader, validate_resource_identifier, ArchiveProvider, ModDownloadInfo},
+        task::{archive_mod, ArchiveTaskStage},
+        upload::{archive_uploaded_mod, create_upload_path, inspect_mod_archive, CustomModInfo},
+    },
+    minecraft::version::to_semver,
+    sea_orm::DatabaseConnection,
+};
+
+use super::ProviderArg;
+
+/// A mod jar on the local file system with the release it belongs to.
+#[derive(Debug, Clone)]
+pub struct LocalJar {
+    path: PathBuf,
+    game_version: semver::Version,
+    loader: ModLoader,
+}
+
+/// Parses `<PATH>:<GAME_VERSION>:<LOADER>`, the path may contain colons.
+pub fn parse_local_jar(value: &str) -> Result<LocalJar, String> {
+    let mut parts = value.rsplitn(3, ':');
+    let (Some(loader), Some(game_version), Some(path)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err("expected <PATH>:<GAME_VERSION>:<LOADER>".to_string());
+    };
+
+    Ok(LocalJar {
+        path: PathBuf::from(path),
+        game_version: to_semver(game_version)
+            .map_err(|_| format!("invalid game version `{}`", game_version))?,
+        loader: parse_loader(loader).ok_or_else(|| format!("unsupported loader `{}`", loader))?,
+    })
+}
+
+impl From<ProviderArg> for ArchiveProvider {
+    fn from(provider: ProviderArg) -> Self {
+        match provider {
+            ProviderArg::Modrinth => ArchiveProvider::Modrinth,
+        }
+    }
+}
+
+pub async fn archive_from_provider(
+    db: &DatabaseConnection,
+    config: &ServerConfig,
+    provider: ProviderArg,
+    identifier: String,
+) -> anyhow::Result<()> {
+    let provider = ArchiveProvider::from(provider);
+    let valid = validate_resource_identifier(&provider, &identifier)
+        .await
+        .unwrap_or(false);
+    if !valid {
+        anyhow::bail!("Invalid resource identifier: {}", identifier);
+    }
+
+    let mc_mod = archive_and_record(db, config, &provider, identifier).await?;
+    print_archived(&mc_mod);
+    Ok(())
+}
+
+pub async fn archive_local_jars(
+    db: &DatabaseConnection,
+    jars: Vec<LocalJar>,
+    identifier: Option<String>,
+    name: Option<String>,
+    description: Option<String>,
+    page_url: Option<String>,
+) -> anyhow::Result<()> {
+    let mut namespaces = Vec::with_capacity(jars.len());
+    let mut downloads = Vec::with_capacity(jars.len());
+    for jar in jars {
+        let namespace = inspect_mod_archive(&jar.path)
+            .with_context(|| format!("{} is not a supported mod jar", jar.path.display()))?;
+        // The archived copies are removed once parsed, the given jars are kept.
+        let path = create_upload_path()?;
+        let size = std::fs::copy(&jar.path, &path)
+            .with_context(|| format!("Failed to copy {}", jar.path.display()))?;
+
+        namespaces.push(namespace);
+        downloads.push(ModDownloadInfo {
+            url: jar.path.display().to_string(),
+            size: size as usize,
+            loader: jar.loader,
+            game_version: jar.game_version,
+            path,
+        });
+    }
+    downloads.sort_by(|a, b| a.game_version.cmp(&b.game_version));
+
+    let identifier = identifier.unwrap_or_else(|| namespaces[0].clone());
+    let info = CustomModInfo {
+        name: name.unwrap_or_else(|| identifier.clone()),
+        identifier: identifier.clone(),
+        description,
+        page_url,
+    };
+
+    let provider = ArchiveProvider::Custom;
+    match archive_uploaded_mod(db, info, downloads, print_stage).await {
+        Ok((mc_mod, skipped_keys)) => {
+            clear_archive_failure(db, &provider, &identifier).await?;
+            if !skipped_keys.is_empty() {
+                eprintln!(
+                    "Skipped {} keys used by other mods: {}",
+                    skipped_keys.len(),
+                    skipped_keys.join(", ")
+                );
+            }
+            print_archived(&mc_mod);
+            Ok(())
+        }
+        Err(err) => {
+            record_archive_failure(db, &provider, &identifier, &err, None).await?;
+            Err(err)
+        }
+    }
+}
+
+pub async fn list_failures(db: &DatabaseConnection) -> anyhow::Result<()> {
+    let failures = list_archive_failures(db).await?;
+    if failures.is_empty() {
+        println!("No failed archive tasks");
+        return Ok(());
+    }
+
+    for failure in failures {
+        println!(
+            "{}\t{:?}\t{}\t{}\t{}\t{}",
+            failure.id,
+            failure.provider_type,
+            failure.identifier,
+            failure.failed_at.to_rfc3339(),
+            failure.requester.as_deref().unwrap_or("-"),
+            failure.error.replace('\n', " "),
+        );
+    }
+    Ok(())
+}
+
+/// Archives the mods again, only mods fetched from a provider can be retried as the
+/// files of uploaded and vanilla mods are not kept.
+pub async fn retry_failures(
+    db: &DatabaseConnection,
+    config: &ServerConfig,
+    ids: Vec<i32>,
+    all: bool,
+) -> anyhow::Result<()> {
+    let failures = if all {
+        list_archive_failures(db).await?
+    } else {
+        let mut failures = Vec::with_capacity(ids.len());
+        for id in ids {
+            let failure = find_archive_failure(db, id)
+                .await?
+                .with_context(|| format!("No failed task with ID {}", id))?;
+            failures.push(failure);
+        }
+        failures
+    };
+
+    let total = failures.len();
+    let mut failed = 0;
+    for failure in failures {
+        let provider = ArchiveProvider::from_mod_provider_type(&failure.provider_type);
+        if provider != ArchiveProvider::Modrinth {
+            eprintln!(
+                "Skipped {}: {:?} mods can't be fetched again",
+                failure.identifier, failure.provider_type
+            );
+            failed += 1;
+            continue;
+        }
+
+        eprintln!("Retrying {}", failure.identifier);
+        match archive_and_record(db, config, &provider, failure.identifier.clone()).await {
+            Ok(mc_mod) => print_archived(&mc_mod),
+            Err(err) => {
+                eprintln!("Failed to archive {}: {:#}", failure.identifier, err);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} tasks were not archived", failed, total);
+    }
+    Ok(())
+}
+
+/// Archives the mod, recording the failure or clearing the previous one.
+async fn archive_and_record(
+    db: &DatabaseConnection,
+    config: &ServerConfig,
+    provider: &ArchiveProvider,
+    identifier: String,
+) -> anyhow::Result<minecraft_mod::Model> {
+    let result = archive_mod(
+        db,
+        provider,
+        identifier.clone(),
+        &config.download_options(),
+        print_stage,
+    )
+    .await;
+
+    match result {
+        Ok(mc_mod) => {
+            clear_archive_failure(db, provider, &identifier).await?;
+            Ok(mc_mod)
+        }
+        Err(err) => {
+            record_archive_failure(db, provider, &identifier, &err, None).await?;
+            Err(err)
+        }
+    }
+}
+
+fn print_stage(stage: Option<ArchiveTaskStage>, _progress: f32) {
+    if let Some(stage) = stage {
+        eprintln!("{:?}...", stage);
+    }
+}
+
+fn print_archived(mc_mod: &minecraft_mod::Model) {
+    println!("Archived mod {} ({:?})", mc_mod.id, mc_mod.status);
+}
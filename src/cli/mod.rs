@@ -0,0 +1,215 @@
+//! Headless operations on the database, run without the HTTP server.
+
+mod archive;
+mod translation;
+mod user;
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use api::config::ServerConfig;
+use clap::{Parser, Subcommand, ValueEnum};
+use service::{
+    exchange::ExchangeFormat,
+    initialize_database,
+    sea_orm::{ConnectionTrait, Database, DatabaseConnection},
+};
+
+#[derive(Debug, Parser)]
+#[command(version, about = "RTranslator backend and administration commands")]
+pub struct Cli {
+    /// TOML configuration file, environment variables take precedence over its values.
+    #[arg(long, global = true, env = "CONFIG_FILE", value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Start the HTTP server, the default without a command.
+    Serve,
+    /// Create the missing database tables.
+    Migrate,
+    /// Archive a mod from its provider.
+    Archive {
+        #[arg(value_enum)]
+        provider: ProviderArg,
+        /// Project ID or slug of the mod.
+        identifier: String,
+    },
+    /// Archive local mod jars as a custom mod.
+    ArchiveJar {
+        /// A mod jar as `<PATH>:<GAME_VERSION>:<LOADER>`, can be repeated for
+        /// several releases of the mod.
+        #[arg(long = "jar", value_name = "JAR", required = true, value_parser = archive::parse_local_jar)]
+        jars: Vec<archive::LocalJar>,
+        /// Defaults to the namespace of the first jar.
+        #[arg(long)]
+        identifier: Option<String>,
+        /// Defaults to the identifier.
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        description: Option<String>,
+        #[arg(long)]
+        page_url: Option<String>,
+    },
+    /// Import translations of a mod from a file exported for CAT tools.
+    Import {
+        mod_id: i32,
+        language: String,
+        file: PathBuf,
+        /// Defaults to the format matching the file extension.
+        #[arg(long, value_enum)]
+        format: Option<FormatArg>,
+        /// Username of the user the translations are saved for.
+        #[arg(long)]
+        translator: String,
+        /// Submit the translations for review instead of saving drafts.
+        #[arg(long)]
+        submit: bool,
+        /// Only validate the file and report what would be imported.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Export the entries of a mod with their approved translations.
+    Export {
+        mod_id: i32,
+        language: String,
+        #[arg(long, value_enum, default_value_t = FormatArg::Xliff)]
+        format: FormatArg,
+        /// Defaults to the standard output.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Rebuild the database indexes and the statistics used to plan searches.
+    Reindex,
+    /// List or retry failed archive tasks.
+    #[command(subcommand)]
+    Tasks(TasksCommand),
+    /// Create an administrator, or make an existing user an administrator.
+    CreateAdmin {
+        username: String,
+        /// Defaults to the username.
+        #[arg(long)]
+        display_name: Option<String>,
+        /// Read from the standard input if not set and the user does not exist.
+        #[arg(long, env = "ADMIN_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TasksCommand {
+    /// List the mods that failed to be archived.
+    List,
+    /// Archive the mods of failed tasks again.
+    Retry {
+        /// IDs of the failures as listed.
+        #[arg(required_unless_present = "all")]
+        ids: Vec<i32>,
+        /// Retry every failed task.
+        #[arg(long, conflicts_with = "ids")]
+        all: bool,
+    },
+}
+
+/// Providers mods can be fetched from.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ProviderArg {
+    Modrinth,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum FormatArg {
+    Xliff,
+    Po,
+    Csv,
+}
+
+impl From<FormatArg> for ExchangeFormat {
+    fn from(format: FormatArg) -> Self {
+        match format {
+            FormatArg::Xliff => ExchangeFormat::Xliff,
+            FormatArg::Po => ExchangeFormat::Po,
+            FormatArg::Csv => ExchangeFormat::Csv,
+        }
+    }
+}
+
+#[tokio::main]
+pub async fn run(command: Command, config: ServerConfig) -> anyhow::Result<()> {
+    let db = connect(&config).await?;
+
+    match command {
+        Command::Serve => unreachable!("The server is started by main"),
+        // Tables are created when connecting.
+        Command::Migrate => {
+            println!("Database is up to date");
+            Ok(())
+        }
+        Command::Archive {
+            provider,
+            identifier,
+        } => archive::archive_from_provider(&db, &config, provider, identifier).await,
+        Command::ArchiveJar {
+            jars,
+            identifier,
+            name,
+            description,
+            page_url,
+        } => archive::archive_local_jars(&db, jars, identifier, name, description, page_url).await,
+        Command::Import {
+            mod_id,
+            language,
+            file,
+            format,
+            translator,
+            submit,
+            dry_run,
+        } => {
+            let options = translation::ImportArgs {
+                submit,
+                dry_run,
+                format,
+            };
+            translation::import(&db, mod_id, &language, &file, &translator, options).await
+        }
+        Command::Export {
+            mod_id,
+            language,
+            format,
+            output,
+        } => translation::export(&db, mod_id, &language, format, output).await,
+        Command::Reindex => rebuild_indexes(&db).await,
+        Command::Tasks(TasksCommand::List) => archive::list_failures(&db).await,
+        Command::Tasks(TasksCommand::Retry { ids, all }) => {
+            archive::retry_failures(&db, &config, ids, all).await
+        }
+        Command::CreateAdmin {
+            username,
+            display_name,
+            password,
+        } => user::create_admin(&db, username, display_name, password).await,
+    }
+}
+
+async fn connect(config: &ServerConfig) -> anyhow::Result<DatabaseConnection> {
+    let db = Database::connect(&config.database_url)
+        .await
+        .with_context(|| format!("Failed to connect to database at {}", config.database_url))?;
+    initialize_database(&db)
+        .await
+        .context("Failed to initialize database")?;
+
+    Ok(db)
+}
+
+async fn rebuild_indexes(db: &DatabaseConnection) -> anyhow::Result<()> {
+    db.execute_unprepared("REINDEX").await?;
+    db.execute_unprepared("ANALYZE").await?;
+
+    println!("Rebuilt database indexes");
+    Ok(())
+}
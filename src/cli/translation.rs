@@ -0,0 +1,81 @@
+use std::{io::Write, path::Path, path::PathBuf};
+
+use anyhow::Context;
+use service::{
+    account::user::find_user_by_username,
+    exchange::{export_entries, import_entries, parse_entries, ExchangeFormat, ImportOptions},
+    sea_orm::DatabaseConnection,
+};
+
+use super::FormatArg;
+
+pub struct ImportArgs {
+    pub submit: bool,
+    pub dry_run: bool,
+    pub format: Option<FormatArg>,
+}
+
+/// Imports the translations and prints the report as JSON.
+pub async fn import(
+    db: &DatabaseConnection,
+    mod_id: i32,
+    language: &str,
+    file: &Path,
+    translator: &str,
+    args: ImportArgs,
+) -> anyhow::Result<()> {
+    let format = match args.format {
+        Some(format) => format.into(),
+        None => format_from_extension(file)?,
+    };
+    let translator = find_user_by_username(db, translator)
+        .await?
+        .with_context(|| format!("User not found: {}", translator))?;
+
+    let data = std::fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let entries = parse_entries(format, &data).context("Invalid import file")?;
+    let options = ImportOptions {
+        submit: args.submit,
+        dry_run: args.dry_run,
+    };
+    let report = import_entries(db, mod_id, language, translator.id, entries, options)
+        .await?
+        .with_context(|| format!("Mod not found: {}", mod_id))?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+pub async fn export(
+    db: &DatabaseConnection,
+    mod_id: i32,
+    language: &str,
+    format: FormatArg,
+    output: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let bytes = export_entries(db, mod_id, language, format.into())
+        .await?
+        .with_context(|| format!("Mod not found: {}", mod_id))?;
+
+    match output {
+        Some(path) => std::fs::write(&path, bytes)
+            .with_context(|| format!("Failed to write {}", path.display()))?,
+        None => std::io::stdout().write_all(&bytes)?,
+    }
+    Ok(())
+}
+
+fn format_from_extension(file: &Path) -> anyhow::Result<ExchangeFormat> {
+    let extension = file
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    match extension.as_str() {
+        "xlf" | "xliff" => Ok(ExchangeFormat::Xliff),
+        "po" => Ok(ExchangeFormat::Po),
+        "csv" => Ok(ExchangeFormat::Csv),
+        _ => anyhow::bail!("Unknown file format, use --format"),
+    }
+}
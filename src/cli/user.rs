@@ -0,0 +1,56 @@
+use std::io::BufRead;
+
+use entity::account::user_role::Role;
+use service::{
+    account::{
+        role::grant_role,
+        user::{create_user, find_user_by_username, is_valid_username, MIN_PASSWORD_LENGTH},
+    },
+    sea_orm::DatabaseConnection,
+};
+
+/// Grants the global admin role, creating the user first if it does not exist.
+pub async fn create_admin(
+    db: &DatabaseConnection,
+    username: String,
+    display_name: Option<String>,
+    password: Option<String>,
+) -> anyhow::Result<()> {
+    let user = match find_user_by_username(db, &username).await? {
+        Some(user) => user,
+        None => {
+            if !is_valid_username(&username) {
+                anyhow::bail!(
+                    "Username must be 3-32 characters of letters, digits, '_', '-' or '.'"
+                );
+            }
+            let password = match password {
+                Some(password) => password,
+                None => read_password()?,
+            };
+            if password.chars().count() < MIN_PASSWORD_LENGTH {
+                anyhow::bail!(
+                    "Password must be at least {} characters",
+                    MIN_PASSWORD_LENGTH
+                );
+            }
+
+            let display_name = display_name.unwrap_or_else(|| username.clone());
+            create_user(db, username, display_name, Some(&password)).await?
+        }
+    };
+
+    grant_role(db, user.id, Role::Admin, None).await?;
+    println!("{} (ID {}) is an administrator", user.username, user.id);
+    Ok(())
+}
+
+/// Reads the password from the first line of the standard input, so that it
+/// can be piped in without showing up in the process list.
+fn read_password() -> anyhow::Result<String> {
+    eprintln!("Password:");
+    let mut password = String::new();
+    std::io::stdin().lock().read_line(&mut password)?;
+
+    Ok(password.trim_end_matches(['\r', '\n']).to_string())
+}
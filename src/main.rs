@@ -1,9 +1,48 @@
+mod cli;
+
+use std::process::ExitCode;
+
+use api::config::ServerConfig;
+use clap::Parser;
 use log::error;
+use service::archive::task::set_archives_directory;
+
+use crate::cli::{Cli, Command};
 
-fn main() {
-    let result = api::start();
+fn main() -> ExitCode {
+    // Loaded first, so that it can also set the configuration file.
+    if let Err(err) = dotenvy::dotenv() {
+        // A missing file is fine, the environment may be set by other means.
+        if !err.not_found() {
+            eprintln!("Error: Failed to load .env file: {err}");
+            return ExitCode::FAILURE;
+        }
+    }
+    let cli = Cli::parse();
+
+    let config = match ServerConfig::load(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    set_archives_directory(config.archives_directory.clone());
 
-    if let Some(err) = result.err() {
-        error!("Backend Error: {err}");
+    match cli.command {
+        None | Some(Command::Serve) => {
+            if let Err(err) = api::start(config) {
+                error!("Backend Error: {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+        Some(command) => {
+            if let Err(err) = cli::run(command, config) {
+                eprintln!("Error: {err:#}");
+                return ExitCode::FAILURE;
+            }
+        }
     }
+
+    ExitCode::SUCCESS
 }